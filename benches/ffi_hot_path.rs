@@ -0,0 +1,76 @@
+// Benchmarks of the FFI calls made in the audio thread and of the latency of
+// callbacks invoked by Link. Run with: cargo bench
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rusty_link::{AblLink, SessionState};
+use std::{
+    hint::black_box,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+fn audio_round_trip(c: &mut Criterion) {
+    let link = AblLink::new(120.);
+    let mut session_state = SessionState::new();
+
+    c.bench_function("capture_audio + beat_at_time + commit_audio", |b| {
+        b.iter(|| {
+            link.capture_audio_session_state(&mut session_state);
+            let beat = session_state.beat_at_time(black_box(link.clock_micros()), 4.);
+            link.commit_audio_session_state(&session_state);
+            black_box(beat)
+        })
+    });
+
+    c.bench_function("audio_frame_info", |b| {
+        b.iter(|| link.audio_frame_info(black_box(link.clock_micros()), black_box(4.)))
+    });
+
+    c.bench_function("capture_audio_session_state", |b| {
+        b.iter(|| link.capture_audio_session_state(black_box(&mut session_state)))
+    });
+
+    c.bench_function("beat_at_time", |b| {
+        let time = link.clock_micros();
+        b.iter(|| session_state.beat_at_time(black_box(time), black_box(4.)))
+    });
+
+    c.bench_function("commit_audio_session_state", |b| {
+        b.iter(|| link.commit_audio_session_state(black_box(&session_state)))
+    });
+}
+
+fn callback_dispatch(c: &mut Criterion) {
+    let link = AblLink::new(120.);
+    let mut session_state = SessionState::new();
+
+    let (tx, rx) = mpsc::channel::<Instant>();
+    link.set_tempo_callback(move |_tempo| {
+        let _ = tx.send(Instant::now());
+    });
+
+    c.bench_function("tempo callback dispatch latency", |b| {
+        let mut tempo = 120.;
+        b.iter_custom(|iters| {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                // Alternate tempo, so every commit results in a callback
+                tempo = if tempo == 120. { 121. } else { 120. };
+
+                link.capture_app_session_state(&mut session_state);
+                session_state.set_tempo(tempo, link.clock_micros());
+
+                let start = Instant::now();
+                link.commit_app_session_state(&session_state);
+                let received = rx.recv().expect("Tempo callback not invoked.");
+                total += received.duration_since(start);
+            }
+            total
+        })
+    });
+
+    link.delete_tempo_callback();
+}
+
+criterion_group!(benches, audio_round_trip, callback_dispatch);
+criterion_main!(benches);
@@ -4,7 +4,33 @@ fn main() {
     // ---------
 
     // Read `CMakeLists.txt` from `cmake` directory, build and return '$OUT_DIR'
-    let out_dir = cmake::Config::new("cmake").build();
+    let mut config = cmake::Config::new("cmake");
+
+    // Build the C++ LinkHutSilent example for the golden compatibility tests
+    let golden_tests = std::env::var_os("CARGO_FEATURE_GOLDEN_TESTS").is_some();
+    if golden_tests {
+        config.define("RUSTY_LINK_BUILD_LINKHUT", "ON");
+    }
+
+    let out_dir = config.build();
+
+    if golden_tests {
+        println!(
+            "cargo:rustc-env=LINK_HUT_SILENT={}/bin/LinkHutSilent",
+            out_dir.display()
+        );
+    }
+
+    // Version of the bundled Link sources, reported by `AblLink::diagnostics`
+    if let Ok(output) = std::process::Command::new("git")
+        .args(["-C", "link", "describe", "--tags", "--always"])
+        .output()
+    {
+        let version = String::from_utf8_lossy(&output.stdout);
+        if output.status.success() && !version.trim().is_empty() {
+            println!("cargo:rustc-env=RUSTY_LINK_LINK_VERSION={}", version.trim());
+        }
+    }
 
     // Link standard C++ lib
     #[cfg(target_os = "macos")]
@@ -16,13 +42,20 @@ fn main() {
     println!("cargo:rustc-link-search=native={}/lib", out_dir.display());
     println!("cargo:rustc-link-lib=static=lib_abl_link");
 
+    // Bridge to the C++ `ableton::Link` class for the `full-cpp` feature
+    #[cfg(feature = "full-cpp")]
+    build_full_cpp();
+
     // -----------
     // - BINDGEN -
     // -----------
 
     let bindings = bindgen::builder()
         .header("link/extensions/abl_link/include/abl_link.h")
+        .header("cmake/rusty_link_shim.h")
+        .clang_arg("-Ilink/extensions/abl_link/include")
         .allowlist_function("abl_link_.*")
+        .allowlist_function("rusty_link_shim_.*")
         .generate()
         .expect("Failed to generate C bindings.");
 
@@ -30,3 +63,32 @@ fn main() {
         .write_to_file(out_dir.join("link_bindings.rs"))
         .expect("Failed to write bindings to `link_bindings.rs`.");
 }
+
+#[cfg(feature = "full-cpp")]
+fn build_full_cpp() {
+    let mut build = cxx_build::bridge("src/full_cpp.rs");
+    build
+        .file("cmake/rusty_link_full.cpp")
+        .include("link/include")
+        .include("link/modules/asio-standalone/asio/include")
+        .flag_if_supported("-std=c++14")
+        .flag_if_supported("/std:c++14");
+
+    // Same platform defines as 'AbletonLinkConfig.cmake'
+    #[cfg(target_os = "macos")]
+    build.define("LINK_PLATFORM_MACOSX", "1");
+    #[cfg(target_os = "linux")]
+    build.define("LINK_PLATFORM_LINUX", "1");
+    #[cfg(target_os = "windows")]
+    build
+        .define("LINK_PLATFORM_WINDOWS", "1")
+        .define("_WIN32_WINNT", "0x0601");
+    #[cfg(unix)]
+    build.define("LINK_PLATFORM_UNIX", "1");
+
+    build.compile("rusty_link_full");
+
+    println!("cargo:rerun-if-changed=src/full_cpp.rs");
+    println!("cargo:rerun-if-changed=cmake/rusty_link_full.h");
+    println!("cargo:rerun-if-changed=cmake/rusty_link_full.cpp");
+}
@@ -0,0 +1,191 @@
+#include "rusty_link/cmake/rusty_link_full.h"
+#include "rusty_link/src/full_cpp.rs.h"
+
+#include <chrono>
+
+namespace rusty_link
+{
+
+namespace
+{
+
+std::chrono::microseconds micros(const int64_t time)
+{
+  return std::chrono::microseconds{time};
+}
+
+// Clock source of the FullLink being constructed on this thread
+thread_local std::shared_ptr<rust::Box<BoxedClockSource>> tPendingSource;
+
+} // namespace
+
+FullClock::FullClock()
+  : mSource(tPendingSource)
+{
+}
+
+std::chrono::microseconds FullClock::micros() const
+{
+  if (mSource)
+  {
+    return std::chrono::microseconds{boxed_clock_source_micros(**mSource)};
+  }
+  return mPlatformClock.micros();
+}
+
+FullLink::FullLink(const double bpm)
+  : link(bpm)
+{
+}
+
+std::unique_ptr<FullLink> new_full_link(const double bpm)
+{
+  return std::make_unique<FullLink>(bpm);
+}
+
+std::unique_ptr<FullLink> new_full_link_with_clock(
+  const double bpm, rust::Box<BoxedClockSource> source)
+{
+  tPendingSource = std::make_shared<rust::Box<BoxedClockSource>>(std::move(source));
+  auto link = std::make_unique<FullLink>(bpm);
+  tPendingSource.reset();
+  return link;
+}
+
+void full_link_enable(const FullLink& link, const bool enable)
+{
+  link.link.enable(enable);
+}
+
+bool full_link_is_enabled(const FullLink& link)
+{
+  return link.link.isEnabled();
+}
+
+std::size_t full_link_num_peers(const FullLink& link)
+{
+  return link.link.numPeers();
+}
+
+void full_link_enable_start_stop_sync(const FullLink& link, const bool enable)
+{
+  link.link.enableStartStopSync(enable);
+}
+
+bool full_link_is_start_stop_sync_enabled(const FullLink& link)
+{
+  return link.link.isStartStopSyncEnabled();
+}
+
+int64_t full_link_clock_micros(const FullLink& link)
+{
+  return link.link.clock().micros().count();
+}
+
+std::unique_ptr<SessionState> full_link_capture_audio_session_state(const FullLink& link)
+{
+  return std::make_unique<SessionState>(link.link.captureAudioSessionState());
+}
+
+void full_link_capture_audio_session_state_into(const FullLink& link, SessionState& out)
+{
+  out = link.link.captureAudioSessionState();
+}
+
+void full_link_commit_audio_session_state(const FullLink& link, const SessionState& state)
+{
+  link.link.commitAudioSessionState(state);
+}
+
+std::unique_ptr<SessionState> full_link_capture_app_session_state(const FullLink& link)
+{
+  return std::make_unique<SessionState>(link.link.captureAppSessionState());
+}
+
+void full_link_capture_app_session_state_into(const FullLink& link, SessionState& out)
+{
+  out = link.link.captureAppSessionState();
+}
+
+void full_link_commit_app_session_state(const FullLink& link, const SessionState& state)
+{
+  link.link.commitAppSessionState(state);
+}
+
+std::unique_ptr<SessionState> session_state_clone(const SessionState& state)
+{
+  return std::make_unique<SessionState>(state);
+}
+
+double session_state_tempo(const SessionState& state)
+{
+  return state.tempo();
+}
+
+void session_state_set_tempo(SessionState& state, const double bpm, const int64_t at_time)
+{
+  state.setTempo(bpm, micros(at_time));
+}
+
+double session_state_beat_at_time(
+  const SessionState& state, const int64_t time, const double quantum)
+{
+  return state.beatAtTime(micros(time), quantum);
+}
+
+double session_state_phase_at_time(
+  const SessionState& state, const int64_t time, const double quantum)
+{
+  return state.phaseAtTime(micros(time), quantum);
+}
+
+int64_t session_state_time_at_beat(
+  const SessionState& state, const double beat, const double quantum)
+{
+  return state.timeAtBeat(beat, quantum).count();
+}
+
+void session_state_request_beat_at_time(
+  SessionState& state, const double beat, const int64_t time, const double quantum)
+{
+  state.requestBeatAtTime(beat, micros(time), quantum);
+}
+
+void session_state_force_beat_at_time(
+  SessionState& state, const double beat, const int64_t time, const double quantum)
+{
+  state.forceBeatAtTime(beat, micros(time), quantum);
+}
+
+void session_state_set_is_playing(
+  SessionState& state, const bool is_playing, const int64_t time)
+{
+  state.setIsPlaying(is_playing, micros(time));
+}
+
+bool session_state_is_playing(const SessionState& state)
+{
+  return state.isPlaying();
+}
+
+int64_t session_state_time_for_is_playing(const SessionState& state)
+{
+  return state.timeForIsPlaying().count();
+}
+
+void session_state_request_beat_at_start_playing_time(
+  SessionState& state, const double beat, const double quantum)
+{
+  state.requestBeatAtStartPlayingTime(beat, quantum);
+}
+
+void session_state_set_is_playing_and_request_beat_at_time(SessionState& state,
+  const bool is_playing,
+  const int64_t time,
+  const double beat,
+  const double quantum)
+{
+  state.setIsPlayingAndRequestBeatAtTime(is_playing, micros(time), beat, quantum);
+}
+
+} // namespace rusty_link
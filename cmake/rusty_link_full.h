@@ -0,0 +1,86 @@
+/* C++ side of the `full-cpp` feature of rusty_link, see 'src/full_cpp.rs'. */
+
+#pragma once
+
+#include "rust/cxx.h"
+
+#include <ableton/Link.hpp>
+
+#include <chrono>
+#include <cstddef>
+#include <cstdint>
+#include <memory>
+
+namespace rusty_link
+{
+
+// Defined in Rust, wraps a `ClockSource` trait object
+struct BoxedClockSource;
+
+/*! @brief Clock of a FullLink: the platform clock of Link or a clock implemented in Rust.
+ *
+ *  @discussion BasicLink default-constructs its clock and copies it afterwards. A
+ *  default-constructed FullClock uses the source passed to new_full_link_with_clock
+ *  on the constructing thread, if any.
+ */
+class FullClock
+{
+public:
+  FullClock();
+
+  std::chrono::microseconds micros() const;
+
+private:
+  ableton::link::platform::Clock mPlatformClock;
+  // Link copies its clock, rust::Box can only be moved
+  std::shared_ptr<rust::Box<BoxedClockSource>> mSource;
+};
+
+using SessionState = ableton::BasicLink<FullClock>::SessionState;
+
+/*! @brief A Link instance, used directly instead of through abl_link. */
+struct FullLink
+{
+  explicit FullLink(double bpm);
+
+  // Link is thread-safe, so it can be used through shared references from Rust
+  mutable ableton::BasicLink<FullClock> link;
+};
+
+std::unique_ptr<FullLink> new_full_link(double bpm);
+std::unique_ptr<FullLink> new_full_link_with_clock(
+  double bpm, rust::Box<BoxedClockSource> source);
+
+void full_link_enable(const FullLink& link, bool enable);
+bool full_link_is_enabled(const FullLink& link);
+std::size_t full_link_num_peers(const FullLink& link);
+void full_link_enable_start_stop_sync(const FullLink& link, bool enable);
+bool full_link_is_start_stop_sync_enabled(const FullLink& link);
+int64_t full_link_clock_micros(const FullLink& link);
+
+std::unique_ptr<SessionState> full_link_capture_audio_session_state(const FullLink& link);
+void full_link_capture_audio_session_state_into(const FullLink& link, SessionState& out);
+void full_link_commit_audio_session_state(const FullLink& link, const SessionState& state);
+std::unique_ptr<SessionState> full_link_capture_app_session_state(const FullLink& link);
+void full_link_capture_app_session_state_into(const FullLink& link, SessionState& out);
+void full_link_commit_app_session_state(const FullLink& link, const SessionState& state);
+
+std::unique_ptr<SessionState> session_state_clone(const SessionState& state);
+double session_state_tempo(const SessionState& state);
+void session_state_set_tempo(SessionState& state, double bpm, int64_t at_time);
+double session_state_beat_at_time(const SessionState& state, int64_t time, double quantum);
+double session_state_phase_at_time(const SessionState& state, int64_t time, double quantum);
+int64_t session_state_time_at_beat(const SessionState& state, double beat, double quantum);
+void session_state_request_beat_at_time(
+  SessionState& state, double beat, int64_t time, double quantum);
+void session_state_force_beat_at_time(
+  SessionState& state, double beat, int64_t time, double quantum);
+void session_state_set_is_playing(SessionState& state, bool is_playing, int64_t time);
+bool session_state_is_playing(const SessionState& state);
+int64_t session_state_time_for_is_playing(const SessionState& state);
+void session_state_request_beat_at_start_playing_time(
+  SessionState& state, double beat, double quantum);
+void session_state_set_is_playing_and_request_beat_at_time(
+  SessionState& state, bool is_playing, int64_t time, double beat, double quantum);
+
+} // namespace rusty_link
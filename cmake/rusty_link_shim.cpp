@@ -0,0 +1,39 @@
+#include "rusty_link_shim.h"
+
+#include <ableton/Link.hpp>
+
+#include <chrono>
+
+extern "C"
+{
+  rusty_link_frame_info rusty_link_shim_audio_frame_info(
+    abl_link link, int64_t time, double quantum)
+  {
+    auto *pLink = reinterpret_cast<ableton::Link *>(link.impl);
+    const auto sessionState = pLink->captureAudioSessionState();
+    const auto t = std::chrono::microseconds{time};
+
+    return rusty_link_frame_info{
+      sessionState.tempo(),
+      sessionState.beatAtTime(t, quantum),
+      sessionState.phaseAtTime(t, quantum),
+      sessionState.isPlaying(),
+      pLink->clock().micros().count(),
+    };
+  }
+
+  void rusty_link_shim_copy_session_state(
+    abl_link_session_state dst, abl_link_session_state src)
+  {
+    *reinterpret_cast<ableton::Link::SessionState *>(dst.impl) =
+      *reinterpret_cast<ableton::Link::SessionState *>(src.impl);
+  }
+
+  void rusty_link_shim_delete_callbacks(abl_link link)
+  {
+    auto *const impl = reinterpret_cast<ableton::Link *>(link.impl);
+    impl->setNumPeersCallback([](std::size_t) {});
+    impl->setTempoCallback([](double) {});
+    impl->setStartStopCallback([](bool) {});
+  }
+}
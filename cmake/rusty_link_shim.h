@@ -0,0 +1,62 @@
+/* Additions to the abl_link C API used by rusty_link. */
+
+#pragma once
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#include "abl_link.h"
+
+#ifdef __cplusplus
+extern "C"
+{
+#endif
+
+  /*! @brief The values of the audio Session State most audio callbacks need per buffer.
+   */
+  typedef struct rusty_link_frame_info
+  {
+    double tempo;
+    double beat;
+    double phase;
+    bool is_playing;
+    /* Link clock time of the capture in microseconds */
+    int64_t capture_time;
+  } rusty_link_frame_info;
+
+  /*! @brief Capture the audio Session State and read tempo, beat, phase and transport
+   *  state at the given time in a single call.
+   *  Thread-safe: no
+   *  Realtime-safe: yes
+   *
+   *  @discussion Same as abl_link_capture_audio_session_state followed by
+   *  abl_link_tempo, abl_link_beat_at_time, abl_link_phase_at_time and
+   *  abl_link_is_playing, plus abl_link_clock_micros for the time of the capture.
+   *  This function should ONLY be called in the audio thread.
+   */
+  rusty_link_frame_info rusty_link_shim_audio_frame_info(
+    abl_link link, int64_t time, double quantum);
+
+  /*! @brief Copy the timeline and start/stop state of one Session State into another.
+   *  Thread-safe: no
+   *  Realtime-safe: yes
+   *
+   *  @discussion Neither Session State may be used by another thread during the copy.
+   */
+  void rusty_link_shim_copy_session_state(
+    abl_link_session_state dst, abl_link_session_state src);
+
+  /*! @brief Deregister the num_peers, tempo and start/stop callbacks.
+   *  Thread-safe: yes
+   *  Realtime-safe: no
+   *
+   *  @discussion The abl_link C API has no functions to delete callbacks. Unlike
+   *  registering empty C functions, this replaces the callbacks with functions which
+   *  capture no context. Link serializes replacing and invoking callbacks, so once this
+   *  function returns, no callback references the previous context anymore.
+   */
+  void rusty_link_shim_delete_callbacks(abl_link link);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,106 @@
+// Measures how long a tempo change committed on one instance takes to reach the tempo
+// callback of another instance, and prints the distribution of the latencies.
+// Both instances run in this process, so the measurement covers the binding, Link's
+// discovery messages and the local network stack.
+// Usage: cargo run --release --example callback_latency [rounds]
+
+use rusty_link::{AblLink, SessionState};
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Pause between two rounds, so every measurement starts from a settled session
+const ROUND_INTERVAL: Duration = Duration::from_millis(50);
+
+fn main() {
+    let rounds: usize = std::env::args().nth(1).map_or(200, |arg| {
+        arg.parse()
+            .expect("Number of rounds has to be a whole number.")
+    });
+
+    let link_a = AblLink::new(120.);
+    let link_b = AblLink::new(120.);
+
+    let (peers_tx, peers_rx) = mpsc::channel();
+    link_b.set_num_peers_callback(move |num_peers| {
+        let _ = peers_tx.send(num_peers);
+    });
+
+    // Timestamp as early as possible, on the Link-managed thread
+    let (tempo_tx, tempo_rx) = mpsc::channel();
+    link_b.set_tempo_callback(move |bpm| {
+        let _ = tempo_tx.send((bpm, Instant::now()));
+    });
+
+    link_a.enable(true);
+    link_b.enable(true);
+
+    loop {
+        match peers_rx.recv_timeout(TIMEOUT) {
+            Ok(num_peers) if num_peers > 0 => break,
+            Ok(_) => continue,
+            Err(_) => {
+                eprintln!("The instances did not discover each other. Is a network interface up?");
+                return;
+            }
+        }
+    }
+    link_b.delete_num_peers_callback();
+
+    println!("Measuring {} tempo changes...", rounds);
+    let mut session_state = SessionState::new();
+    let mut latencies = Vec::with_capacity(rounds);
+    let mut lost = 0;
+
+    for round in 0..rounds {
+        thread::sleep(ROUND_INTERVAL);
+        // Drop reports of the previous round, e.g. late duplicates
+        while tempo_rx.try_recv().is_ok() {}
+
+        let bpm = if round % 2 == 0 { 121. } else { 119. };
+        link_a.capture_app_session_state(&mut session_state);
+        session_state.set_tempo(bpm, link_a.clock_micros());
+        let committed = Instant::now();
+        link_a.commit_app_session_state(&session_state);
+
+        let deadline = committed + TIMEOUT;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match tempo_rx.recv_timeout(remaining) {
+                Ok((seen, at)) if (seen - bpm).abs() < 0.01 => {
+                    latencies.push(at - committed);
+                    break;
+                }
+                Ok(_) => continue,
+                Err(_) => {
+                    lost += 1;
+                    break;
+                }
+            }
+        }
+    }
+
+    link_b.delete_tempo_callback();
+    link_a.enable(false);
+    link_b.enable(false);
+
+    if latencies.is_empty() {
+        eprintln!("B did not observe any tempo change.");
+        return;
+    }
+
+    latencies.sort();
+    let percentile = |p: f64| latencies[((latencies.len() - 1) as f64 * p).round() as usize];
+    let mean = latencies.iter().sum::<Duration>() / latencies.len() as u32;
+
+    println!("observed: {} | lost: {}", latencies.len(), lost);
+    println!("min:    {:>10.3?}", latencies[0]);
+    println!("mean:   {:>10.3?}", mean);
+    println!("median: {:>10.3?}", percentile(0.5));
+    println!("p95:    {:>10.3?}", percentile(0.95));
+    println!("p99:    {:>10.3?}", percentile(0.99));
+    println!("max:    {:>10.3?}", latencies[latencies.len() - 1]);
+}
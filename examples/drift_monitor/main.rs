@@ -0,0 +1,46 @@
+// Soak test measuring the drift and jitter of the Link timeline over a long time.
+// Usage: cargo run --release --example drift_monitor [minutes]
+
+use rusty_link::{AblLink, DriftMonitor};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+fn main() {
+    let minutes: Option<u64> = std::env::args().nth(1).map(|arg| {
+        arg.parse()
+            .expect("Duration has to be a whole number of minutes.")
+    });
+
+    let link = AblLink::new(120.);
+    link.enable(true);
+
+    let mut monitor = DriftMonitor::new(4.);
+    let start = Instant::now();
+    let mut last_report = Instant::now();
+
+    println!("Measuring drift. Stop with Ctrl+C.");
+
+    loop {
+        monitor.sample(&link);
+        thread::sleep(SAMPLE_INTERVAL);
+
+        if last_report.elapsed() >= REPORT_INTERVAL {
+            println!("peers: {} | {}", link.num_peers(), monitor.report());
+            last_report = Instant::now();
+        }
+
+        if let Some(minutes) = minutes {
+            if start.elapsed() >= Duration::from_secs(minutes * 60) {
+                break;
+            }
+        }
+    }
+
+    println!("\nFinal report:\n{}", monitor.report());
+    link.enable(false);
+}
@@ -0,0 +1,132 @@
+// Headless command line tool to inspect and control a Link session.
+// Usage: cargo run --example link_cli -- <command>
+
+use rusty_link::{format_position, AblLink, SessionState, Transport};
+use std::{
+    env, process, thread,
+    time::{Duration, Instant},
+};
+
+const USAGE: &str = "usage: link_cli <command>
+
+commands:
+  status              print the state of the Link session
+  tempo get           print the session tempo
+  tempo set <bpm>     change the session tempo
+  start               start transport (with start stop sync)
+  stop                stop transport (with start stop sync)
+  watch [--json]      print session events as lines or JSON until killed";
+
+/// Time to wait for peers, before reading or changing the session
+const DISCOVERY_TIME: Duration = Duration::from_secs(1);
+const QUANTUM: f64 = 4.;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let link = AblLink::new(120.);
+    link.enable_start_stop_sync(true);
+    link.enable(true);
+
+    match args.as_slice() {
+        ["status"] => status(&link),
+        ["tempo", "get"] => {
+            join_session(&link);
+            println!("{:.2}", capture(&link).tempo());
+        }
+        ["tempo", "set", bpm] => {
+            let bpm: f64 = bpm.parse().unwrap_or_else(|_| exit_with_usage());
+            join_session(&link);
+            let mut session_state = capture(&link);
+            session_state.set_tempo(bpm, link.clock_micros());
+            commit(&link, &session_state);
+        }
+        ["start"] => set_playing(&link, true),
+        ["stop"] => set_playing(&link, false),
+        ["watch"] => watch(&link, false),
+        ["watch", "--json"] => watch(&link, true),
+        _ => exit_with_usage(),
+    }
+
+    link.enable(false);
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+/// Wait until at least one peer joined or the discovery time elapsed.
+fn join_session(link: &AblLink) {
+    let start = Instant::now();
+    while link.num_peers() == 0 && start.elapsed() < DISCOVERY_TIME {
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn capture(link: &AblLink) -> SessionState {
+    let mut session_state = SessionState::new();
+    link.capture_app_session_state(&mut session_state);
+    session_state
+}
+
+/// Commit and give Link some time to send the change to the peers before exiting.
+fn commit(link: &AblLink, session_state: &SessionState) {
+    link.commit_app_session_state(session_state);
+    thread::sleep(Duration::from_millis(100));
+}
+
+fn status(link: &AblLink) {
+    join_session(link);
+    let session_state = capture(link);
+    let time = link.clock_micros();
+
+    println!("peers:           {}", link.num_peers());
+    println!("tempo:           {:.2}", session_state.tempo());
+    let beat = session_state.beat_at_time(time, QUANTUM);
+    println!("beat:            {:.2}", beat);
+    println!("position:        {}", format_position(beat, QUANTUM, 960));
+    println!(
+        "phase:           {:.2}",
+        session_state.phase_at_time(time, QUANTUM)
+    );
+    println!(
+        "transport:       {}",
+        Transport::from_session_state(&session_state, time, QUANTUM)
+    );
+}
+
+fn set_playing(link: &AblLink, is_playing: bool) {
+    join_session(link);
+    let mut session_state = capture(link);
+    let time = link.clock_micros() as u64;
+    if is_playing {
+        session_state.set_is_playing_and_request_beat_at_time(true, time, 0., QUANTUM);
+    } else {
+        session_state.set_is_playing(false, time);
+    }
+    commit(link, &session_state);
+}
+
+fn watch(link: &AblLink, json: bool) {
+    fn print_event(json: bool, event: &str, key: &str, value: String) {
+        if json {
+            println!("{{\"event\":\"{}\",\"{}\":{}}}", event, key, value);
+        } else {
+            println!("{}: {}", event, value);
+        }
+    }
+
+    link.set_num_peers_callback(move |num_peers| {
+        print_event(json, "peers", "num_peers", num_peers.to_string())
+    });
+    link.set_tempo_callback(move |bpm| print_event(json, "tempo", "bpm", format!("{:.2}", bpm)));
+    link.set_start_stop_callback(move |is_playing| {
+        print_event(json, "start_stop", "is_playing", is_playing.to_string())
+    });
+
+    loop {
+        thread::park();
+    }
+}
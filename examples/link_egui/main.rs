@@ -0,0 +1,148 @@
+// Example of using Link in a GUI with egui: tempo display and slider, transport
+// buttons and a phase bar. Usage: cargo run --example link_egui
+
+use eframe::egui;
+use rusty_link::{AblLink, DisplayTempo, PhaseProvider, SessionState, TempoCommitLimiter};
+use std::{
+    sync::{mpsc, Arc},
+    time::Duration,
+};
+
+/// Frame period at 60 fps
+const FRAME_TIME: Duration = Duration::from_millis(16);
+
+fn main() -> eframe::Result {
+    eframe::run_native(
+        "Link egui",
+        eframe::NativeOptions::default(),
+        Box::new(|cc| Ok(Box::new(LinkApp::new(&cc.egui_ctx)))),
+    )
+}
+
+struct LinkApp {
+    link: Arc<AblLink>,
+    session_state: SessionState,
+    /// Beat and phase for every frame, without capturing a Session State per frame
+    phase: PhaseProvider,
+    /// Dragging the tempo slider commits at most 10 tempi per second
+    tempo_limiter: TempoCommitLimiter,
+    quantum: f64,
+    /// Tempo updates sent by the tempo callback from a Link-managed thread
+    tempo_updates: mpsc::Receiver<f64>,
+    tempo: f64,
+    /// Rounded tempo for the heading, without flickering while Link refines the tempo
+    display_tempo: DisplayTempo,
+}
+
+impl LinkApp {
+    fn new(ctx: &egui::Context) -> Self {
+        let link = Arc::new(AblLink::new(120.));
+        link.enable(true);
+
+        // The GUI thread must not block on Link. Tempo changes are sent over a channel
+        // and egui is asked to repaint, so the display updates without polling.
+        let (tx, tempo_updates) = mpsc::channel();
+        let repaint_ctx = ctx.clone();
+        link.set_tempo_callback(move |bpm| {
+            let _ = tx.send(bpm);
+            repaint_ctx.request_repaint();
+        });
+
+        let mut session_state = SessionState::new();
+        link.capture_app_session_state(&mut session_state);
+        let tempo = session_state.tempo();
+
+        Self {
+            phase: PhaseProvider::new(Arc::clone(&link)),
+            tempo_limiter: TempoCommitLimiter::new(Arc::clone(&link), 10.),
+            link,
+            session_state,
+            quantum: 4.,
+            tempo_updates,
+            tempo,
+            display_tempo: DisplayTempo::new(2),
+        }
+    }
+
+    fn set_playing(&mut self, is_playing: bool) {
+        self.link.capture_app_session_state(&mut self.session_state);
+        let time = self.link.clock_micros() as u64;
+        if is_playing {
+            self.session_state.set_is_playing_and_request_beat_at_time(
+                true,
+                time,
+                0.,
+                self.quantum,
+            );
+        } else {
+            self.session_state.set_is_playing(false, time);
+        }
+        self.link.commit_app_session_state(&self.session_state);
+    }
+}
+
+impl eframe::App for LinkApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Only the latest tempo is of interest
+        if let Some(bpm) = self.tempo_updates.try_iter().last() {
+            self.tempo = bpm;
+        }
+
+        // Show the phase at the time the frame reaches the screen, about one frame later
+        let phase = self
+            .phase
+            .phase_at_predicted_display_time(FRAME_TIME, self.quantum);
+        let is_playing = self.phase.is_playing();
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let mut enabled = self.link.is_enabled();
+            if ui.checkbox(&mut enabled, "Link enabled").changed() {
+                self.link.enable(enabled);
+            }
+            ui.label(format!("Peers: {}", self.link.num_peers()));
+            self.display_tempo.update(self.tempo);
+            ui.heading(format!("{} BPM", self.display_tempo));
+
+            let mut tempo = self.tempo;
+            let slider = egui::Slider::new(&mut tempo, 20.0..=999.0).text("Tempo");
+            if ui.add(slider).changed() {
+                self.tempo_limiter.set_tempo(tempo);
+                self.tempo = tempo;
+            }
+            self.tempo_limiter.poll();
+
+            ui.add(egui::Slider::new(&mut self.quantum, 1.0..=16.0).text("Quantum"));
+
+            ui.horizontal(|ui| {
+                if ui.button("Play").clicked() {
+                    self.set_playing(true);
+                }
+                if ui.button("Stop").clicked() {
+                    self.set_playing(false);
+                }
+                ui.label(match is_playing {
+                    true => "[playing]",
+                    false => "[stopped]",
+                });
+            });
+
+            ui.add(
+                egui::ProgressBar::new((phase / self.quantum) as f32).text(format!(
+                    "Beat {} / {}",
+                    phase.floor() as u64 + 1,
+                    self.quantum as u64
+                )),
+            );
+        });
+
+        // Keep the phase bar moving
+        ctx.request_repaint_after(FRAME_TIME);
+    }
+}
+
+impl Drop for LinkApp {
+    fn drop(&mut self) {
+        self.link.delete_tempo_callback();
+        self.link.enable(false);
+    }
+}
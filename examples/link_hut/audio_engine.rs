@@ -149,7 +149,7 @@ impl AudioEngine {
         };
 
         // Build audio stream and start playback
-        let stream = audio_cpal.build_stream::<f32>(engine_callback);
+        let stream = audio_cpal.build_stream(engine_callback);
 
         Self {
             stream: Some(stream),
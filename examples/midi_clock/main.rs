@@ -0,0 +1,80 @@
+// Sends MIDI clock synchronized to the Link session to a MIDI output port.
+// Usage: cargo run --release --example midi_clock --features midir
+
+use midir::{MidiOutput, MidiOutputPort};
+use rusty_link::{AblLink, MidiClockOut, SessionState};
+use std::{
+    io::{self, BufRead, Write},
+    sync::Arc,
+};
+
+const QUANTUM: f64 = 4.;
+
+fn main() {
+    let midi_out = MidiOutput::new("rusty_link MIDI clock").expect("Could not open MIDI output.");
+    let port = select_port(&midi_out);
+    let port_name = midi_out.port_name(&port).unwrap_or_default();
+    let connection = midi_out
+        .connect(&port, "rusty_link")
+        .expect("Could not connect to MIDI port.");
+
+    let link = Arc::new(AblLink::new(120.));
+    link.enable_start_stop_sync(true);
+    link.enable(true);
+
+    let clock = MidiClockOut::start(Arc::clone(&link), connection, QUANTUM);
+
+    println!("\nSending MIDI clock to '{}'.", port_name);
+    println!("usage:");
+    println!("  start / stop: s + enter");
+    println!("  quit: q + enter");
+
+    for line in io::stdin().lock().lines() {
+        match line.expect("Input read error").trim() {
+            "s" => toggle_playing(&link),
+            "q" => break,
+            _ => {}
+        }
+    }
+
+    clock.stop();
+    link.enable(false);
+}
+
+/// List all MIDI output ports and let the user pick one.
+fn select_port(midi_out: &MidiOutput) -> MidiOutputPort {
+    let ports = midi_out.ports();
+    if ports.is_empty() {
+        eprintln!("No MIDI output ports available.");
+        std::process::exit(1);
+    }
+
+    println!("Available MIDI output ports:");
+    for (i, port) in ports.iter().enumerate() {
+        println!("  {}: {}", i, midi_out.port_name(port).unwrap_or_default());
+    }
+
+    loop {
+        print!("Select port: ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).expect("Input read error");
+        match input.trim().parse::<usize>() {
+            Ok(i) if i < ports.len() => return ports[i].clone(),
+            _ => println!("Invalid port number."),
+        }
+    }
+}
+
+fn toggle_playing(link: &AblLink) {
+    let mut session_state = SessionState::new();
+    link.capture_app_session_state(&mut session_state);
+    let time = link.clock_micros() as u64;
+    if session_state.is_playing() {
+        session_state.set_is_playing(false, time);
+    } else {
+        session_state.set_is_playing_and_request_beat_at_time(true, time, 0., QUANTUM);
+    }
+    link.commit_app_session_state(&session_state);
+}
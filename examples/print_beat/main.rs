@@ -0,0 +1,29 @@
+// Minimal example: enable Link and print tempo, beat and phase every 100ms.
+// Usage: cargo run --example print_beat
+
+use rusty_link::{AblLink, SessionState};
+use std::{thread, time::Duration};
+
+fn main() {
+    let quantum = 4.;
+
+    let link = AblLink::new(120.);
+    link.enable(true);
+
+    let mut session_state = SessionState::new();
+
+    loop {
+        link.capture_app_session_state(&mut session_state);
+        let time = link.clock_micros();
+
+        println!(
+            "peers: {} | tempo: {:.2} | beat: {:.2} | phase: {:.2}",
+            link.num_peers(),
+            session_state.tempo(),
+            session_state.beat_at_time(time, quantum),
+            session_state.phase_at_time(time, quantum),
+        );
+
+        thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,77 @@
+// Quantized launch: arm a "clip" and start it exactly on the next bar of the Link
+// session, then print how many milliseconds early or late the launch landed.
+// Usage: cargo run --release --example quantized_launch
+
+use rusty_link::{AblLink, SessionState};
+use std::{
+    io::{self, BufRead},
+    thread,
+    time::Duration,
+};
+
+const QUANTUM: f64 = 4.;
+
+fn main() {
+    let link = AblLink::new(120.);
+    link.enable_start_stop_sync(true);
+    link.enable(true);
+
+    println!("usage:");
+    println!("  arm clip: enter");
+    println!("  quit: q + enter");
+
+    for line in io::stdin().lock().lines() {
+        if line.expect("Input read error").trim() == "q" {
+            break;
+        }
+        launch_clip(&link);
+    }
+
+    link.enable(false);
+}
+
+fn launch_clip(link: &AblLink) {
+    let mut session_state = SessionState::new();
+    link.capture_app_session_state(&mut session_state);
+    let now = link.clock_micros();
+
+    // Start transport if necessary. Without peers it starts right away at beat 0,
+    // otherwise Link maps beat 0 to the next time it is in phase with the session.
+    if !session_state.is_playing() {
+        session_state.set_is_playing_and_request_beat_at_time(true, now as u64, 0., QUANTUM);
+        link.commit_app_session_state(&session_state);
+    }
+
+    // The clip launches on the next bar, which is beat 0 for a freshly started transport
+    let beat = session_state.beat_at_time(now, QUANTUM);
+    let launch_beat = (beat / QUANTUM).ceil().max(0.) * QUANTUM;
+    let launch_time = session_state.time_at_beat(launch_beat, QUANTUM);
+    println!(
+        "Armed at beat {:.2}, launching at beat {} in {:.0} ms",
+        beat,
+        launch_beat,
+        (launch_time - now) as f64 / 1000.
+    );
+
+    // Sleep until shortly before the launch, then spin for accuracy
+    let wait = launch_time - link.clock_micros() - 2000;
+    if wait > 0 {
+        thread::sleep(Duration::from_micros(wait as u64));
+    }
+    while link.clock_micros() < launch_time {
+        std::hint::spin_loop();
+    }
+    let launched_at = link.clock_micros();
+
+    // Compare the actual launch with the session grid at this moment
+    link.capture_app_session_state(&mut session_state);
+    let launched_beat = session_state.beat_at_time(launched_at, QUANTUM);
+    let error_ms = (launched_beat - launch_beat) * 60_000. / session_state.tempo();
+
+    println!(
+        "Launched at beat {:.4}, {:.3} ms {}",
+        launched_beat,
+        error_ms.abs(),
+        if error_ms < 0. { "early" } else { "late" }
+    );
+}
@@ -0,0 +1,66 @@
+// Two AblLink instances in one process discover each other like any other peers.
+// Changing the tempo on one instance is followed by the other one.
+// Usage: cargo run --example two_instances
+
+use rusty_link::{AblLink, SessionState};
+use std::{sync::mpsc, time::Duration};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn main() {
+    let link_a = AblLink::new(120.);
+    let link_b = AblLink::new(90.);
+
+    let (peers_tx, peers_rx) = mpsc::channel();
+    link_b.set_num_peers_callback(move |num_peers| {
+        println!("B: number of peers changed to {}", num_peers);
+        let _ = peers_tx.send(num_peers);
+    });
+
+    let (tempo_tx, tempo_rx) = mpsc::channel();
+    link_b.set_tempo_callback(move |bpm| {
+        println!("B: tempo changed to {:.2}", bpm);
+        let _ = tempo_tx.send(bpm);
+    });
+
+    println!("Enabling both instances...");
+    link_a.enable(true);
+    link_b.enable(true);
+
+    // Wait for B to discover A
+    loop {
+        match peers_rx.recv_timeout(TIMEOUT) {
+            Ok(num_peers) if num_peers > 0 => break,
+            Ok(_) => continue,
+            Err(_) => {
+                eprintln!("The instances did not discover each other. Is a network interface up?");
+                return;
+            }
+        }
+    }
+
+    println!("A: setting tempo to 140");
+    let mut session_state = SessionState::new();
+    link_a.capture_app_session_state(&mut session_state);
+    session_state.set_tempo(140., link_a.clock_micros());
+    link_a.commit_app_session_state(&session_state);
+
+    // Joining the session may already have changed B's tempo, wait for the new one
+    loop {
+        match tempo_rx.recv_timeout(TIMEOUT) {
+            Ok(bpm) if (bpm - 140.).abs() < 0.01 => break,
+            Ok(_) => continue,
+            Err(_) => {
+                eprintln!("B did not follow the tempo change.");
+                return;
+            }
+        }
+    }
+
+    println!("B follows A. Done!");
+
+    link_b.delete_num_peers_callback();
+    link_b.delete_tempo_callback();
+    link_a.enable(false);
+    link_b.enable(false);
+}
@@ -0,0 +1,131 @@
+// Web dashboard for a Link session built with axum. Shows live tempo, peers and
+// phase over a WebSocket and allows changing the tempo from the browser.
+// Usage: cargo run --example web_dashboard, then open http://127.0.0.1:3000
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::StatusCode,
+    response::{Html, Response},
+    routing::{get, post},
+    Router,
+};
+use rusty_link::{AblLink, SessionState};
+use std::{sync::Arc, time::Duration};
+
+const ADDRESS: &str = "127.0.0.1:3000";
+const QUANTUM: f64 = 4.;
+const UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+
+#[tokio::main]
+async fn main() {
+    let link = Arc::new(AblLink::new(120.));
+    link.enable(true);
+
+    let app = Router::new()
+        .route("/", get(index))
+        .route("/ws", get(websocket))
+        .route("/tempo", post(set_tempo))
+        .with_state(link);
+
+    let listener = tokio::net::TcpListener::bind(ADDRESS)
+        .await
+        .expect("Could not bind address.");
+    println!("Dashboard running at http://{}", ADDRESS);
+    axum::serve(listener, app).await.unwrap();
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn websocket(ws: WebSocketUpgrade, State(link): State<Arc<AblLink>>) -> Response {
+    ws.on_upgrade(move |socket| stream_state(socket, link))
+}
+
+/// Send the state of the session to the browser until the socket closes.
+async fn stream_state(mut socket: WebSocket, link: Arc<AblLink>) {
+    let mut session_state = SessionState::new();
+    let mut interval = tokio::time::interval(UPDATE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        link.capture_app_session_state(&mut session_state);
+        let time = link.clock_micros();
+        let json = format!(
+            "{{\"tempo\":{:.2},\"peers\":{},\"beat\":{:.3},\"phase\":{:.3},\"quantum\":{},\"playing\":{}}}",
+            session_state.tempo(),
+            link.num_peers(),
+            session_state.beat_at_time(time, QUANTUM),
+            session_state.phase_at_time(time, QUANTUM),
+            QUANTUM,
+            session_state.is_playing()
+        );
+
+        if socket.send(Message::Text(json.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Commit the tempo sent as plain text in the request body.
+async fn set_tempo(State(link): State<Arc<AblLink>>, body: String) -> StatusCode {
+    let bpm = match body.trim().parse::<f64>() {
+        Ok(bpm) if (20. ..=999.).contains(&bpm) => bpm,
+        _ => return StatusCode::BAD_REQUEST,
+    };
+
+    let mut session_state = SessionState::new();
+    link.capture_app_session_state(&mut session_state);
+    session_state.set_tempo(bpm, link.clock_micros());
+    link.commit_app_session_state(&session_state);
+
+    StatusCode::NO_CONTENT
+}
+
+const INDEX_HTML: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>rusty_link dashboard</title>
+  <style>
+    body { font-family: sans-serif; background: #222; color: #eee; text-align: center; }
+    #tempo { font-size: 4em; margin: 0.2em; }
+    circle { fill: none; stroke-width: 12; }
+  </style>
+</head>
+<body>
+  <h1 id="tempo">-</h1>
+  <p>peers: <span id="peers">-</span> | beat: <span id="beat">-</span> | <span id="playing">-</span></p>
+  <svg width="200" height="200" viewBox="0 0 200 200">
+    <circle cx="100" cy="100" r="80" stroke="#444"></circle>
+    <circle id="ring" cx="100" cy="100" r="80" stroke="#f80"
+            transform="rotate(-90 100 100)" stroke-dasharray="0 503"></circle>
+  </svg>
+  <form id="tempo-form">
+    <input id="bpm" type="number" min="20" max="999" step="0.01" value="120">
+    <button type="submit">Set tempo</button>
+  </form>
+  <script>
+    const circumference = 2 * Math.PI * 80;
+    const socket = new WebSocket(`ws://${location.host}/ws`);
+    socket.onmessage = (message) => {
+      const state = JSON.parse(message.data);
+      document.getElementById("tempo").textContent = state.tempo.toFixed(2) + " BPM";
+      document.getElementById("peers").textContent = state.peers;
+      document.getElementById("beat").textContent = state.beat.toFixed(2);
+      document.getElementById("playing").textContent = state.playing ? "playing" : "stopped";
+      const filled = circumference * state.phase / state.quantum;
+      document.getElementById("ring").setAttribute("stroke-dasharray", `${filled} ${circumference}`);
+    };
+    document.getElementById("tempo-form").onsubmit = (event) => {
+      event.preventDefault();
+      fetch("/tempo", { method: "POST", body: document.getElementById("bpm").value });
+    };
+  </script>
+</body>
+</html>
+"##;
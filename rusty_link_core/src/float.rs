@@ -0,0 +1,42 @@
+// Rounding of f64 without std, which provides these through the platform's libm.
+// Valid for values within the range of i64, which covers beats and microseconds.
+
+pub(crate) fn floor(x: f64) -> f64 {
+    let truncated = x as i64 as f64;
+    if truncated > x {
+        truncated - 1.
+    } else {
+        truncated
+    }
+}
+
+pub(crate) fn ceil(x: f64) -> f64 {
+    -floor(-x)
+}
+
+/// Round half away from zero, like `f64::round`.
+pub(crate) fn round(x: f64) -> f64 {
+    if x < 0. {
+        -floor(-x + 0.5)
+    } else {
+        floor(x + 0.5)
+    }
+}
+
+pub(crate) fn abs(x: f64) -> f64 {
+    if x < 0. {
+        -x
+    } else {
+        x
+    }
+}
+
+/// `x` modulo `m` in `[0, m)`, like `f64::rem_euclid`.
+pub(crate) fn rem_euclid(x: f64, m: f64) -> f64 {
+    let r = x % m;
+    if r < 0. {
+        r + abs(m)
+    } else {
+        r
+    }
+}
@@ -0,0 +1,44 @@
+use crate::float::{abs, ceil, floor, round};
+
+/// Kind of a grid line, from the most to the least prominent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GridLineKind {
+    Bar,
+    Beat,
+    Subdivision,
+}
+
+/// The beats of all grid lines from `from_beat` to `to_beat` (inclusive), with bars of
+/// `quantum` beats and every beat split into `subdivision` parts.
+pub fn grid_lines(
+    from_beat: f64,
+    to_beat: f64,
+    quantum: f64,
+    subdivision: u32,
+) -> impl Iterator<Item = (f64, GridLineKind)> {
+    let subdivision = subdivision.max(1);
+    let steps = f64::from(subdivision);
+    let first = ceil(from_beat * steps) as i64;
+    let last = floor(to_beat * steps) as i64;
+
+    (first..=last).map(move |step| {
+        let beat = step as f64 / steps;
+        let kind = if step.rem_euclid(i64::from(subdivision)) != 0 {
+            GridLineKind::Subdivision
+        } else if is_bar_line(beat, quantum) {
+            GridLineKind::Bar
+        } else {
+            GridLineKind::Beat
+        };
+        (beat, kind)
+    })
+}
+
+/// Does a bar start at `beat`? Tolerates rounding of fractional quanta.
+fn is_bar_line(beat: f64, quantum: f64) -> bool {
+    if quantum == 0. {
+        return false;
+    }
+    let bars = beat / quantum;
+    abs(bars - round(bars)) < 1e-9
+}
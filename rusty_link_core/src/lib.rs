@@ -0,0 +1,22 @@
+//! The pure beat, phase and time math of [rusty_link](https://crates.io/crates/rusty_link),
+//! without the FFI to Ableton Link.
+//!
+//! This crate is `no_std` and has no dependencies, so e.g. a clock box on a microcontroller
+//! can share its timing code with a desktop bridge which runs Link through `rusty_link`.
+//! All times are in microseconds of the Link clock.
+
+#![no_std]
+
+mod float;
+mod grid;
+mod phase;
+mod position;
+mod timeline;
+
+pub use grid::{grid_lines, GridLineKind};
+pub use phase::{
+    closest_phase_match, from_phase_encoded_beats, next_bar, next_phase_match, phase,
+    to_phase_encoded_beats,
+};
+pub use position::Position;
+pub use timeline::Timeline;
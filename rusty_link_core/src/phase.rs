@@ -0,0 +1,56 @@
+//! Phase math of Link, after `ableton/link/Phase.hpp`.
+
+use crate::{
+    float::{ceil, rem_euclid},
+    Timeline,
+};
+
+/// The phase of `beat` in the range `[0, quantum)`, also for negative beats.
+/// A quantum of 0 has a phase of 0.
+pub fn phase(beat: f64, quantum: f64) -> f64 {
+    if quantum == 0. {
+        0.
+    } else {
+        rem_euclid(beat, quantum)
+    }
+}
+
+/// The least beat not before `beat` with the same phase as `target`.
+/// Returns `beat` for a quantum of 0.
+pub fn next_phase_match(beat: f64, target: f64, quantum: f64) -> f64 {
+    if quantum == 0. {
+        return beat;
+    }
+    let difference = rem_euclid(phase(target, quantum) - phase(beat, quantum), quantum);
+    beat + difference
+}
+
+/// The beat closest to `beat` with the same phase as `target`.
+pub fn closest_phase_match(beat: f64, target: f64, quantum: f64) -> f64 {
+    next_phase_match(beat - 0.5 * quantum, target, quantum)
+}
+
+/// The first beat of the next bar of `quantum` beats, or `beat` if a bar starts on it.
+pub fn next_bar(beat: f64, quantum: f64) -> f64 {
+    if quantum == 0. {
+        beat
+    } else {
+        ceil(beat / quantum) * quantum
+    }
+}
+
+/// The beat at `time` of a session, which only shares the phase of `timeline` with
+/// respect to `quantum`, like `SessionState::beat_at_time`.
+pub fn to_phase_encoded_beats(timeline: &Timeline, time: i64, quantum: f64) -> f64 {
+    let beat = timeline.to_beats(time);
+    closest_phase_match(beat, beat - timeline.beat_origin(), quantum)
+}
+
+/// The time of a phase encoded `beat`, the inverse of [to_phase_encoded_beats], like
+/// `SessionState::time_at_beat`.
+pub fn from_phase_encoded_beats(timeline: &Timeline, beat: f64, quantum: f64) -> i64 {
+    // The encoding moved the beat by less than half a quantum to the phase of
+    // `beat - beat_origin`, so the original beat is the closest one with the inverse phase
+    let inverse = closest_phase_match(beat, beat + timeline.beat_origin(), quantum);
+    timeline.from_beats(inverse)
+}
@@ -0,0 +1,46 @@
+use crate::float::{floor, rem_euclid};
+use core::fmt;
+
+/// A position on the Link timeline, displayed as `bar.beat.tick` (e.g. `5.3.241`).
+///
+/// Bars and beats count from 1, ticks from 0. Negative beats, e.g. the count-in of a
+/// quantized start, are displayed in bar 0 and below.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub beat: f64,
+    pub quantum: f64,
+    pub ticks_per_beat: u32,
+}
+
+impl Position {
+    pub fn new(beat: f64, quantum: f64, ticks_per_beat: u32) -> Self {
+        Self {
+            beat,
+            quantum,
+            ticks_per_beat,
+        }
+    }
+
+    /// The bar, counted from 1.
+    pub fn bar(&self) -> i64 {
+        floor(self.beat / self.quantum) as i64 + 1
+    }
+
+    /// The beat within the bar, counted from 1.
+    pub fn beat_in_bar(&self) -> i64 {
+        floor(rem_euclid(self.beat, self.quantum)) as i64 + 1
+    }
+
+    /// The tick within the beat, counted from 0.
+    pub fn tick(&self) -> u32 {
+        let tick = floor(rem_euclid(self.beat, 1.) * self.ticks_per_beat as f64) as u32;
+        // Guard against rounding up to the next beat
+        tick.min(self.ticks_per_beat.saturating_sub(1))
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.bar(), self.beat_in_bar(), self.tick())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::float::round;
+
+/// Mapping between Link clock time and beats, as exchanged between peers.
+///
+/// Like Link, tempo is stored in microseconds per beat and beats in micro beats, so timelines
+/// survive the round trip over the network without rounding errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timeline {
+    pub micros_per_beat: i64,
+    /// Beat at `time_origin`, in micro beats.
+    pub beat_origin: i64,
+    /// Link clock time in microseconds.
+    pub time_origin: i64,
+}
+
+impl Timeline {
+    pub fn new(bpm: f64, beat_origin: f64, time_origin: i64) -> Self {
+        Self {
+            micros_per_beat: bpm_to_micros_per_beat(bpm),
+            beat_origin: round(beat_origin * 1e6) as i64,
+            time_origin,
+        }
+    }
+
+    /// Tempo in Beats Per Minute.
+    pub fn tempo(&self) -> f64 {
+        60e6 / self.micros_per_beat as f64
+    }
+
+    /// Beat at `time_origin`.
+    pub fn beat_origin(&self) -> f64 {
+        self.beat_origin as f64 / 1e6
+    }
+
+    /// The beat at `time` (Link clock in microseconds).
+    pub fn to_beats(&self, time: i64) -> f64 {
+        let elapsed = (time - self.time_origin) as f64 / self.micros_per_beat as f64;
+        self.beat_origin() + elapsed
+    }
+
+    /// The time (Link clock in microseconds) of `beat`.
+    pub fn from_beats(&self, beat: f64) -> i64 {
+        let beats = beat - self.beat_origin();
+        self.time_origin + round(beats * self.micros_per_beat as f64) as i64
+    }
+}
+
+fn bpm_to_micros_per_beat(bpm: f64) -> i64 {
+    round(60e6 / bpm) as i64
+}
@@ -0,0 +1,55 @@
+// The phase math has to agree with Link, since peers exchange phase encoded timelines.
+
+use rusty_link_core::{
+    closest_phase_match, from_phase_encoded_beats, grid_lines, next_bar, next_phase_match, phase,
+    to_phase_encoded_beats, GridLineKind, Position, Timeline,
+};
+
+#[test]
+fn phase_of_negative_beats() {
+    assert_eq!(phase(5., 4.), 1.);
+    assert_eq!(phase(-1., 4.), 3.);
+    assert_eq!(phase(-4., 4.), 0.);
+    assert_eq!(phase(3., 0.), 0.);
+}
+
+#[test]
+fn phase_matches() {
+    assert_eq!(next_phase_match(5., 0., 4.), 8.);
+    assert_eq!(next_phase_match(5., 5., 4.), 5.);
+    assert_eq!(closest_phase_match(5., 0., 4.), 4.);
+    assert_eq!(closest_phase_match(7., 0., 4.), 8.);
+    assert_eq!(next_bar(4.5, 4.), 8.);
+    assert_eq!(next_bar(-1., 4.), 0.);
+}
+
+#[test]
+fn phase_encoding_round_trips() {
+    let timeline = Timeline::new(120., 3.25, 1_000_000);
+    for time in [0, 1_000_000, 1_250_000, 7_777_777] {
+        let beat = to_phase_encoded_beats(&timeline, time, 4.);
+        let phase_of_timeline = phase(timeline.to_beats(time) - timeline.beat_origin(), 4.);
+        assert!((phase(beat, 4.) - phase_of_timeline).abs() < 1e-9);
+        assert!((from_phase_encoded_beats(&timeline, beat, 4.) - time).abs() <= 1);
+    }
+}
+
+#[test]
+fn position_of_negative_beats() {
+    assert_eq!(Position::new(-0.5, 4., 4).to_string(), "0.4.2");
+    assert_eq!(Position::new(17.25, 4., 4).to_string(), "5.2.1");
+}
+
+#[test]
+fn grid_lines_of_a_bar() {
+    let lines: Vec<_> = grid_lines(3.5, 5., 4., 2).collect();
+    assert_eq!(
+        lines,
+        [
+            (3.5, GridLineKind::Subdivision),
+            (4., GridLineKind::Bar),
+            (4.5, GridLineKind::Subdivision),
+            (5., GridLineKind::Beat),
+        ]
+    );
+}
@@ -0,0 +1,123 @@
+//! Attribute macro of [rusty_link](https://crates.io/crates/rusty_link), which registers
+//! methods of a struct as Link callbacks. Use it through the `derive` feature of
+//! `rusty_link`, see `rusty_link::link_handlers`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Error, Ident, ImplItem, ItemImpl};
+
+/// Callbacks of `AblLink` which methods can be registered for.
+const HANDLERS: [(&str, &str); 3] = [
+    ("on_tempo", "set_tempo_callback"),
+    ("on_peers", "set_num_peers_callback"),
+    ("on_start_stop", "set_start_stop_callback"),
+];
+
+/// Implement `rusty_link::LinkHandlers` for the type of an `impl` block, registering the
+/// methods marked with `#[on_tempo]`, `#[on_peers]` and `#[on_start_stop]` as the
+/// respective callbacks.
+///
+/// Marked methods take `&mut self` and the value passed by Link (`f64` bpm, `u64` peers
+/// or `bool` is_playing). Each callback can be handled by at most one method.
+#[proc_macro_attribute]
+pub fn link_handlers(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return Error::new(Span::call_site(), "link_handlers takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let mut item = parse_macro_input!(item as ItemImpl);
+    match expand(&mut item) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(item: &mut ItemImpl) -> syn::Result<proc_macro2::TokenStream> {
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(Error::new_spanned(
+            path,
+            "link_handlers must be used on an inherent impl block",
+        ));
+    }
+
+    let mut registrations = Vec::new();
+    let mut registered: Vec<&str> = Vec::new();
+
+    for impl_item in &mut item.items {
+        let ImplItem::Fn(method) = impl_item else {
+            continue;
+        };
+
+        let mut markers = Vec::new();
+        method.attrs.retain(|attr| {
+            match HANDLERS.iter().find(|(name, _)| attr.path().is_ident(name)) {
+                Some(handler) => {
+                    markers.push((*handler, attr.clone()));
+                    false
+                }
+                None => true,
+            }
+        });
+        if let Some((_, extra)) = markers.get(1) {
+            return Err(Error::new_spanned(
+                extra,
+                "a method can only be marked with one handler attribute",
+            ));
+        }
+        let Some(((name, setter), attr)) = markers.pop() else {
+            continue;
+        };
+
+        if registered.contains(&name) {
+            return Err(Error::new_spanned(
+                attr,
+                format!("only one method can be marked #[{}]", name),
+            ));
+        }
+        let mut_ref_self = method
+            .sig
+            .receiver()
+            .is_some_and(|r| r.reference.is_some() && r.mutability.is_some());
+        if !mut_ref_self || method.sig.inputs.len() != 2 {
+            return Err(Error::new_spanned(
+                &method.sig,
+                format!(
+                    "#[{}] methods take &mut self and the value passed by Link",
+                    name
+                ),
+            ));
+        }
+        registered.push(name);
+
+        let method_name = &method.sig.ident;
+        let setter = Ident::new(setter, Span::call_site());
+        registrations.push(quote! {
+            let handlers = ::std::sync::Arc::clone(this);
+            link.#setter(move |value| {
+                handlers
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .#method_name(value)
+            });
+        });
+    }
+
+    let self_ty = &item.self_ty;
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics ::rusty_link::LinkHandlers for #self_ty #where_clause {
+            fn register_handlers(
+                this: &::std::sync::Arc<::std::sync::Mutex<Self>>,
+                link: &::rusty_link::AblLink,
+            ) {
+                #(#registrations)*
+            }
+        }
+    })
+}
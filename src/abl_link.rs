@@ -1,9 +1,49 @@
-use crate::{rust_bindings::*, session_state::SessionState, split};
-use std::os::raw::c_void;
+use crate::{
+    callbacks::{trampoline, Callbacks},
+    realtime,
+    rust_bindings::*,
+    session_state::{Capture, CaptureRole, SessionState},
+    CommitPolicy, Delivery, Error, FrameInfo, LinkEvent, Result, TimedEvent, DEFAULT_QUANTUM,
+};
+use std::{
+    fmt,
+    os::raw::c_void,
+    sync::{
+        atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+        Mutex, MutexGuard, TryLockError,
+    },
+    time::Duration,
+};
 
 /// The representation of an abl_link instance.
 pub struct AblLink {
     pub(crate) link: abl_link,
+    offline: bool,
+    /// Link clock time at construction, see [AblLink::uptime]
+    created_at: i64,
+    offline_enabled: AtomicBool,
+    follow_only: AtomicBool,
+    start_stop_sync: AtomicBool,
+    /// Set while [AblLink::try_commit_audio_session_state] commits
+    audio_committing: AtomicBool,
+    dropped_audio_commits: AtomicU64,
+    /// Commits refused by the `strict` feature
+    refused_commits: AtomicU64,
+    /// Link clock time of the last audio capture, `i64::MIN` before the first one
+    last_audio_capture: AtomicI64,
+    /// Number of the last capture, see [SessionState::generation]
+    capture_generation: AtomicU64,
+    output_latency: AtomicU64,
+    /// Bits of the `f64` quantum of the app, see [AblLink::quantum]
+    pub(crate) quantum: AtomicU64,
+    /// Set by [AblLink::panic]
+    pub(crate) panicked: AtomicBool,
+    audio_session_state: Mutex<SessionState>,
+    app_session_state: Mutex<SessionState>,
+    render_session_state: Mutex<SessionState>,
+    // Fields are dropped after `Drop::drop` destroyed `link`, so Link never
+    // invokes a trampoline with a freed slot.
+    pub(crate) callbacks: Box<Callbacks>,
 }
 
 unsafe impl Send for AblLink {}
@@ -11,7 +51,63 @@ unsafe impl Sync for AblLink {}
 
 impl Drop for AblLink {
     fn drop(&mut self) {
-        unsafe { abl_link_destroy(self.link) }
+        // Deregister the trampolines first, so Link can not invoke the callbacks of a
+        // partially destroyed instance, e.g. reporting 0 peers while it shuts down.
+        unsafe {
+            rusty_link_shim_delete_callbacks(self.link);
+            abl_link_destroy(self.link)
+        }
+    }
+}
+
+/// Age after which a captured Session State is considered stale, see [AblLink::is_stale].
+pub const STALE_SESSION_STATE_AGE: Duration = Duration::from_millis(500);
+
+/// Capture a fresh app Session State, so formatting never blocks on a held snapshot.
+///
+/// Beat and phase are formatted for the quantum of the app, see [AblLink::quantum].
+fn display_state(link: &AblLink) -> (SessionState, i64) {
+    let mut session_state = SessionState::new();
+    link.capture_app_session_state(&mut session_state);
+    (session_state, link.clock_micros())
+}
+
+impl fmt::Debug for AblLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (session_state, now) = display_state(self);
+        let quantum = self.quantum();
+        f.debug_struct("AblLink")
+            .field("enabled", &self.is_enabled())
+            .field("offline", &self.offline)
+            .field("tempo", &session_state.tempo())
+            .field("beat", &session_state.beat_at_time(now, quantum))
+            .field("phase", &session_state.phase_at_time(now, quantum))
+            .field("quantum", &quantum)
+            .field("is_playing", &session_state.is_playing())
+            .field("num_peers", &self.num_peers())
+            .finish()
+    }
+}
+
+/// Current state of the session, e.g. `120.00 bpm | beat 12.34 | phase 0.34/4 | playing | 2 peers`.
+impl fmt::Display for AblLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (session_state, now) = display_state(self);
+        let quantum = self.quantum();
+        write!(
+            f,
+            "{:.2} bpm | beat {:.2} | phase {:.2}/{} | {} | {} peers",
+            session_state.tempo(),
+            session_state.beat_at_time(now, quantum),
+            session_state.phase_at_time(now, quantum),
+            quantum,
+            if session_state.is_playing() {
+                "playing"
+            } else {
+                "stopped"
+            },
+            self.num_peers()
+        )
     }
 }
 
@@ -21,19 +117,83 @@ impl AblLink {
     ///  Thread-safe: yes
     ///
     ///  Realtime-safe: no
+    ///
+    /// If the environment variable `ABL_LINK_ISOLATED` is set to `1`, the instance is
+    /// created offline, see [AblLink::new_offline].
     pub fn new(bpm: f64) -> AblLink {
+        AblLink::create(bpm, isolated_by_env())
+    }
+
+    /// Construct a new AblLink instance with an initial tempo, which never touches the network.
+    ///
+    /// All other functions behave normally against the local clock, so apps can use the same
+    /// code path whether networking is permitted or not (e.g. sandboxed plugins). Enabling an
+    /// offline instance is only reflected by [AblLink::is_enabled], no peers will ever be found.
+    ///
+    /// Also meant for test suites, which should neither hang on locked-down networks nor
+    /// disturb real Link sessions on the LAN. Setting `ABL_LINK_ISOLATED=1` makes every
+    /// instance created with [AblLink::new] offline, without changing the code under test.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    #[doc(alias = "new_isolated")]
+    pub fn new_offline(bpm: f64) -> AblLink {
+        AblLink::create(bpm, true)
+    }
+
+    fn create(bpm: f64, offline: bool) -> AblLink {
+        let link = unsafe { abl_link_create(bpm) };
+        let callbacks = Box::new(Callbacks::new(link));
+
+        // The trampolines are registered once and stay registered until the instance is
+        // dropped. Setting or deleting a callback only swaps the closure inside of its slot.
+        let context = &*callbacks as *const Callbacks as *mut c_void;
+        unsafe {
+            abl_link_set_num_peers_callback(link, Some(trampoline::<u64>), context);
+            abl_link_set_tempo_callback(link, Some(trampoline::<f64>), context);
+            abl_link_set_start_stop_callback(link, Some(trampoline::<bool>), context);
+        }
+
         AblLink {
-            link: unsafe { abl_link_create(bpm) },
+            link,
+            offline,
+            created_at: unsafe { abl_link_clock_micros(link) },
+            offline_enabled: AtomicBool::new(false),
+            follow_only: AtomicBool::new(false),
+            start_stop_sync: AtomicBool::new(unsafe { abl_link_is_start_stop_sync_enabled(link) }),
+            output_latency: AtomicU64::new(0),
+            audio_committing: AtomicBool::new(false),
+            dropped_audio_commits: AtomicU64::new(0),
+            refused_commits: AtomicU64::new(0),
+            last_audio_capture: AtomicI64::new(i64::MIN),
+            capture_generation: AtomicU64::new(0),
+            quantum: AtomicU64::new(DEFAULT_QUANTUM.to_bits()),
+            panicked: AtomicBool::new(false),
+            audio_session_state: Mutex::new(SessionState::new()),
+            app_session_state: Mutex::new(SessionState::new()),
+            render_session_state: Mutex::new(SessionState::new()),
+            callbacks,
         }
     }
 
+    /// Was this instance constructed offline, see [AblLink::new_offline]?
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
     ///  Is Link currently enabled?
     ///
     ///  Thread-safe: yes
     ///
     ///  Realtime-safe: yes
     pub fn is_enabled(&self) -> bool {
-        unsafe { abl_link_is_enabled(self.link) }
+        realtime(|| {
+            if self.offline {
+                return self.offline_enabled.load(Ordering::Acquire);
+            }
+            unsafe { abl_link_is_enabled(self.link) }
+        })
     }
 
     ///  Enable/disable Link.
@@ -42,16 +202,32 @@ impl AblLink {
     ///
     ///  Realtime-safe: no
     pub fn enable(&self, enable: bool) {
+        debug!(enable, offline = self.offline, "enable Link");
+        if self.offline {
+            self.offline_enabled.store(enable, Ordering::Release);
+            return;
+        }
         unsafe { abl_link_enable(self.link, enable) }
+
+        if enable && !crate::network_status::probe_network() {
+            warn!(
+                "no network interface can reach the Link multicast group, no peers will be found"
+            );
+            #[cfg(feature = "log")]
+            log::warn!("Link enabled without network, no peers will be found");
+        }
     }
 
     ///  Is start/stop synchronization enabled?
     ///
     ///  Thread-safe: yes
     ///
-    ///  Realtime-safe: no
+    ///  Realtime-safe: yes
+    ///
+    /// Tracked by this instance, so transport code can check whether its start/stop
+    /// changes will propagate to peers without calling into Link.
     pub fn is_start_stop_sync_enabled(&self) -> bool {
-        unsafe { abl_link_is_start_stop_sync_enabled(self.link) }
+        self.start_stop_sync.load(Ordering::Acquire)
     }
 
     ///  Enable start/stop synchronization.
@@ -59,8 +235,18 @@ impl AblLink {
     ///  Thread-safe: yes
     ///
     ///  Realtime-safe: no
+    ///
+    /// Queues a [LinkEvent::StartStopSyncChanged], if the setting changed.
     pub fn enable_start_stop_sync(&self, enable: bool) {
+        debug!(enable, "enable start/stop sync");
         unsafe { abl_link_enable_start_stop_sync(self.link, enable) }
+
+        if self.start_stop_sync.swap(enable, Ordering::AcqRel) != enable {
+            let event = LinkEvent::StartStopSyncChanged { enabled: enable };
+            #[cfg(feature = "metrics")]
+            crate::telemetry::record_event(event);
+            self.callbacks.record(event, self.clock_micros());
+        }
     }
 
     ///  How many peers are currently connected in a Link session?
@@ -69,7 +255,7 @@ impl AblLink {
     ///
     ///  Realtime-safe: yes
     pub fn num_peers(&self) -> u64 {
-        unsafe { abl_link_num_peers(self.link) }
+        realtime(|| unsafe { abl_link_num_peers(self.link) })
     }
 
     /// Get the current link clock time in microseconds.
@@ -78,7 +264,26 @@ impl AblLink {
     ///
     ///  Realtime-safe: yes
     pub fn clock_micros(&self) -> i64 {
-        unsafe { abl_link_clock_micros(self.link) }
+        realtime(|| unsafe { abl_link_clock_micros(self.link) })
+    }
+
+    /// Time since this instance was constructed, according to the Link clock.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn uptime(&self) -> Duration {
+        self.elapsed_since(self.created_at)
+    }
+
+    /// Time since `micros` on the Link clock, see [AblLink::clock_micros]. Zero if `micros`
+    /// is in the future.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn elapsed_since(&self, micros: i64) -> Duration {
+        Duration::from_micros(self.clock_micros().saturating_sub(micros).max(0) as u64)
     }
 
     ///  Capture the current Link Session State from the audio thread.
@@ -92,7 +297,10 @@ impl AblLink {
     ///  of the current Link Session State, so it should be used in a local scope. The
     ///  session_state should not be created on the audio thread.
     pub fn capture_audio_session_state(&self, session_state: &mut SessionState) {
-        unsafe { abl_link_capture_audio_session_state(self.link, session_state.session_state) }
+        realtime(|| unsafe {
+            abl_link_capture_audio_session_state(self.link, session_state.session_state)
+        });
+        self.stamp(session_state, CaptureRole::Audio);
     }
 
     /// Capture the current Link Session State from an application thread.
@@ -106,7 +314,113 @@ impl AblLink {
     ///  contains a snapshot of the current Link state, so it should be used in a local
     ///  scope.
     pub fn capture_app_session_state(&self, session_state: &mut SessionState) {
-        unsafe { abl_link_capture_app_session_state(self.link, session_state.session_state) };
+        realtime(|| unsafe {
+            abl_link_capture_app_session_state(self.link, session_state.session_state)
+        });
+        self.stamp(session_state, CaptureRole::App);
+    }
+
+    /// Capture the current Link Session State from the audio thread and read tempo, beat,
+    /// phase and transport state at `time` for the given `quantum`.
+    ///
+    ///  Thread-safe: no
+    ///
+    ///  Realtime-safe: yes
+    ///
+    /// Same as [AblLink::capture_audio_session_state] followed by [SessionState::tempo],
+    /// [SessionState::beat_at_time], [SessionState::phase_at_time] and
+    /// [SessionState::is_playing], but crosses the FFI boundary only once.
+    /// This function should ONLY be called in the audio thread.
+    pub fn audio_frame_info(&self, time: i64, quantum: f64) -> FrameInfo {
+        let info =
+            realtime(|| unsafe { rusty_link_shim_audio_frame_info(self.link, time, quantum) });
+        self.last_audio_capture
+            .store(info.capture_time, Ordering::Relaxed);
+        info.into()
+    }
+
+    /// Link clock time of the last capture of the audio Session State, `None` if it was
+    /// never captured. Used by [AudioWatchdog](crate::AudioWatchdog).
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn last_audio_capture(&self) -> Option<i64> {
+        match self.last_audio_capture.load(Ordering::Relaxed) {
+            i64::MIN => None,
+            time => Some(time),
+        }
+    }
+
+    /// Capture the current Link Session State from the audio thread into a Session State
+    /// owned by this instance.
+    ///
+    ///  Thread-safe: no
+    ///
+    ///  Realtime-safe: yes
+    ///
+    /// Same as [AblLink::capture_audio_session_state], without creating a [SessionState]
+    /// first. The returned guard can be modified and passed to
+    /// [AblLink::commit_audio_session_state]. Drop it before the end of the audio callback.
+    ///
+    /// Never blocks: returns `None` while another thread holds the snapshot, e.g. a guard
+    /// which was sent away from the audio thread.
+    pub fn audio_snapshot(&self) -> Option<MutexGuard<'_, SessionState>> {
+        let mut session_state = match self.audio_session_state.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+        self.capture_audio_session_state(&mut session_state);
+        Some(session_state)
+    }
+
+    /// Capture the current Link Session State from an application thread into a Session
+    /// State owned by this instance.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// Same as [AblLink::capture_app_session_state], without creating a [SessionState]
+    /// first. The returned guard can be modified and passed to
+    /// [AblLink::commit_app_session_state]. Other application threads calling this function
+    /// block until the guard is dropped.
+    pub fn app_snapshot(&self) -> MutexGuard<'_, SessionState> {
+        let mut session_state = lock(&self.app_session_state);
+        self.capture_app_session_state(&mut session_state);
+        session_state
+    }
+
+    /// Capture the current Link Session State from a render thread, which is neither the
+    /// audio thread nor a regular application thread, e.g. the frame loop of a lighting engine.
+    ///
+    ///  Thread-safe: no
+    ///
+    ///  Realtime-safe: no, but does not allocate
+    ///
+    /// Uses the application thread capture of Link, so it can not interfere with the audio
+    /// thread. It may briefly wait for other application threads capturing or committing,
+    /// which is acceptable at frame rate but not in an audio callback. Create `session_state`
+    /// once, outside of the render loop, and reuse it for every frame, or use
+    /// [AblLink::render_snapshot]. Changes are committed with
+    /// [AblLink::commit_app_session_state].
+    pub fn capture_render_session_state(&self, session_state: &mut SessionState) {
+        self.capture_app_session_state(session_state)
+    }
+
+    /// Capture the current Link Session State from a render thread into a Session State
+    /// owned by this instance, see [AblLink::capture_render_session_state].
+    ///
+    ///  Thread-safe: no
+    ///
+    ///  Realtime-safe: no, but does not allocate
+    ///
+    /// Meant for a single render thread, drop the guard before the end of the frame.
+    pub fn render_snapshot(&self) -> MutexGuard<'_, SessionState> {
+        let mut session_state = lock(&self.render_session_state);
+        self.capture_render_session_state(&mut session_state);
+        session_state
     }
 
     ///  Commit the given Session State to the Link session from the audio thread.
@@ -118,8 +432,75 @@ impl AblLink {
     ///  This function should ONLY be called in the audio thread. The given
     ///  session_state will replace the current Link state. Modifications will be
     ///  communicated to other peers in the session.
+    ///
+    /// With the `strict` feature, Session States failing [AblLink::check_capture] are not
+    /// committed.
     pub fn commit_audio_session_state(&self, session_state: &SessionState) {
-        unsafe { abl_link_commit_audio_session_state(self.link, session_state.session_state) };
+        if self.blocks_commit(session_state)
+            || self.rejects_stale(session_state, CaptureRole::Audio)
+        {
+            return;
+        }
+        self.note_commit(session_state);
+        realtime(|| unsafe {
+            abl_link_commit_audio_session_state(self.link, session_state.session_state)
+        });
+        trace!("commit audio session state");
+    }
+
+    /// Commit the given Session State from the audio thread, if that is possible without
+    /// waiting. Returns whether the commit was passed on to Link.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    ///
+    /// Never blocks. The commit is dropped and counted in [AblLink::dropped_audio_commits],
+    /// if another thread is committing through this function at the same time, or if the
+    /// [CommitPolicy] (or the `strict` feature) refuses it.
+    ///
+    /// The audio commit of Link itself is wait-free: a commit which Link has not picked up
+    /// yet is replaced by the next one, last write wins. Such replaced commits can not be
+    /// observed and are not counted.
+    pub fn try_commit_audio_session_state(&self, session_state: &SessionState) -> bool {
+        if self.blocks_commit(session_state)
+            || self.rejects_stale(session_state, CaptureRole::Audio)
+        {
+            self.dropped_audio_commits.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        if self.audio_committing.swap(true, Ordering::Acquire) {
+            self.dropped_audio_commits.fetch_add(1, Ordering::Relaxed);
+            trace!("drop contended audio commit");
+            return false;
+        }
+
+        self.note_commit(session_state);
+        realtime(|| unsafe {
+            abl_link_commit_audio_session_state(self.link, session_state.session_state)
+        });
+        self.audio_committing.store(false, Ordering::Release);
+        trace!("commit audio session state");
+        true
+    }
+
+    /// Number of commits dropped by [AblLink::try_commit_audio_session_state].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn dropped_audio_commits(&self) -> u64 {
+        self.dropped_audio_commits.load(Ordering::Relaxed)
+    }
+
+    /// Number of commits refused by the `strict` feature, see [AblLink::check_capture].
+    /// Always 0 without it.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn refused_commits(&self) -> u64 {
+        self.refused_commits.load(Ordering::Relaxed)
     }
 
     ///  Commit the given Session State to the Link session from an application thread.
@@ -131,8 +512,161 @@ impl AblLink {
     ///  The given SessionState will replace the current Link Session State.
     ///  Modifications of the Session State will be communicated to other peers in the
     ///  session.
+    ///
+    /// With the `strict` feature, Session States failing [AblLink::check_capture] are not
+    /// committed.
     pub fn commit_app_session_state(&self, session_state: &SessionState) {
+        if self.blocks_commit(session_state) {
+            debug!("drop commit of a modified timeline, commit policy is FollowOnly");
+            return;
+        }
+        if self.rejects_stale(session_state, CaptureRole::App) {
+            return;
+        }
+        self.note_commit(session_state);
         unsafe { abl_link_commit_app_session_state(self.link, session_state.session_state) };
+        debug!("commit app session state");
+    }
+
+    /// Commit the given Session State from an application thread, unless it does not differ
+    /// from the current app Session State of Link. Returns whether it was committed.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// Meant for GUI code, which commits on every tick of a slider: unchanged values are not
+    /// sent to the session. Tempo, transport state and the beat timeline are compared against
+    /// a fresh capture, so changes made by peers since the last commit are taken into account.
+    pub fn commit_app_if_changed(&self, session_state: &SessionState) -> bool {
+        // Not the state of app_snapshot, `session_state` may be its guard
+        let mut current = SessionState::new();
+        self.capture_app_session_state(&mut current);
+        if same_session(session_state, &current, self.clock_micros()) {
+            trace!("skip commit of an unchanged app session state");
+            return false;
+        }
+
+        self.commit_app_session_state(session_state);
+        true
+    }
+
+    /// Choose which commits are passed on to Link. See [CommitPolicy].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn set_commit_policy(&self, policy: CommitPolicy) {
+        self.follow_only
+            .store(policy == CommitPolicy::FollowOnly, Ordering::Release);
+    }
+
+    /// The current [CommitPolicy], [CommitPolicy::Full] by default.
+    pub fn commit_policy(&self) -> CommitPolicy {
+        if self.follow_only.load(Ordering::Acquire) {
+            CommitPolicy::FollowOnly
+        } else {
+            CommitPolicy::Full
+        }
+    }
+
+    fn blocks_commit(&self, session_state: &SessionState) -> bool {
+        session_state.timeline_modified && self.follow_only.load(Ordering::Acquire)
+    }
+
+    /// Remember the tempo of a commit changing the timeline, so the resulting tempo change
+    /// is reported as [TempoSource::Local](crate::TempoSource::Local), and let the outputs
+    /// follow a local start or stop, see [AblLink::set_transport_policy].
+    fn note_commit(&self, session_state: &SessionState) {
+        if session_state.timeline_modified {
+            self.callbacks.note_local_tempo(session_state.tempo());
+        }
+        if session_state.transport_modified {
+            self.callbacks.set_outputs_playing(
+                session_state.is_playing(),
+                session_state.time_for_is_playing() as i64,
+            );
+        }
+    }
+
+    /// Mark `session_state` as freshly captured for `role`.
+    fn stamp(&self, session_state: &mut SessionState, role: CaptureRole) {
+        let time = self.clock_micros();
+        if role == CaptureRole::Audio {
+            self.last_audio_capture.store(time, Ordering::Relaxed);
+        }
+        session_state.timeline_modified = false;
+        session_state.transport_modified = false;
+        session_state.capture = Some(Capture {
+            generation: self.capture_generation.fetch_add(1, Ordering::Relaxed) + 1,
+            role,
+            time,
+        });
+    }
+
+    /// Was `session_state` captured more than [STALE_SESSION_STATE_AGE] ago?
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    ///
+    /// Committing a stale Session State silently replaces changes made by peers since it was
+    /// captured. Session States which were never captured are not stale.
+    pub fn is_stale(&self, session_state: &SessionState) -> bool {
+        session_state.capture.is_some_and(|capture| {
+            self.clock_micros() - capture.time > STALE_SESSION_STATE_AGE.as_micros() as i64
+        })
+    }
+
+    /// Can `session_state` be committed from a thread of `role` without replacing newer
+    /// changes? Returns [Error::WrongThread] if it was captured for the other role and
+    /// [Error::StaleSessionState] if it is stale, see [AblLink::is_stale].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    ///
+    /// With the `strict` feature, commits failing this check are refused and counted in
+    /// [AblLink::refused_commits]. Session States which were never captured pass.
+    pub fn check_capture(&self, session_state: &SessionState, role: CaptureRole) -> Result<()> {
+        let Some(capture) = session_state.capture else {
+            return Ok(());
+        };
+        if capture.role != role {
+            return Err(Error::WrongThread {
+                expected: match capture.role {
+                    CaptureRole::Audio => "audio",
+                    CaptureRole::App => "app",
+                },
+            });
+        }
+        let age = self.clock_micros() - capture.time;
+        if age > STALE_SESSION_STATE_AGE.as_micros() as i64 {
+            return Err(Error::StaleSessionState {
+                age: Duration::from_micros(age as u64),
+            });
+        }
+        Ok(())
+    }
+
+    /// With the `strict` feature, refuse commits of stale Session States and of Session
+    /// States captured for the other thread role.
+    #[cfg(feature = "strict")]
+    fn rejects_stale(&self, session_state: &SessionState, role: CaptureRole) -> bool {
+        let Err(_error) = self.check_capture(session_state, role) else {
+            return false;
+        };
+        self.refused_commits.fetch_add(1, Ordering::Relaxed);
+        warn!(?role, error = %_error, "refuse commit");
+        #[cfg(feature = "log")]
+        log::warn!("refused commit from the {:?} thread: {}", role, _error);
+        true
+    }
+
+    #[cfg(not(feature = "strict"))]
+    #[inline(always)]
+    fn rejects_stale(&self, _session_state: &SessionState, _role: CaptureRole) -> bool {
+        false
     }
 
     ///  Register a callback to be notified when the number of
@@ -143,11 +677,13 @@ impl AblLink {
     ///  Realtime-safe: no
     ///
     ///  The callback is invoked on a Link-managed thread.
-    pub fn set_num_peers_callback<C: FnMut(u64) + Send + 'static>(&self, mut closure: C) {
-        unsafe {
-            let (state, callback) = split::split_closure_trailing_data(&mut closure);
-            abl_link_set_num_peers_callback(self.link, Some(callback), state);
-        };
+    ///
+    /// See [AblLink::set_num_peers_callback_with] for other threads.
+    ///
+    /// The closure is boxed once during registration. Invoking it does not allocate.
+    /// A closure may replace itself, the new one is used after it returns.
+    pub fn set_num_peers_callback<C: FnMut(u64) + Send + 'static>(&self, closure: C) {
+        self.set_num_peers_callback_with(Delivery::Inline, closure)
     }
 
     ///  Register a callback to be notified when the session tempo changes.
@@ -157,11 +693,13 @@ impl AblLink {
     ///  Realtime-safe: no
     ///
     ///  The callback is invoked on a Link-managed thread.
-    pub fn set_tempo_callback<C: FnMut(f64) + Send + 'static>(&self, mut closure: C) {
-        unsafe {
-            let (state, callback) = split::split_closure_trailing_data(&mut closure);
-            abl_link_set_tempo_callback(self.link, Some(callback), state);
-        }
+    ///
+    /// See [AblLink::set_tempo_callback_with] for other threads.
+    ///
+    /// The closure is boxed once during registration. Invoking it does not allocate.
+    /// A closure may replace itself, the new one is used after it returns.
+    pub fn set_tempo_callback<C: FnMut(f64) + Send + 'static>(&self, closure: C) {
+        self.set_tempo_callback_with(Delivery::Inline, closure)
     }
 
     ///  Register a callback to be notified when the state of start/stop isPlaying changes.
@@ -171,11 +709,13 @@ impl AblLink {
     ///  Realtime-safe: no
     ///
     ///  The callback is invoked on a Link-managed thread.
-    pub fn set_start_stop_callback<C: FnMut(bool) + Send + 'static>(&self, mut closure: C) {
-        unsafe {
-            let (state, callback) = split::split_closure_trailing_data(&mut closure);
-            abl_link_set_start_stop_callback(self.link, Some(callback), state);
-        }
+    ///
+    /// See [AblLink::set_start_stop_callback_with] for other threads.
+    ///
+    /// The closure is boxed once during registration. Invoking it does not allocate.
+    /// A closure may replace itself, the new one is used after it returns.
+    pub fn set_start_stop_callback<C: FnMut(bool) + Send + 'static>(&self, closure: C) {
+        self.set_start_stop_callback_with(Delivery::Inline, closure)
     }
 
     ///  Delete the callback which notifies when the number of peers in the Link session changes.
@@ -183,15 +723,12 @@ impl AblLink {
     ///  Thread-safe: yes
     ///
     ///  Realtime-safe: no
+    ///
+    /// Link keeps reporting changes to [AblLink::poll_events], only the closure is dropped.
+    /// Once this returns, the closure is not running and will not be invoked again. Called
+    /// from inside of the closure itself, it is dropped as soon as it returns.
     pub fn delete_num_peers_callback(&self) {
-        extern "C" fn empty_fn(_: u64, _: *mut c_void) {}
-        unsafe {
-            abl_link_set_num_peers_callback(
-                self.link,
-                Some(empty_fn),
-                std::ptr::null_mut() as *mut c_void,
-            );
-        }
+        self.callbacks.num_peers.clear();
     }
 
     ///  Delete the callback which notifies when the session tempo changes.
@@ -199,15 +736,12 @@ impl AblLink {
     ///  Thread-safe: yes
     ///
     ///  Realtime-safe: no
+    ///
+    /// Link keeps reporting changes to [AblLink::poll_events], only the closure is dropped.
+    /// Once this returns, the closure is not running and will not be invoked again. Called
+    /// from inside of the closure itself, it is dropped as soon as it returns.
     pub fn delete_tempo_callback(&self) {
-        extern "C" fn empty_fn(_: f64, _: *mut c_void) {}
-        unsafe {
-            abl_link_set_tempo_callback(
-                self.link,
-                Some(empty_fn),
-                std::ptr::null_mut() as *mut c_void,
-            );
-        }
+        self.callbacks.tempo.clear();
     }
 
     ///  Delete the callback which notifies when the state of start/stop isPlaying changes.
@@ -215,15 +749,92 @@ impl AblLink {
     ///  Thread-safe: yes
     ///
     ///  Realtime-safe: no
+    ///
+    /// Link keeps reporting changes to [AblLink::poll_events], only the closure is dropped.
+    /// Once this returns, the closure is not running and will not be invoked again. Called
+    /// from inside of the closure itself, it is dropped as soon as it returns.
     pub fn delete_start_stop_callback(&self) {
-        extern "C" fn empty_fn(_: bool, _: *mut c_void) {}
-        unsafe {
-            abl_link_set_start_stop_callback(
-                self.link,
-                Some(empty_fn),
-                std::ptr::null_mut() as *mut c_void,
-            );
-        }
+        self.callbacks.start_stop.clear();
+    }
+
+    /// Hand all session changes reported by Link since the last poll to `f`, oldest first.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes, if `f` is
+    ///
+    /// Events are queued independently of the registered callbacks, so `poll_events` can be
+    /// used from a UI or audio loop instead of reacting on a Link-managed thread. The queue
+    /// holds up to [EVENT_QUEUE_CAPACITY](crate::EVENT_QUEUE_CAPACITY) events, older events are
+    /// dropped. See [AblLink::set_event_coalescing].
+    pub fn poll_events<F: FnMut(LinkEvent)>(&self, f: F) {
+        self.callbacks.events.poll(f)
+    }
+
+    /// Keep a history of the last `capacity` events reported by Link, see
+    /// [AblLink::recent_events]. A capacity of 0 (default) disables the history.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn set_event_history_capacity(&self, capacity: usize) {
+        self.callbacks.history.set_capacity(capacity)
+    }
+
+    /// The last events reported by Link, oldest first, with their Link and wall clock times.
+    ///
+    /// Meant for post-mortem debugging, e.g. attaching the events around a reported tempo
+    /// jump to an issue. Requires [AblLink::set_event_history_capacity].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn recent_events(&self) -> Vec<TimedEvent> {
+        self.callbacks.history.to_vec()
+    }
+
+    /// Deliver only the latest value of each kind of [LinkEvent] per [AblLink::poll_events]
+    /// (default), or every single change reported by Link.
+    ///
+    /// While Link refines the tempo of a session, it reports lots of small tempo changes.
+    /// Coalescing them avoids redundant downstream work like MIDI resyncs or UI redraws.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn set_event_coalescing(&self, coalesce: bool) {
+        self.callbacks.events.set_coalescing(coalesce)
+    }
+
+    /// Store the output latency of the audio device, to report it with [AblLink::diagnostics].
+    ///
+    /// Link itself does not know about latency. Compensate for it by looking up the Session
+    /// State at `host time + output latency` in the audio callback.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn set_output_latency(&self, latency: Duration) {
+        self.output_latency
+            .store(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// The output latency set with [AblLink::set_output_latency], zero by default.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn output_latency(&self) -> Duration {
+        Duration::from_micros(self.output_latency.load(Ordering::Relaxed))
+    }
+
+    /// Which of the num_peers, tempo and start_stop callbacks are registered?
+    pub(crate) fn registered_callbacks(&self) -> (bool, bool, bool) {
+        (
+            self.callbacks.num_peers.is_set(),
+            self.callbacks.tempo.is_set(),
+            self.callbacks.start_stop.is_set(),
+        )
     }
 
     /// Convert time in microseconds from host to global host
@@ -236,3 +847,20 @@ impl AblLink {
         unsafe { abl_link_ghost_to_host(self.link, ghost) }
     }
 }
+
+/// Do `a` and `b` have the same tempo, transport state and beat timeline?
+fn same_session(a: &SessionState, b: &SessionState, now: i64) -> bool {
+    a.tempo() == b.tempo()
+        && a.is_playing() == b.is_playing()
+        && a.time_for_is_playing() == b.time_for_is_playing()
+        && (a.beat_at_time(now, 1.) - b.beat_at_time(now, 1.)).abs() < 1e-9
+}
+
+fn lock(session_state: &Mutex<SessionState>) -> MutexGuard<'_, SessionState> {
+    session_state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Is the process-wide `ABL_LINK_ISOLATED=1` switch set?
+fn isolated_by_env() -> bool {
+    std::env::var_os("ABL_LINK_ISOLATED").is_some_and(|value| value == "1")
+}
@@ -0,0 +1,127 @@
+use crate::{AblLink, LinkEvent, TimedEvent};
+use std::{sync::atomic::Ordering, thread, time::Duration};
+
+/// Time without tempo and peer changes after which [AblLink::wait_until_settled]
+/// considers the timeline stable.
+pub const SETTLE_GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+/// Link clock times in microseconds of the latest changes of a session, returned by
+/// [AblLink::last_activity]. `None` if there was no such change since the instance was
+/// constructed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionActivity {
+    pub tempo: Option<i64>,
+    pub num_peers: Option<i64>,
+    pub start_stop: Option<i64>,
+}
+
+impl SessionActivity {
+    /// Time of the latest change of any kind.
+    pub fn latest(&self) -> Option<i64> {
+        self.tempo.max(self.num_peers).max(self.start_stop)
+    }
+}
+
+impl AblLink {
+    /// When Link last reported a change of tempo, peers or transport, e.g. for idle
+    /// detection, auto-saving or warnings about a session which seems dead.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn last_activity(&self) -> SessionActivity {
+        let load = |time: &std::sync::atomic::AtomicI64| match time.load(Ordering::Acquire) {
+            i64::MIN => None,
+            time => Some(time),
+        };
+
+        SessionActivity {
+            tempo: load(&self.callbacks.last_tempo_change),
+            num_peers: load(&self.callbacks.last_num_peers_change),
+            start_stop: load(&self.callbacks.last_start_stop_change),
+        }
+    }
+
+    /// Time since the latest change reported by Link, or since construction if there was
+    /// none, see [AblLink::last_activity].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn idle_time(&self) -> Duration {
+        match self.last_activity().latest() {
+            Some(time) => self.elapsed_since(time),
+            None => self.uptime(),
+        }
+    }
+
+    /// The last [PEER_HISTORY_CAPACITY](crate::PEER_HISTORY_CAPACITY) peer joins and
+    /// leaves, oldest first, i.e. [LinkEvent::PeerJoined] and [LinkEvent::PeerLeft] events
+    /// with their Link and wall clock times.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn peer_history(&self) -> Vec<TimedEvent> {
+        self.callbacks.peer_history.to_vec()
+    }
+
+    /// The latest peer join or leave, e.g. to show "a peer left 5 seconds ago" with
+    /// [AblLink::elapsed_since] of its time.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn last_peer_change(&self) -> Option<TimedEvent> {
+        self.peer_history().pop()
+    }
+
+    /// Time since the latest peer left, `None` if no peer left yet.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn time_since_peer_left(&self) -> Option<Duration> {
+        self.peer_history()
+            .iter()
+            .rev()
+            .find(|event| matches!(event.event, LinkEvent::PeerLeft { .. }))
+            .map(|event| self.elapsed_since(event.time))
+    }
+
+    /// Block until Link reported no tempo or peer changes for [SETTLE_GRACE_PERIOD], or
+    /// until `timeout` elapsed. Returns whether the timeline settled.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// Call it after enabling Link, so scheduling does not begin against a timeline which
+    /// is still converging with newly discovered peers. Waits at least the grace period.
+    pub fn wait_until_settled(&self, timeout: Duration) -> bool {
+        let grace = i64::try_from(SETTLE_GRACE_PERIOD.as_micros()).unwrap_or(i64::MAX);
+        let timeout = i64::try_from(timeout.as_micros()).unwrap_or(i64::MAX);
+        let start = self.clock_micros();
+
+        loop {
+            let activity = self.last_activity();
+            let quiet_since = activity
+                .tempo
+                .max(activity.num_peers)
+                .unwrap_or(start)
+                .max(start);
+            let now = self.clock_micros();
+            if now - quiet_since >= grace {
+                debug!(waited = now - start, "timeline settled");
+                return true;
+            }
+            if now - start >= timeout {
+                debug!(waited = now - start, "timeline did not settle");
+                return false;
+            }
+
+            let remaining = (quiet_since + grace - now).min(start.saturating_add(timeout) - now);
+            thread::sleep(Duration::from_micros(remaining.clamp(0, 10_000) as u64));
+        }
+    }
+}
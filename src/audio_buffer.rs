@@ -0,0 +1,39 @@
+/// An audio buffer on the Link clock, to place events of pull-based schedulers, e.g.
+/// [NoteScheduler::advance_to](crate::NoteScheduler::advance_to), at sample offsets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioBuffer {
+    /// Link clock time at which the first frame hits the speaker, in microseconds.
+    pub start: i64,
+    pub frames: usize,
+    pub sample_rate: f64,
+}
+
+impl AudioBuffer {
+    pub fn new(start: i64, frames: usize, sample_rate: f64) -> Self {
+        Self {
+            start,
+            frames,
+            sample_rate,
+        }
+    }
+
+    /// Link clock time right after the last frame, the `until` of the schedulers.
+    pub fn end(&self) -> i64 {
+        self.time_at_frame(self.frames)
+    }
+
+    /// Link clock time of `frame`, counted from the start of the buffer.
+    pub fn time_at_frame(&self, frame: usize) -> i64 {
+        self.start + (frame as f64 * 1e6 / self.sample_rate).round() as i64
+    }
+
+    /// Offset of the frame at `time` in the buffer. Times before the buffer, e.g. events
+    /// emitted late, map to frame 0, `None` if `time` is after the buffer.
+    pub fn frame_at(&self, time: i64) -> Option<usize> {
+        if time >= self.end() {
+            return None;
+        }
+        let frame = ((time - self.start) as f64 * self.sample_rate / 1e6).round();
+        Some((frame.max(0.) as usize).min(self.frames.saturating_sub(1)))
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{AblLink, LinkEvent, SessionState, ThreadConfig};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Shortest time between two checks of the audio captures.
+const MIN_CHECK_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Detects a stalled audio thread: queues a [LinkEvent::AudioStalled], if the audio Session
+/// State of an [AblLink] instance was not captured for longer than a timeout while transport
+/// is playing, and a [LinkEvent::AudioResumed] once captures continue.
+///
+/// Captures are recorded by [AblLink::capture_audio_session_state] and everything built on
+/// it. Apps can react to the events by falling back to timing on an application thread, e.g.
+/// when the audio device was unplugged or its callback hangs.
+pub struct AudioWatchdog {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AudioWatchdog {
+    /// Start watching the audio captures of `link`. The captures are checked every quarter
+    /// of `timeout`, but at most every millisecond.
+    pub fn start(link: Arc<AblLink>, timeout: Duration) -> AudioWatchdog {
+        Self::start_with_config(link, timeout, ThreadConfig::default())
+    }
+
+    /// Same as [AudioWatchdog::start], with a custom configuration of the watchdog thread.
+    pub fn start_with_config(
+        link: Arc<AblLink>,
+        timeout: Duration,
+        config: ThreadConfig,
+    ) -> AudioWatchdog {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = Arc::clone(&running);
+
+        let interval = (timeout / 4).max(MIN_CHECK_INTERVAL);
+        let handle = config.spawn("watchdog", move || {
+            let mut session_state = SessionState::new();
+            let started = link.clock_micros();
+            let timeout_micros = timeout.as_micros() as i64;
+            let mut stalled = false;
+
+            while running_clone.load(Ordering::Acquire) {
+                link.capture_app_session_state(&mut session_state);
+                let last_capture = link.last_audio_capture().unwrap_or(started).max(started);
+                let now = link.clock_micros();
+
+                if !stalled && session_state.is_playing() && now - last_capture > timeout_micros {
+                    stalled = true;
+                    debug!(last_capture, "audio stalled");
+                    report(&link, LinkEvent::AudioStalled { last_capture }, now);
+                } else if stalled && now - last_capture <= timeout_micros {
+                    stalled = false;
+                    debug!("audio resumed");
+                    report(&link, LinkEvent::AudioResumed, now);
+                }
+
+                // Woken up early by `stop`
+                let deadline = Instant::now() + interval;
+                while running_clone.load(Ordering::Acquire) {
+                    let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                        break;
+                    };
+                    thread::park_timeout(remaining);
+                }
+            }
+        });
+
+        AudioWatchdog {
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop the watchdog thread.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for AudioWatchdog {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Queue `event` like an event reported by Link.
+fn report(link: &AblLink, event: LinkEvent, time: i64) {
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_event(event);
+    link.callbacks.record(event, time);
+}
@@ -0,0 +1,86 @@
+use crate::{Position, SessionState};
+use rusty_link_core::grid_lines;
+
+pub use rusty_link_core::GridLineKind;
+
+/// Most lines of a [BeatGrid]. Windows with more lines are cut off at the end.
+pub const MAX_GRID_LINES: usize = 10_000;
+
+/// A line of a [BeatGrid].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridLine {
+    pub kind: GridLineKind,
+    pub beat: f64,
+    /// Link clock time of the line in microseconds.
+    pub time: i64,
+    /// Position of the line, bars and beats count from 1.
+    pub position: Position,
+}
+
+impl GridLine {
+    /// `5` for bar lines, `5.2` for beat lines and nothing for subdivisions.
+    pub fn label(&self) -> Option<String> {
+        match self.kind {
+            GridLineKind::Bar => Some(self.position.bar().to_string()),
+            GridLineKind::Beat => Some(format!(
+                "{}.{}",
+                self.position.bar(),
+                self.position.beat_in_bar()
+            )),
+            GridLineKind::Subdivision => None,
+        }
+    }
+}
+
+/// Bar, beat and subdivision lines of the Link timeline in a window of time, e.g. for
+/// the timeline of an arranger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeatGrid {
+    pub from_time: i64,
+    pub to_time: i64,
+    /// Lines ordered by time.
+    pub lines: Vec<GridLine>,
+}
+
+impl BeatGrid {
+    /// The lines between `from_time` and `to_time` (inclusive), with bars of `quantum`
+    /// beats and every beat split into `subdivision` parts. A subdivision of 1 has no
+    /// subdivision lines.
+    pub fn for_window(
+        session_state: &SessionState,
+        from_time: i64,
+        to_time: i64,
+        quantum: f64,
+        subdivision: u32,
+    ) -> Self {
+        let from_beat = session_state.beat_at_time(from_time, quantum);
+        let to_beat = session_state.beat_at_time(to_time, quantum);
+
+        let lines = grid_lines(from_beat, to_beat, quantum, subdivision)
+            .take(MAX_GRID_LINES)
+            .map(|(beat, kind)| GridLine {
+                kind,
+                beat,
+                time: session_state.time_at_beat(beat, quantum),
+                position: Position::new(beat, quantum, subdivision.max(1)),
+            })
+            .collect();
+
+        Self {
+            from_time,
+            to_time,
+            lines,
+        }
+    }
+
+    /// Horizontal position of `time` in a view of `width` pixels showing the window.
+    pub fn x(&self, time: i64, width: f32) -> f32 {
+        let duration = (self.to_time - self.from_time).max(1) as f64;
+        ((time - self.from_time) as f64 / duration * f64::from(width)) as f32
+    }
+
+    /// Lines of the given kind.
+    pub fn lines_of(&self, kind: GridLineKind) -> impl Iterator<Item = &GridLine> {
+        self.lines.iter().filter(move |line| line.kind == kind)
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{AblLink, SessionState};
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::prelude::*;
+use std::sync::Arc;
+
+/// Bevy plugin, which creates an [AblLink] instance and updates the [LinkBeat]
+/// resource from the captured app Session State at the start of every frame.
+///
+/// The instance is available as the [LinkInstance] resource, for example to commit
+/// tempo changes. The quantum used for beat and phase can be changed at runtime
+/// through [LinkBeat::quantum].
+pub struct LinkPlugin {
+    pub bpm: f64,
+    pub quantum: f64,
+    /// Enable Link when the plugin is added.
+    pub enable: bool,
+}
+
+impl Default for LinkPlugin {
+    fn default() -> Self {
+        Self {
+            bpm: 120.,
+            quantum: 4.,
+            enable: true,
+        }
+    }
+}
+
+/// The [AblLink] instance created by [LinkPlugin].
+#[derive(Resource, Clone)]
+pub struct LinkInstance(pub Arc<AblLink>);
+
+/// The state of the Link session in the current frame.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct LinkBeat {
+    pub beat: f64,
+    pub phase: f64,
+    pub tempo: f64,
+    pub is_playing: bool,
+    pub num_peers: u64,
+    /// Quantum used to calculate `beat` and `phase`.
+    pub quantum: f64,
+}
+
+impl Plugin for LinkPlugin {
+    fn build(&self, app: &mut App) {
+        let link = AblLink::new(self.bpm);
+        link.enable(self.enable);
+
+        app.insert_resource(LinkInstance(Arc::new(link)))
+            .insert_resource(LinkBeat {
+                beat: 0.,
+                phase: 0.,
+                tempo: self.bpm,
+                is_playing: false,
+                num_peers: 0,
+                quantum: self.quantum,
+            })
+            .add_systems(PreUpdate, update_link_beat);
+    }
+}
+
+fn update_link_beat(
+    link: Res<LinkInstance>,
+    mut link_beat: ResMut<LinkBeat>,
+    mut session_state: Local<SessionState>,
+) {
+    let link = &link.0;
+    link.capture_app_session_state(&mut session_state);
+    let time = link.clock_micros();
+    let quantum = link_beat.quantum;
+
+    *link_beat = LinkBeat {
+        beat: session_state.beat_at_time(time, quantum),
+        phase: session_state.phase_at_time(time, quantum),
+        tempo: session_state.tempo(),
+        is_playing: session_state.is_playing(),
+        num_peers: link.num_peers(),
+        quantum,
+    };
+}
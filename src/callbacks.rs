@@ -0,0 +1,478 @@
+use crate::{
+    delivery::{Delivery, Dispatched, Dispatcher},
+    events::{
+        EventHistory, EventQueue, TempoSource, TimedEvent, EVENT_QUEUE_CAPACITY,
+        PEER_HISTORY_CAPACITY,
+    },
+    realtime_callback,
+    rust_bindings::{abl_link, abl_link_clock_micros},
+    transport_policy::TransportPolicy,
+    LinkEvent, ThreadConfig,
+};
+use std::{
+    collections::VecDeque,
+    os::raw::c_void,
+    sync::{
+        atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicU8, Ordering},
+        OnceLock, TryLockError,
+    },
+    time::SystemTime,
+};
+
+#[cfg(rusty_link_loom)]
+use loom::{sync::Mutex, thread};
+#[cfg(not(rusty_link_loom))]
+use std::{sync::Mutex, thread};
+
+type BoxedCallback<T> = Box<dyn FnMut(T) + Send>;
+
+/// Storage for a user callback, which is invoked from a Link-managed thread.
+///
+/// Link only ever gets a pointer to the slots, never to the closure itself. The slot
+/// lives as long as the [AblLink](crate::AblLink) instance, so replacing or deleting
+/// the closure while Link invokes it can not leave Link with a dangling pointer.
+///
+/// Closures are boxed when they are set. Invoking them locks the slot, records the
+/// invoking thread and checks for a deferred change. Apart from the thread handle std
+/// creates once per Link thread, nothing is allocated, so there is no allocator churn
+/// when Link fires lots of callbacks, e.g. during tempo negotiation.
+///
+/// A closure may replace or delete itself. The slot is locked while the closure runs, so
+/// such a change is deferred until the closure returns. The invoking thread is recognized
+/// by its id, which is why setting or deleting a callback from inside of a closure does not
+/// deadlock.
+pub struct CallbackSlot<T> {
+    callback: Mutex<Option<BoxedCallback<T>>>,
+    /// Thread currently running the closure
+    invoking: Mutex<Option<thread::ThreadId>>,
+    /// Replacement set by the closure itself, `Some(None)` for a deletion
+    deferred: Mutex<Option<Option<BoxedCallback<T>>>>,
+}
+
+impl<T> Default for CallbackSlot<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> CallbackSlot<T> {
+    pub fn new() -> Self {
+        Self {
+            callback: Mutex::new(None),
+            invoking: Mutex::new(None),
+            deferred: Mutex::new(None),
+        }
+    }
+
+    /// Replace the stored callback.
+    pub fn set(&self, callback: BoxedCallback<T>) {
+        self.replace(Some(callback));
+    }
+
+    /// Remove the stored callback.
+    pub fn clear(&self) {
+        self.replace(None);
+    }
+
+    /// Is a callback stored?
+    pub fn is_set(&self) -> bool {
+        if self.is_invoking_thread() {
+            return lock(&self.deferred)
+                .as_ref()
+                .is_none_or(|replacement| replacement.is_some());
+        }
+        self.lock().is_some()
+    }
+
+    /// Invoke the stored callback, if there is one.
+    pub fn invoke(&self, value: T) {
+        let mut callback = self.lock();
+        let previous = self.call(&mut callback, value);
+        drop(callback);
+        drop(previous);
+    }
+
+    /// Invoke the stored callback with the values returned by `next`, until it returns
+    /// `None` or the closure replaces itself. Returns the number of consumed values.
+    ///
+    /// Never waits for the closure: if the slot is locked by another thread, e.g. while
+    /// the closure runs or is being replaced, `next` is not called and 0 is returned.
+    pub fn try_invoke_each<N: FnMut() -> Option<T>>(&self, mut next: N) -> usize {
+        let mut callback = match self.callback.try_lock() {
+            Ok(callback) => callback,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return 0,
+        };
+
+        let mut count = 0;
+        while let Some(value) = next() {
+            count += 1;
+            if let Some(previous) = self.call(&mut callback, value) {
+                drop(callback);
+                drop(previous);
+                break;
+            }
+        }
+        count
+    }
+
+    /// Run the closure in `callback` and apply a change the closure made to its own slot.
+    /// Returns the replaced closure, to be dropped outside of the lock.
+    fn call(&self, callback: &mut Option<BoxedCallback<T>>, value: T) -> Option<BoxedCallback<T>> {
+        if let Some(callback) = callback.as_mut() {
+            *lock(&self.invoking) = Some(thread::current().id());
+            callback(value);
+            *lock(&self.invoking) = None;
+        }
+
+        let replacement = lock(&self.deferred).take()?;
+        std::mem::replace(callback, replacement)
+    }
+
+    fn replace(&self, replacement: Option<BoxedCallback<T>>) {
+        // Locking the slot from inside of the closure would deadlock
+        if self.is_invoking_thread() {
+            *lock(&self.deferred) = Some(replacement);
+            return;
+        }
+        let previous = std::mem::replace(&mut *self.lock(), replacement);
+        // Drop the old closure outside of the lock
+        drop(previous);
+    }
+
+    fn is_invoking_thread(&self) -> bool {
+        *lock(&self.invoking) == Some(thread::current().id())
+    }
+
+    fn lock(&self) -> impl std::ops::DerefMut<Target = Option<BoxedCallback<T>>> + '_ {
+        lock(&self.callback)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> impl std::ops::DerefMut<Target = T> + '_ {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The callback slots and the event queue of one [AblLink](crate::AblLink) instance.
+pub(crate) struct Callbacks {
+    /// Started by the first callback with [Delivery::Dispatcher]. Declared first, so the
+    /// dispatcher thread is joined before the slots it invokes are dropped.
+    dispatcher: OnceLock<Dispatcher>,
+    /// Configuration of the dispatcher thread, see [AblLink::set_thread_config](crate::AblLink::set_thread_config)
+    pub thread_config: Mutex<ThreadConfig>,
+    /// [Delivery] of the num_peers, tempo and start/stop callbacks
+    delivery: [AtomicU8; 3],
+    /// Values for callbacks with [Delivery::Queued]
+    num_peers_queue: Mutex<VecDeque<u64>>,
+    tempo_queue: Mutex<VecDeque<f64>>,
+    start_stop_queue: Mutex<VecDeque<bool>>,
+    pub num_peers: CallbackSlot<u64>,
+    pub tempo: CallbackSlot<f64>,
+    pub start_stop: CallbackSlot<bool>,
+    pub events: EventQueue,
+    pub history: EventHistory,
+    /// The last [LinkEvent::PeerJoined] and [LinkEvent::PeerLeft] events
+    pub peer_history: EventHistory,
+    /// Peer count reported by the previous num_peers callback
+    pub last_num_peers: AtomicU64,
+    /// Link clock times of the last tempo, peer and start/stop changes, `i64::MIN` if none
+    pub last_tempo_change: AtomicI64,
+    pub last_num_peers_change: AtomicI64,
+    pub last_start_stop_change: AtomicI64,
+    /// Bits of the `f64` tempo of the last local commit, NaN if there is none
+    local_tempo: AtomicU64,
+    /// [TransportPolicy] of the instance
+    pub transport_policy: AtomicU8,
+    /// Transport state of the crate-managed outputs according to the policy and the Link
+    /// clock time it changed at
+    pub outputs_playing: AtomicBool,
+    pub outputs_since: AtomicI64,
+    link: abl_link,
+}
+
+impl Callbacks {
+    pub fn new(link: abl_link) -> Self {
+        let peer_history = EventHistory::default();
+        peer_history.set_capacity(PEER_HISTORY_CAPACITY);
+
+        Self {
+            dispatcher: OnceLock::new(),
+            thread_config: Mutex::new(ThreadConfig::default()),
+            delivery: Default::default(),
+            num_peers_queue: Mutex::new(VecDeque::with_capacity(EVENT_QUEUE_CAPACITY)),
+            tempo_queue: Mutex::new(VecDeque::with_capacity(EVENT_QUEUE_CAPACITY)),
+            start_stop_queue: Mutex::new(VecDeque::with_capacity(EVENT_QUEUE_CAPACITY)),
+            num_peers: CallbackSlot::new(),
+            tempo: CallbackSlot::new(),
+            start_stop: CallbackSlot::new(),
+            events: EventQueue::default(),
+            history: EventHistory::default(),
+            peer_history,
+            last_num_peers: AtomicU64::new(0),
+            last_tempo_change: AtomicI64::new(i64::MIN),
+            last_num_peers_change: AtomicI64::new(i64::MIN),
+            last_start_stop_change: AtomicI64::new(i64::MIN),
+            local_tempo: AtomicU64::new(f64::NAN.to_bits()),
+            transport_policy: AtomicU8::new(TransportPolicy::AcceptAll as u8),
+            outputs_playing: AtomicBool::new(false),
+            outputs_since: AtomicI64::new(0),
+            link,
+        }
+    }
+
+    /// Remember the tempo of a local commit, so the next tempo change reported by Link can
+    /// be attributed to it.
+    pub fn note_local_tempo(&self, bpm: f64) {
+        self.local_tempo.store(bpm.to_bits(), Ordering::Release);
+    }
+
+    /// Did the tempo change to `bpm` originate from the last local commit? Every change
+    /// consumes the noted local tempo.
+    fn tempo_source(&self, bpm: f64) -> TempoSource {
+        let local = f64::from_bits(self.local_tempo.swap(f64::NAN.to_bits(), Ordering::AcqRel));
+        if (local - bpm).abs() < 1e-6 {
+            TempoSource::Local
+        } else {
+            TempoSource::Remote
+        }
+    }
+
+    /// Change where the callback of `T` is invoked, starting the dispatcher if needed.
+    pub fn set_delivery<T: CallbackValue>(&self, delivery: Delivery) {
+        if delivery == Delivery::Dispatcher {
+            self.dispatcher
+                .get_or_init(|| Dispatcher::start(self, &lock(&self.thread_config)));
+        }
+        self.delivery[T::INDEX].store(delivery as u8, Ordering::Release);
+    }
+
+    /// Invoke the callback of `T` with `value` where its [Delivery] says.
+    fn deliver<T: CallbackValue>(&self, value: T) {
+        match Delivery::from_u8(self.delivery[T::INDEX].load(Ordering::Acquire)) {
+            Delivery::Inline => T::slot(self).invoke(value),
+            Delivery::Dispatcher => {
+                if let Some(dispatcher) = self.dispatcher.get() {
+                    dispatcher.send(value.dispatched());
+                }
+            }
+            Delivery::Queued => {
+                let mut queue = T::queue(self).lock().unwrap_or_else(|e| e.into_inner());
+                if queue.len() == EVENT_QUEUE_CAPACITY {
+                    queue.pop_front();
+                }
+                queue.push_back(value);
+            }
+        }
+    }
+
+    /// Invoke the callback of `T` with the queued values. Never blocks.
+    pub fn run_queued<T: CallbackValue>(&self) -> usize {
+        // Values stay queued while the slot is busy. Each value is popped on its own, so
+        // Link is never blocked by user code.
+        T::slot(self).try_invoke_each(|| match T::queue(self).try_lock() {
+            Ok(mut queue) => queue.pop_front(),
+            Err(TryLockError::Poisoned(e)) => e.into_inner().pop_front(),
+            Err(TryLockError::WouldBlock) => None,
+        })
+    }
+
+    /// Start or stop the crate-managed outputs at `time`.
+    pub fn set_outputs_playing(&self, is_playing: bool, time: i64) {
+        if self.outputs_playing.swap(is_playing, Ordering::AcqRel) != is_playing {
+            self.outputs_since.store(time, Ordering::Release);
+        }
+    }
+
+    /// Let the outputs follow a start or stop reported by Link, as far as the policy
+    /// allows. Local changes were already applied by the commit.
+    fn follow_transport(&self, is_playing: bool, time: i64) {
+        let policy = TransportPolicy::from_u8(self.transport_policy.load(Ordering::Acquire));
+        match policy {
+            TransportPolicy::AcceptAll => self.set_outputs_playing(is_playing, time),
+            TransportPolicy::IgnoreRemoteStops if is_playing => {
+                self.set_outputs_playing(is_playing, time)
+            }
+            TransportPolicy::IgnoreRemoteStops | TransportPolicy::ManualOnly => {}
+        }
+    }
+
+    /// Queue `event` for polling and add it to the history.
+    pub fn record(&self, event: LinkEvent, time: i64) {
+        self.events.push(event);
+        self.history.push(TimedEvent {
+            time,
+            system_time: SystemTime::now(),
+            event,
+        });
+    }
+}
+
+/// A value passed by Link to one of the callbacks.
+pub(crate) trait CallbackValue: Copy + Sized + std::fmt::Debug {
+    /// Index of the callback in [Callbacks::delivery]
+    const INDEX: usize;
+
+    fn slot(callbacks: &Callbacks) -> &CallbackSlot<Self>;
+    fn queue(callbacks: &Callbacks) -> &Mutex<VecDeque<Self>>;
+    fn event(self, callbacks: &Callbacks) -> LinkEvent;
+    fn dispatched(self) -> Dispatched;
+}
+
+impl CallbackValue for u64 {
+    const INDEX: usize = 0;
+
+    fn slot(callbacks: &Callbacks) -> &CallbackSlot<Self> {
+        &callbacks.num_peers
+    }
+
+    fn queue(callbacks: &Callbacks) -> &Mutex<VecDeque<Self>> {
+        &callbacks.num_peers_queue
+    }
+
+    fn dispatched(self) -> Dispatched {
+        Dispatched::NumPeers(self)
+    }
+
+    fn event(self, _callbacks: &Callbacks) -> LinkEvent {
+        LinkEvent::NumPeersChanged { num_peers: self }
+    }
+}
+
+impl CallbackValue for f64 {
+    const INDEX: usize = 1;
+
+    fn slot(callbacks: &Callbacks) -> &CallbackSlot<Self> {
+        &callbacks.tempo
+    }
+
+    fn queue(callbacks: &Callbacks) -> &Mutex<VecDeque<Self>> {
+        &callbacks.tempo_queue
+    }
+
+    fn dispatched(self) -> Dispatched {
+        Dispatched::Tempo(self)
+    }
+
+    fn event(self, callbacks: &Callbacks) -> LinkEvent {
+        LinkEvent::TempoChanged {
+            bpm: self,
+            source: callbacks.tempo_source(self),
+        }
+    }
+}
+
+impl CallbackValue for bool {
+    const INDEX: usize = 2;
+
+    fn slot(callbacks: &Callbacks) -> &CallbackSlot<Self> {
+        &callbacks.start_stop
+    }
+
+    fn queue(callbacks: &Callbacks) -> &Mutex<VecDeque<Self>> {
+        &callbacks.start_stop_queue
+    }
+
+    fn dispatched(self) -> Dispatched {
+        Dispatched::StartStop(self)
+    }
+
+    fn event(self, _callbacks: &Callbacks) -> LinkEvent {
+        LinkEvent::StartStopChanged { is_playing: self }
+    }
+}
+
+/// C callback registered with Link. `context` has to point to the [Callbacks] of the instance.
+pub(crate) unsafe extern "C" fn trampoline<T: CallbackValue>(value: T, context: *mut c_void) {
+    debug_assert!(!context.is_null());
+
+    let callbacks = &*(context as *const Callbacks);
+    let event = value.event(callbacks);
+    let time = abl_link_clock_micros(callbacks.link);
+    let previous_num_peers = match event {
+        LinkEvent::NumPeersChanged { num_peers } => {
+            callbacks.last_num_peers.swap(num_peers, Ordering::AcqRel)
+        }
+        _ => callbacks.last_num_peers.load(Ordering::Acquire),
+    };
+
+    let last_change = match event {
+        LinkEvent::NumPeersChanged { .. } => &callbacks.last_num_peers_change,
+        LinkEvent::TempoChanged { .. } => &callbacks.last_tempo_change,
+        _ => &callbacks.last_start_stop_change,
+    };
+    last_change.store(time, Ordering::Release);
+    if let LinkEvent::StartStopChanged { is_playing } = event {
+        callbacks.follow_transport(is_playing, time);
+    }
+
+    debug!(?event, "Link callback");
+    #[cfg(feature = "log")]
+    log_event(event, time, previous_num_peers);
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_event(event);
+
+    realtime_callback(|| {
+        callbacks.record(event, time);
+        if let LinkEvent::NumPeersChanged { num_peers } = event {
+            let peer_event = match num_peers.cmp(&previous_num_peers) {
+                std::cmp::Ordering::Greater => Some(LinkEvent::PeerJoined { num_peers }),
+                std::cmp::Ordering::Less => Some(LinkEvent::PeerLeft { num_peers }),
+                std::cmp::Ordering::Equal => None,
+            };
+            if let Some(peer_event) = peer_event {
+                callbacks.record(peer_event, time);
+                callbacks.peer_history.push(TimedEvent {
+                    time,
+                    system_time: SystemTime::now(),
+                    event: peer_event,
+                });
+            }
+        }
+        callbacks.deliver(value);
+    });
+}
+
+/// Emit a `log` record for a session change, timestamped with the Link clock.
+#[cfg(feature = "log")]
+fn log_event(event: LinkEvent, time: i64, previous_num_peers: u64) {
+    match event {
+        LinkEvent::TempoChanged { bpm, source } => {
+            log::debug!(
+                "[{}us] tempo changed to {:.2} bpm ({:?})",
+                time,
+                bpm,
+                source
+            )
+        }
+        LinkEvent::NumPeersChanged { num_peers } if num_peers > previous_num_peers => {
+            log::info!("[{}us] peer joined, {} peer(s)", time, num_peers)
+        }
+        LinkEvent::NumPeersChanged { num_peers } => {
+            log::info!("[{}us] peer left, {} peer(s)", time, num_peers)
+        }
+        LinkEvent::StartStopChanged { is_playing: true } => {
+            log::info!("[{}us] transport started", time)
+        }
+        LinkEvent::StartStopChanged { is_playing: false } => {
+            log::info!("[{}us] transport stopped", time)
+        }
+        LinkEvent::QuantumChanged { quantum } => {
+            log::debug!("[{}us] quantum changed to {}", time, quantum)
+        }
+        LinkEvent::StartStopSyncChanged { enabled } => {
+            log::info!("[{}us] start/stop sync enabled: {}", time, enabled)
+        }
+        LinkEvent::AudioStalled { last_capture } => {
+            log::warn!("[{}us] audio stalled since {}us", time, last_capture)
+        }
+        LinkEvent::AudioResumed => log::info!("[{}us] audio resumed", time),
+        LinkEvent::NetworkChanged { available: true } => {
+            log::info!("[{}us] network available", time)
+        }
+        LinkEvent::NetworkChanged { available: false } => {
+            log::warn!("[{}us] network unavailable, Link can not find peers", time)
+        }
+        // Logged as NumPeersChanged
+        LinkEvent::PeerJoined { .. } | LinkEvent::PeerLeft { .. } => {}
+    }
+}
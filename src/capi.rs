@@ -0,0 +1,446 @@
+//! C API of the additions of this crate, for hosts which embed `rusty_link` instead of
+//! using `abl_link` directly.
+//!
+//! A `rusty_link` handle owns an `abl_link` instance. [rusty_link_abl_link] returns that
+//! instance, so everything covered by `abl_link.h` is still done through `abl_link_*`
+//! functions, while events, the MIDI clock and the note scheduler come from here.
+//!
+//! The functions are compatible with [cbindgen](https://github.com/mozilla/cbindgen), a
+//! header can be generated with `cbindgen --config cbindgen.toml --output rusty_link.h`.
+//! Build a static or dynamic library with e.g.
+//! `cargo rustc --release --features capi --crate-type staticlib`.
+
+#![allow(non_camel_case_types)]
+
+use crate::{
+    AblLink, LinkEvent, MidiClockOut, NoteScheduler, ScheduledNote, SessionState, TempoSource,
+};
+use std::{os::raw::c_void, sync::Arc};
+
+pub use crate::rust_bindings::abl_link;
+
+/// Handle of an [AblLink] instance.
+pub struct rusty_link {
+    link: Arc<AblLink>,
+}
+
+/// Handle of a running [MidiClockOut].
+pub struct rusty_link_midi_clock {
+    clock: MidiClockOut,
+}
+
+/// Callback of a [rusty_link_note_scheduler], invoked with each emitted MIDI message, its
+/// beat and Link clock time and the context of the host.
+pub type rusty_link_emit_note =
+    extern "C" fn(beat: f64, time: i64, message: *const u8, length: usize, context: *mut c_void);
+
+/// Handle of a [NoteScheduler].
+pub struct rusty_link_note_scheduler {
+    link: Arc<AblLink>,
+    scheduler: NoteScheduler<Box<dyn FnMut(ScheduledNote)>>,
+    /// Audio Session State captured by [rusty_link_note_scheduler_advance_audio]
+    session_state: SessionState,
+}
+
+/// Kind of a [rusty_link_event].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum rusty_link_event_kind {
+    RUSTY_LINK_NUM_PEERS_CHANGED,
+    RUSTY_LINK_TEMPO_CHANGED,
+    RUSTY_LINK_START_STOP_CHANGED,
+    RUSTY_LINK_QUANTUM_CHANGED,
+    RUSTY_LINK_START_STOP_SYNC_CHANGED,
+    RUSTY_LINK_AUDIO_STALLED,
+    RUSTY_LINK_AUDIO_RESUMED,
+    RUSTY_LINK_PEER_JOINED,
+    RUSTY_LINK_PEER_LEFT,
+    RUSTY_LINK_NETWORK_CHANGED,
+}
+
+/// A [LinkEvent]. Only the field belonging to `kind` is set.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct rusty_link_event {
+    pub kind: rusty_link_event_kind,
+    pub num_peers: u64,
+    pub bpm: f64,
+    /// Was the tempo change committed by this instance? See [TempoSource].
+    pub tempo_is_local: bool,
+    pub is_playing: bool,
+    pub quantum: f64,
+    pub start_stop_sync: bool,
+    pub last_audio_capture: i64,
+    pub network_available: bool,
+}
+
+impl From<LinkEvent> for rusty_link_event {
+    fn from(event: LinkEvent) -> Self {
+        let mut c_event = rusty_link_event {
+            kind: rusty_link_event_kind::RUSTY_LINK_NUM_PEERS_CHANGED,
+            num_peers: 0,
+            bpm: 0.,
+            tempo_is_local: false,
+            is_playing: false,
+            quantum: 0.,
+            start_stop_sync: false,
+            last_audio_capture: 0,
+            network_available: false,
+        };
+        match event {
+            LinkEvent::NumPeersChanged { num_peers } => c_event.num_peers = num_peers,
+            LinkEvent::TempoChanged { bpm, source } => {
+                c_event.kind = rusty_link_event_kind::RUSTY_LINK_TEMPO_CHANGED;
+                c_event.bpm = bpm;
+                c_event.tempo_is_local = source == TempoSource::Local;
+            }
+            LinkEvent::StartStopChanged { is_playing } => {
+                c_event.kind = rusty_link_event_kind::RUSTY_LINK_START_STOP_CHANGED;
+                c_event.is_playing = is_playing;
+            }
+            LinkEvent::QuantumChanged { quantum } => {
+                c_event.kind = rusty_link_event_kind::RUSTY_LINK_QUANTUM_CHANGED;
+                c_event.quantum = quantum;
+            }
+            LinkEvent::StartStopSyncChanged { enabled } => {
+                c_event.kind = rusty_link_event_kind::RUSTY_LINK_START_STOP_SYNC_CHANGED;
+                c_event.start_stop_sync = enabled;
+            }
+            LinkEvent::AudioStalled { last_capture } => {
+                c_event.kind = rusty_link_event_kind::RUSTY_LINK_AUDIO_STALLED;
+                c_event.last_audio_capture = last_capture;
+            }
+            LinkEvent::AudioResumed => {
+                c_event.kind = rusty_link_event_kind::RUSTY_LINK_AUDIO_RESUMED;
+            }
+            LinkEvent::PeerJoined { num_peers } => {
+                c_event.kind = rusty_link_event_kind::RUSTY_LINK_PEER_JOINED;
+                c_event.num_peers = num_peers;
+            }
+            LinkEvent::PeerLeft { num_peers } => {
+                c_event.kind = rusty_link_event_kind::RUSTY_LINK_PEER_LEFT;
+                c_event.num_peers = num_peers;
+            }
+            LinkEvent::NetworkChanged { available } => {
+                c_event.kind = rusty_link_event_kind::RUSTY_LINK_NETWORK_CHANGED;
+                c_event.network_available = available;
+            }
+        }
+        c_event
+    }
+}
+
+/// Context pointer of the host, which is passed back to its callbacks.
+struct Context(*mut c_void);
+
+// The host is responsible for the thread safety of its context
+unsafe impl Send for Context {}
+
+/// Create a `rusty_link` handle with a new Link instance, see [AblLink::new].
+///
+/// The handle has to be destroyed with [rusty_link_destroy].
+#[no_mangle]
+pub extern "C" fn rusty_link_create(bpm: f64) -> *mut rusty_link {
+    Box::into_raw(Box::new(rusty_link {
+        link: Arc::new(AblLink::new(bpm)),
+    }))
+}
+
+/// Destroy a handle created by [rusty_link_create].
+///
+/// The Link instance is destroyed once all MIDI clocks started with it are stopped and
+/// all note schedulers created with it are destroyed.
+///
+/// # Safety
+///
+/// `link` has to be a handle returned by [rusty_link_create], which was not destroyed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_destroy(link: *mut rusty_link) {
+    if !link.is_null() {
+        drop(Box::from_raw(link));
+    }
+}
+
+/// The `abl_link` instance of a handle, for use with the functions of `abl_link.h`.
+///
+/// The instance must not be destroyed with `abl_link_destroy`.
+///
+/// # Safety
+///
+/// `link` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_abl_link(link: *const rusty_link) -> abl_link {
+    (&*link).link.link
+}
+
+/// Drain the queued events, see [AblLink::poll_events].
+///
+/// `callback` is invoked for every event on the calling thread, with `context` passed through.
+///
+/// # Safety
+///
+/// `link` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_poll_events(
+    link: *const rusty_link,
+    callback: extern "C" fn(event: *const rusty_link_event, context: *mut c_void),
+    context: *mut c_void,
+) {
+    (&*link).link.poll_events(|event| {
+        let event = rusty_link_event::from(event);
+        callback(&event, context)
+    })
+}
+
+/// Enable or disable coalescing of queued events, see [AblLink::set_event_coalescing].
+///
+/// # Safety
+///
+/// `link` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_set_event_coalescing(link: *const rusty_link, coalesce: bool) {
+    (&*link).link.set_event_coalescing(coalesce)
+}
+
+/// The quantum of the app, see [AblLink::quantum].
+///
+/// # Safety
+///
+/// `link` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_quantum(link: *const rusty_link) -> f64 {
+    (&*link).link.quantum()
+}
+
+/// Change the quantum of the app, see [AblLink::set_quantum]. Returns false, if the quantum
+/// is not positive and finite.
+///
+/// # Safety
+///
+/// `link` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_set_quantum(link: *const rusty_link, quantum: f64) -> bool {
+    (&*link).link.set_quantum(quantum).is_ok()
+}
+
+/// Enable or disable start/stop sync, see [AblLink::enable_start_stop_sync].
+///
+/// Use this instead of `abl_link_enable_start_stop_sync`, so the change is tracked and
+/// reported as `RUSTY_LINK_START_STOP_SYNC_CHANGED`.
+///
+/// # Safety
+///
+/// `link` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_enable_start_stop_sync(link: *const rusty_link, enable: bool) {
+    (&*link).link.enable_start_stop_sync(enable)
+}
+
+/// Silence all outputs of the crate following `link` and optionally stop transport, see
+/// [AblLink::panic].
+///
+/// # Safety
+///
+/// `link` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_panic(link: *const rusty_link, stop_transport: bool) {
+    (&*link).link.panic(stop_transport)
+}
+
+/// Let the outputs silenced by [rusty_link_panic] follow the session again.
+///
+/// # Safety
+///
+/// `link` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_clear_panic(link: *const rusty_link) {
+    (&*link).link.clear_panic()
+}
+
+/// Start sending MIDI clock following the Link session, see [MidiClockOut::start].
+///
+/// `send` is invoked from the clock thread with each MIDI message and `context`. The
+/// clock has to be stopped with [rusty_link_midi_clock_stop].
+///
+/// # Safety
+///
+/// `link` has to be a valid handle. `context` has to be usable from the clock thread
+/// until the clock is stopped.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_midi_clock_start(
+    link: *const rusty_link,
+    quantum: f64,
+    send: extern "C" fn(message: *const u8, length: usize, context: *mut c_void),
+    context: *mut c_void,
+) -> *mut rusty_link_midi_clock {
+    let context = Context(context);
+    let sink = move |message: &[u8]| {
+        let context = &context;
+        send(message.as_ptr(), message.len(), context.0)
+    };
+
+    Box::into_raw(Box::new(rusty_link_midi_clock {
+        clock: MidiClockOut::start(Arc::clone(&(&*link).link), sink, quantum),
+    }))
+}
+
+/// Stop a MIDI clock started by [rusty_link_midi_clock_start] and destroy its handle.
+///
+/// # Safety
+///
+/// `clock` has to be a handle returned by [rusty_link_midi_clock_start], which was not
+/// stopped yet.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_midi_clock_stop(clock: *mut rusty_link_midi_clock) {
+    if !clock.is_null() {
+        Box::from_raw(clock).clock.stop();
+    }
+}
+
+/// Create a note scheduler with bars of `quantum` beats, see [NoteScheduler::new].
+///
+/// `emit` is invoked with `context` on the thread polling or advancing the scheduler. The
+/// handle has to be destroyed with [rusty_link_note_scheduler_destroy].
+///
+/// # Safety
+///
+/// `link` has to be a valid handle. `context` has to be usable until the scheduler is
+/// destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_note_scheduler_create(
+    link: *const rusty_link,
+    quantum: f64,
+    emit: rusty_link_emit_note,
+    context: *mut c_void,
+) -> *mut rusty_link_note_scheduler {
+    let emit = move |note: ScheduledNote| {
+        let message = note.message();
+        emit(
+            note.beat,
+            note.time,
+            message.as_ptr(),
+            message.len(),
+            context,
+        )
+    };
+
+    Box::into_raw(Box::new(rusty_link_note_scheduler {
+        link: Arc::clone(&(&*link).link),
+        scheduler: NoteScheduler::new(quantum, Box::new(emit)),
+        session_state: SessionState::new(),
+    }))
+}
+
+/// Destroy a scheduler created by [rusty_link_note_scheduler_create]. Pending messages
+/// are dropped.
+///
+/// # Safety
+///
+/// `scheduler` has to be a handle returned by [rusty_link_note_scheduler_create], which
+/// was not destroyed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_note_scheduler_destroy(
+    scheduler: *mut rusty_link_note_scheduler,
+) {
+    if !scheduler.is_null() {
+        drop(Box::from_raw(scheduler));
+    }
+}
+
+/// Schedule the MIDI message of `length` bytes at `message` at `beat`, see
+/// [NoteScheduler::schedule]. Returns false, if the message is too long.
+///
+/// # Safety
+///
+/// `scheduler` has to be a valid handle and `message` has to point to `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_note_scheduler_schedule(
+    scheduler: *mut rusty_link_note_scheduler,
+    beat: f64,
+    message: *const u8,
+    length: usize,
+) -> bool {
+    let message = std::slice::from_raw_parts(message, length);
+    (&mut *scheduler).scheduler.schedule(beat, message).is_ok()
+}
+
+/// Schedule a note on at `beat` and its note off `length` beats later, see
+/// [NoteScheduler::schedule_note].
+///
+/// # Safety
+///
+/// `scheduler` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_note_scheduler_schedule_note(
+    scheduler: *mut rusty_link_note_scheduler,
+    beat: f64,
+    length: f64,
+    channel: u8,
+    note: u8,
+    velocity: u8,
+) {
+    (&mut *scheduler)
+        .scheduler
+        .schedule_note(beat, length, channel, note, velocity)
+}
+
+/// Emit the messages due at the current time plus the lookahead, according to the app
+/// Session State, see [NoteScheduler::poll]. Returns the number of emitted messages.
+///
+/// # Safety
+///
+/// `scheduler` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_note_scheduler_poll(
+    scheduler: *mut rusty_link_note_scheduler,
+) -> usize {
+    let scheduler = &mut *scheduler;
+    scheduler.scheduler.poll(&scheduler.link)
+}
+
+/// Capture the audio Session State and emit the messages due up to the Link clock time
+/// `until`, e.g. the end of the audio buffer, see [NoteScheduler::advance_to]. Returns the
+/// number of emitted messages. Only call it from the audio thread.
+///
+/// # Safety
+///
+/// `scheduler` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_note_scheduler_advance_audio(
+    scheduler: *mut rusty_link_note_scheduler,
+    until: i64,
+) -> usize {
+    let scheduler = &mut *scheduler;
+    scheduler
+        .link
+        .capture_audio_session_state(&mut scheduler.session_state);
+    scheduler
+        .scheduler
+        .advance_to(&scheduler.session_state, until)
+}
+
+/// Number of messages which were not emitted yet, see [NoteScheduler::pending].
+///
+/// # Safety
+///
+/// `scheduler` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_note_scheduler_pending(
+    scheduler: *const rusty_link_note_scheduler,
+) -> usize {
+    (&*scheduler).scheduler.pending()
+}
+
+/// Drop all pending messages and emit note offs for all sounding notes at the Link clock
+/// time `now`, see [NoteScheduler::panic].
+///
+/// # Safety
+///
+/// `scheduler` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusty_link_note_scheduler_panic(
+    scheduler: *mut rusty_link_note_scheduler,
+    now: i64,
+) {
+    (&mut *scheduler).scheduler.panic(now)
+}
@@ -0,0 +1,26 @@
+use crate::AblLink;
+
+/// A clock in microseconds, e.g. the clock of an audio interface or a PTP-disciplined clock.
+///
+/// Implemented for [AblLink] (reading its Link clock) and for closures returning
+/// microseconds. The time has to be monotonic.
+///
+/// Only the C++ API can run Link itself on a custom clock, see `full_cpp::Link::with_clock`
+/// (feature `full-cpp`). The C API behind [AblLink] always uses the platform clock, so an
+/// [AblLink] can not be constructed with a [ClockSource].
+pub trait ClockSource: Send + Sync {
+    /// The current time in microseconds.
+    fn micros(&self) -> i64;
+}
+
+impl<F: Fn() -> i64 + Send + Sync> ClockSource for F {
+    fn micros(&self) -> i64 {
+        self()
+    }
+}
+
+impl ClockSource for AblLink {
+    fn micros(&self) -> i64 {
+        self.clock_micros()
+    }
+}
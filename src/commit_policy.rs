@@ -0,0 +1,14 @@
+/// Which commits of Session States an [AblLink](crate::AblLink) instance passes on to Link,
+/// see [AblLink::set_commit_policy](crate::AblLink::set_commit_policy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommitPolicy {
+    /// Every commit is passed on to Link.
+    #[default]
+    Full,
+    /// Commits of Session States with a changed tempo or requested beats are dropped, so
+    /// the instance never disrupts the session timeline. Transport changes are still
+    /// committed.
+    ///
+    /// Meant for monitoring tools and visualizers, which should only follow a session.
+    FollowOnly,
+}
@@ -0,0 +1,191 @@
+//! The API of the unmaintained [ableton-link](https://crates.io/crates/ableton-link) crate,
+//! implemented on top of [AblLink](crate::AblLink).
+//!
+//! Projects using `ableton-link` can switch by replacing `use ableton_link::...` with
+//! `use rusty_link::compat::ableton_link::...`. Differences:
+//!
+//! - Link's tick based `Clock` is not exposed by `abl_link`, [Clock] only provides
+//!   [Clock::micros].
+//! - Callbacks take `fn` pointers like before, closures can be registered on the underlying
+//!   instance, see [Link::inner].
+//! - Times are `i64` microseconds like before and converted for the `u64` based functions
+//!   of `abl_link`. Negative times are clamped to zero.
+
+use crate::AblLink;
+
+/// A Link instance, see [AblLink].
+pub struct Link {
+    link: AblLink,
+}
+
+impl Link {
+    pub fn new(bpm: f64) -> Link {
+        Link {
+            link: AblLink::new(bpm),
+        }
+    }
+
+    /// The underlying [AblLink] instance, for everything this shim does not cover.
+    pub fn inner(&self) -> &AblLink {
+        &self.link
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.link.is_enabled()
+    }
+
+    pub fn enable(&mut self, enable: bool) {
+        self.link.enable(enable)
+    }
+
+    pub fn is_start_stop_sync_enabled(&self) -> bool {
+        self.link.is_start_stop_sync_enabled()
+    }
+
+    pub fn enable_start_stop_sync(&mut self, enable: bool) {
+        self.link.enable_start_stop_sync(enable)
+    }
+
+    pub fn num_peers(&self) -> usize {
+        self.link.num_peers() as usize
+    }
+
+    pub fn set_num_peers_callback(&mut self, callback: fn(usize)) {
+        self.link
+            .set_num_peers_callback(move |num_peers| callback(num_peers as usize))
+    }
+
+    pub fn set_tempo_callback(&mut self, callback: fn(f64)) {
+        self.link.set_tempo_callback(callback)
+    }
+
+    pub fn set_start_stop_callback(&mut self, callback: fn(bool)) {
+        self.link.set_start_stop_callback(callback)
+    }
+
+    pub fn clock(&self) -> Clock<'_> {
+        Clock { link: &self.link }
+    }
+
+    /// Capture the audio Session State and pass it to `f`. Audio thread only.
+    pub fn with_audio_session_state<F: FnMut(SessionState)>(&self, mut f: F) {
+        let mut session_state = SessionState::new();
+        self.link
+            .capture_audio_session_state(&mut session_state.session_state);
+        f(session_state)
+    }
+
+    /// Audio thread only.
+    pub fn commit_audio_session_state(&mut self, session_state: SessionState) {
+        self.link
+            .commit_audio_session_state(&session_state.session_state)
+    }
+
+    /// Capture the app Session State and pass it to `f`.
+    pub fn with_app_session_state<F: FnMut(SessionState)>(&self, mut f: F) {
+        let mut session_state = SessionState::new();
+        self.link
+            .capture_app_session_state(&mut session_state.session_state);
+        f(session_state)
+    }
+
+    pub fn commit_app_session_state(&mut self, session_state: SessionState) {
+        self.link
+            .commit_app_session_state(&session_state.session_state)
+    }
+}
+
+/// The Link clock, see [AblLink::clock_micros].
+pub struct Clock<'a> {
+    link: &'a AblLink,
+}
+
+impl Clock<'_> {
+    pub fn micros(&self) -> i64 {
+        self.link.clock_micros()
+    }
+}
+
+/// A Session State, see [crate::SessionState].
+pub struct SessionState {
+    session_state: crate::SessionState,
+}
+
+impl SessionState {
+    fn new() -> SessionState {
+        SessionState {
+            session_state: crate::SessionState::new(),
+        }
+    }
+
+    /// The underlying [crate::SessionState].
+    pub fn inner(&self) -> &crate::SessionState {
+        &self.session_state
+    }
+
+    pub fn tempo(&self) -> f64 {
+        self.session_state.tempo()
+    }
+
+    pub fn set_tempo(&mut self, bpm: f64, at_time: i64) {
+        self.session_state.set_tempo(bpm, at_time)
+    }
+
+    pub fn beat_at_time(&self, time: i64, quantum: f64) -> f64 {
+        self.session_state.beat_at_time(time, quantum)
+    }
+
+    pub fn phase_at_time(&self, time: i64, quantum: f64) -> f64 {
+        self.session_state.phase_at_time(time, quantum)
+    }
+
+    pub fn time_at_beat(&self, beat: f64, quantum: f64) -> i64 {
+        self.session_state.time_at_beat(beat, quantum)
+    }
+
+    pub fn request_beat_at_time(&mut self, beat: f64, time: i64, quantum: f64) {
+        self.session_state.request_beat_at_time(beat, time, quantum)
+    }
+
+    pub fn force_beat_at_time(&mut self, beat: f64, time: i64, quantum: f64) {
+        self.session_state
+            .force_beat_at_time(beat, unsigned(time), quantum)
+    }
+
+    pub fn set_is_playing(&mut self, is_playing: bool, time: i64) {
+        self.session_state
+            .set_is_playing(is_playing, unsigned(time))
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.session_state.is_playing()
+    }
+
+    pub fn time_for_is_playing(&self) -> i64 {
+        self.session_state.time_for_is_playing() as i64
+    }
+
+    pub fn request_beat_at_start_playing_time(&mut self, beat: f64, quantum: f64) {
+        self.session_state
+            .request_beat_at_start_playing_time(beat, quantum)
+    }
+
+    pub fn set_is_playing_and_request_beat_at_time(
+        &mut self,
+        is_playing: bool,
+        time: i64,
+        beat: f64,
+        quantum: f64,
+    ) {
+        self.session_state.set_is_playing_and_request_beat_at_time(
+            is_playing,
+            unsigned(time),
+            beat,
+            quantum,
+        )
+    }
+}
+
+fn unsigned(time: i64) -> u64 {
+    time.max(0) as u64
+}
@@ -0,0 +1,5 @@
+//! Compatibility shims for projects migrating from other Link bindings (feature `compat`).
+//!
+//! - [ableton_link]: the API of the unmaintained `ableton-link` crate (ableton-link-rs)
+
+pub mod ableton_link;
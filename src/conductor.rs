@@ -0,0 +1,103 @@
+use crate::{AblLink, SessionState};
+use std::time::{Duration, Instant};
+
+/// What [Conductor::update] did.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Correction {
+    /// The session was within the tolerance of the reference.
+    InSync { error: f64 },
+    /// The beat was forced to the reference. `error` is the session beat minus the
+    /// reference beat before the correction.
+    Forced { error: f64 },
+    /// The error exceeded [Conductor::max_correction], the reference is assumed to be broken.
+    Rejected { error: f64 },
+    /// The last correction was less than [Conductor::min_interval] ago.
+    RateLimited,
+}
+
+/// Pins the session grid to an external reference, e.g. a show clock, by forcing the
+/// beat with [SessionState::force_beat_at_time].
+///
+/// Forcing the beat overrides the consensus of the session for all peers, which is only
+/// appropriate if this peer is the authoritative master, e.g. in theatre or broadcast.
+/// Corrections are rate limited and implausibly large ones are rejected.
+pub struct Conductor<R: FnMut(i64) -> f64> {
+    /// The reference beat at a Link clock time.
+    reference: R,
+    pub quantum: f64,
+    /// Errors up to this many beats are left alone.
+    pub tolerance: f64,
+    /// Errors of more than this many beats are rejected instead of corrected.
+    pub max_correction: f64,
+    /// Shortest time between two corrections.
+    pub min_interval: Duration,
+    last_correction: Option<Instant>,
+    session_state: SessionState,
+}
+
+impl<R: FnMut(i64) -> f64> Conductor<R> {
+    /// A conductor following `reference`, which returns the beat the session should be at
+    /// for a Link clock time.
+    pub fn new(quantum: f64, reference: R) -> Self {
+        Self {
+            reference,
+            quantum,
+            tolerance: 0.01,
+            max_correction: quantum,
+            min_interval: Duration::from_secs(1),
+            last_correction: None,
+            session_state: SessionState::new(),
+        }
+    }
+
+    pub fn with_tolerance(mut self, beats: f64) -> Self {
+        self.tolerance = beats;
+        self
+    }
+
+    pub fn with_max_correction(mut self, beats: f64) -> Self {
+        self.max_correction = beats;
+        self
+    }
+
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Compare the session with the reference and force the beat of the app Session
+    /// State of `link`, if needed. Call it regularly, e.g. every 100ms.
+    pub fn update(&mut self, link: &AblLink) -> Correction {
+        self.update_at(link, link.clock_micros())
+    }
+
+    /// Same as [Conductor::update], at the Link clock time `now` instead of the current
+    /// time, e.g. the time of the reference clock's last tick.
+    pub fn update_at(&mut self, link: &AblLink, now: i64) -> Correction {
+        if self
+            .last_correction
+            .is_some_and(|last| last.elapsed() < self.min_interval)
+        {
+            return Correction::RateLimited;
+        }
+
+        link.capture_app_session_state(&mut self.session_state);
+        let reference_beat = (self.reference)(now);
+        let error = self.session_state.beat_at_time(now, self.quantum) - reference_beat;
+
+        if error.abs() <= self.tolerance {
+            return Correction::InSync { error };
+        }
+        if error.is_nan() || error.abs() > self.max_correction {
+            debug!(error, "conductor rejected correction");
+            return Correction::Rejected { error };
+        }
+
+        debug!(error, "conductor forces beat");
+        self.session_state
+            .force_beat_at_time(reference_beat, now.max(0) as u64, self.quantum);
+        link.commit_app_session_state(&self.session_state);
+        self.last_correction = Some(Instant::now());
+        Correction::Forced { error }
+    }
+}
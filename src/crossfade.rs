@@ -0,0 +1,88 @@
+use crate::SessionState;
+use std::f64::consts::FRAC_PI_2;
+
+/// Shape of the gain curves of a [Crossfade].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FadeCurve {
+    /// Gains add up to 1, with a dip in loudness at the middle for uncorrelated signals.
+    Linear,
+    /// Squared gains add up to 1, keeping the loudness of uncorrelated signals constant.
+    #[default]
+    EqualPower,
+}
+
+/// Crossfade between an outgoing and an incoming signal over a number of beats, e.g. for
+/// DJ-style transitions between two tracks.
+///
+/// Like [Ramp](crate::Ramp), the crossfade is evaluated against beats of the Link timeline,
+/// so it starts and ends on the grid, regardless of tempo changes during the transition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crossfade {
+    /// Length of the crossfade in beats.
+    pub beats: f64,
+    /// Beat at which the crossfade starts.
+    pub start: f64,
+    pub curve: FadeCurve,
+}
+
+impl Crossfade {
+    /// An equal power crossfade over `n_beats` beats, starting at beat 0.
+    pub fn over_beats(n_beats: f64) -> Self {
+        Self {
+            beats: n_beats,
+            start: 0.,
+            curve: FadeCurve::default(),
+        }
+    }
+
+    /// Start the crossfade at `beat`.
+    pub fn starting_at(mut self, beat: f64) -> Self {
+        self.start = beat;
+        self
+    }
+
+    pub fn with_curve(mut self, curve: FadeCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Start the crossfade at the beginning of the next bar after `time`, for a bar of
+    /// `quantum` beats.
+    pub fn start_at_next_bar(&mut self, session_state: &SessionState, time: i64, quantum: f64) {
+        let beat = session_state.beat_at_time(time, quantum);
+        self.start = (beat / quantum).floor() * quantum + quantum;
+    }
+
+    /// Gains of the outgoing and the incoming signal at `beat`: `(1, 0)` before the start,
+    /// `(0, 1)` after the end.
+    pub fn gains_at_beat(&self, beat: f64) -> (f64, f64) {
+        let progress = self.progress(beat);
+        match self.curve {
+            FadeCurve::Linear => (1. - progress, progress),
+            FadeCurve::EqualPower => ((progress * FRAC_PI_2).cos(), (progress * FRAC_PI_2).sin()),
+        }
+    }
+
+    /// Gains of the outgoing and the incoming signal at `time` according to `session_state`.
+    pub fn gains_at_time(
+        &self,
+        session_state: &SessionState,
+        time: i64,
+        quantum: f64,
+    ) -> (f64, f64) {
+        self.gains_at_beat(session_state.beat_at_time(time, quantum))
+    }
+
+    /// Progress at `beat`, from 0 at the start to 1 at the end.
+    pub fn progress(&self, beat: f64) -> f64 {
+        if self.beats <= 0. {
+            return if beat >= self.start { 1. } else { 0. };
+        }
+        ((beat - self.start) / self.beats).clamp(0., 1.)
+    }
+
+    /// Has the crossfade reached its end at `beat`?
+    pub fn is_finished(&self, beat: f64) -> bool {
+        beat >= self.start + self.beats
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{
+    callbacks::{CallbackValue, Callbacks},
+    AblLink, ThreadConfig, EVENT_QUEUE_CAPACITY,
+};
+use std::{
+    sync::mpsc::{self, Receiver, SyncSender},
+    thread::JoinHandle,
+};
+
+/// Where a callback registered with [AblLink::set_tempo_callback_with] and friends is
+/// invoked, e.g. inline for MIDI output reacting right away and queued for a UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum Delivery {
+    /// On the Link-managed thread reporting the change. The closure must not block Link.
+    #[default]
+    Inline,
+    /// On a dispatcher thread of the instance, in order of the changes. A slow closure
+    /// only delays later callbacks, never Link. While the dispatcher is
+    /// [EVENT_QUEUE_CAPACITY] values behind, new values are dropped.
+    Dispatcher,
+    /// Queued until [AblLink::run_queued_callbacks] is called, e.g. from the audio thread
+    /// once per buffer. The queue holds up to [EVENT_QUEUE_CAPACITY] values, older values
+    /// are dropped.
+    Queued,
+}
+
+impl Delivery {
+    pub(crate) fn from_u8(value: u8) -> Delivery {
+        match value {
+            1 => Delivery::Dispatcher,
+            2 => Delivery::Queued,
+            _ => Delivery::Inline,
+        }
+    }
+}
+
+/// A value passed by Link, on its way to the dispatcher thread.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Dispatched {
+    NumPeers(u64),
+    Tempo(f64),
+    StartStop(bool),
+}
+
+/// Pointer to the [Callbacks] of an instance, which outlive the dispatcher thread.
+struct CallbacksPtr(*const Callbacks);
+
+// The dispatcher is joined before the rest of the callbacks is dropped
+unsafe impl Send for CallbacksPtr {}
+
+/// Thread invoking callbacks with [Delivery::Dispatcher].
+pub(crate) struct Dispatcher {
+    sender: Option<SyncSender<Dispatched>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Dispatcher {
+    /// Start the dispatcher thread. `callbacks` has to outlive the dispatcher.
+    pub fn start(callbacks: &Callbacks, config: &ThreadConfig) -> Dispatcher {
+        let (sender, receiver) = mpsc::sync_channel(EVENT_QUEUE_CAPACITY);
+        let callbacks = CallbacksPtr(callbacks);
+
+        let handle = config.spawn("callbacks", move || {
+            let callbacks = callbacks;
+            dispatch(unsafe { &*callbacks.0 }, receiver)
+        });
+
+        Dispatcher {
+            sender: Some(sender),
+            handle: Some(handle),
+        }
+    }
+
+    /// Called from Link-managed threads. Never blocks.
+    pub fn send(&self, value: Dispatched) {
+        if let Some(sender) = &self.sender {
+            if sender.try_send(value).is_err() {
+                trace!(?value, "dispatcher is behind, dropped callback value");
+            }
+        }
+    }
+}
+
+impl Drop for Dispatcher {
+    fn drop(&mut self) {
+        // Closing the channel ends the thread
+        drop(self.sender.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn dispatch(callbacks: &Callbacks, receiver: Receiver<Dispatched>) {
+    for value in receiver {
+        match value {
+            Dispatched::NumPeers(value) => callbacks.num_peers.invoke(value),
+            Dispatched::Tempo(value) => callbacks.tempo.invoke(value),
+            Dispatched::StartStop(value) => callbacks.start_stop.invoke(value),
+        }
+    }
+}
+
+impl AblLink {
+    /// [AblLink::set_num_peers_callback] with the thread the closure is invoked on.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn set_num_peers_callback_with<C: FnMut(u64) + Send + 'static>(
+        &self,
+        delivery: Delivery,
+        closure: C,
+    ) {
+        self.set_callback_with::<u64>(delivery, Box::new(closure))
+    }
+
+    /// [AblLink::set_tempo_callback] with the thread the closure is invoked on.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn set_tempo_callback_with<C: FnMut(f64) + Send + 'static>(
+        &self,
+        delivery: Delivery,
+        closure: C,
+    ) {
+        self.set_callback_with::<f64>(delivery, Box::new(closure))
+    }
+
+    /// [AblLink::set_start_stop_callback] with the thread the closure is invoked on.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn set_start_stop_callback_with<C: FnMut(bool) + Send + 'static>(
+        &self,
+        delivery: Delivery,
+        closure: C,
+    ) {
+        self.set_callback_with::<bool>(delivery, Box::new(closure))
+    }
+
+    /// Invoke the callbacks registered with [Delivery::Queued] on the calling thread, once
+    /// per queued value, oldest first. Returns the number of invocations.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes, if the closures are
+    ///
+    /// Never waits for a closure or its replacement: values stay queued for the next call,
+    /// if they arrive while the queue is being drained, if a callback is being replaced or
+    /// invoked on another thread at the same time, or after a closure replaced itself.
+    pub fn run_queued_callbacks(&self) -> usize {
+        self.callbacks.run_queued::<u64>()
+            + self.callbacks.run_queued::<f64>()
+            + self.callbacks.run_queued::<bool>()
+    }
+
+    fn set_callback_with<T: CallbackValue>(
+        &self,
+        delivery: Delivery,
+        closure: Box<dyn FnMut(T) + Send>,
+    ) {
+        T::slot(&self.callbacks).set(closure);
+        self.callbacks.set_delivery::<T>(delivery);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{AblLink, SessionState};
+use std::{fmt, time::Duration};
+
+/// Quantum used for beat and phase in [Diagnostics].
+const DIAGNOSTICS_QUANTUM: f64 = 4.;
+
+/// Summary of the state of an [AblLink] instance, returned by [AblLink::diagnostics].
+///
+/// The [Display](fmt::Display) output is meant to be attached to bug reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics {
+    pub enabled: bool,
+    pub offline: bool,
+    pub num_peers: u64,
+    pub tempo: f64,
+    /// Beat at the time of the diagnostics, for a quantum of 4.
+    pub beat: f64,
+    /// Phase at the time of the diagnostics, for a quantum of 4.
+    pub phase: f64,
+    pub is_playing: bool,
+    pub start_stop_sync: bool,
+    pub output_latency: Duration,
+    /// See [AblLink::dropped_audio_commits].
+    pub dropped_audio_commits: u64,
+    /// See [AblLink::refused_commits].
+    pub refused_commits: u64,
+    pub num_peers_callback: bool,
+    pub tempo_callback: bool,
+    pub start_stop_callback: bool,
+    /// Link clock in microseconds.
+    pub time: i64,
+    pub crate_version: &'static str,
+    /// Version of the bundled Link sources, `unknown` if it could not be determined at build time.
+    pub link_version: &'static str,
+}
+
+impl AblLink {
+    /// Summarize the state of this instance, e.g. for bug reports.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn diagnostics(&self) -> Diagnostics {
+        let mut session_state = SessionState::new();
+        self.capture_app_session_state(&mut session_state);
+        let time = self.clock_micros();
+        let (num_peers_callback, tempo_callback, start_stop_callback) = self.registered_callbacks();
+
+        Diagnostics {
+            enabled: self.is_enabled(),
+            offline: self.is_offline(),
+            num_peers: self.num_peers(),
+            tempo: session_state.tempo(),
+            beat: session_state.beat_at_time(time, DIAGNOSTICS_QUANTUM),
+            phase: session_state.phase_at_time(time, DIAGNOSTICS_QUANTUM),
+            is_playing: session_state.is_playing(),
+            start_stop_sync: self.is_start_stop_sync_enabled(),
+            output_latency: self.output_latency(),
+            dropped_audio_commits: self.dropped_audio_commits(),
+            refused_commits: self.refused_commits(),
+            num_peers_callback,
+            tempo_callback,
+            start_stop_callback,
+            time,
+            crate_version: env!("CARGO_PKG_VERSION"),
+            link_version: option_env!("RUSTY_LINK_LINK_VERSION").unwrap_or("unknown"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yes_no = |value: bool| if value { "yes" } else { "no" };
+
+        writeln!(
+            f,
+            "rusty_link {} (Link {})",
+            self.crate_version, self.link_version
+        )?;
+        writeln!(f, "enabled:          {}", yes_no(self.enabled))?;
+        writeln!(f, "offline:          {}", yes_no(self.offline))?;
+        writeln!(f, "peers:            {}", self.num_peers)?;
+        writeln!(f, "tempo:            {:.2} bpm", self.tempo)?;
+        writeln!(f, "beat:             {:.2}", self.beat)?;
+        writeln!(
+            f,
+            "phase:            {:.2}/{}",
+            self.phase, DIAGNOSTICS_QUANTUM
+        )?;
+        writeln!(f, "playing:          {}", yes_no(self.is_playing))?;
+        writeln!(f, "start/stop sync:  {}", yes_no(self.start_stop_sync))?;
+        writeln!(
+            f,
+            "output latency:   {:.1}ms",
+            self.output_latency.as_secs_f64() * 1e3
+        )?;
+        writeln!(f, "dropped commits:  {}", self.dropped_audio_commits)?;
+        writeln!(f, "refused commits:  {}", self.refused_commits)?;
+        writeln!(
+            f,
+            "callbacks:        num_peers {}, tempo {}, start_stop {}",
+            yes_no(self.num_peers_callback),
+            yes_no(self.tempo_callback),
+            yes_no(self.start_stop_callback)
+        )?;
+        write!(f, "link clock:       {}us", self.time)
+    }
+}
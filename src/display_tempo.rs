@@ -0,0 +1,71 @@
+use std::fmt;
+
+/// Tempo for display in UIs, rounded and filtered with hysteresis.
+///
+/// Link refines the tempo of a session continuously, so the raw value jitters around the
+/// nominal tempo, e.g. 119.998 and 120.003 instead of 120. A `DisplayTempo` only changes
+/// the shown value once the raw tempo moved further than half a display step plus the
+/// hysteresis away from it. Use the raw value for scheduling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayTempo {
+    decimals: u32,
+    /// Additional distance in bpm the raw tempo has to move before the shown value changes.
+    pub hysteresis: f64,
+    shown: Option<f64>,
+}
+
+impl Default for DisplayTempo {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+impl DisplayTempo {
+    /// A display tempo with `decimals` digits after the decimal point and a hysteresis of
+    /// half a display step.
+    pub fn new(decimals: u32) -> Self {
+        Self {
+            decimals,
+            hysteresis: step(decimals) / 2.,
+            shown: None,
+        }
+    }
+
+    pub fn with_hysteresis(mut self, hysteresis: f64) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Update with the raw tempo and return the tempo to show.
+    pub fn update(&mut self, bpm: f64) -> f64 {
+        let step = step(self.decimals);
+        let shown = match self.shown {
+            Some(shown) if (bpm - shown).abs() <= step / 2. + self.hysteresis => shown,
+            _ => {
+                let scale = 10f64.powi(self.decimals as i32);
+                (bpm * scale).round() / scale
+            }
+        };
+        self.shown = Some(shown);
+        shown
+    }
+
+    /// The tempo to show, `None` before the first update.
+    pub fn get(&self) -> Option<f64> {
+        self.shown
+    }
+}
+
+fn step(decimals: u32) -> f64 {
+    10f64.powi(-(decimals as i32))
+}
+
+/// The shown tempo with the configured decimals, e.g. `120.00`, or `-` before the first update.
+impl fmt::Display for DisplayTempo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.shown {
+            Some(bpm) => write!(f, "{:.*}", self.decimals as usize, bpm),
+            None => write!(f, "-"),
+        }
+    }
+}
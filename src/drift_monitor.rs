@@ -0,0 +1,122 @@
+use crate::{AblLink, SessionState};
+use std::fmt;
+
+/// Measures long-term drift and jitter of the Link timeline.
+///
+/// Every sample predicts the current beat from the previous sample and the tempo
+/// of the session, then compares it with the beat observed in the captured app Session
+/// State. Call [DriftMonitor::sample] periodically (e.g. every 100ms) over hours and
+/// attach the [DriftReport] to issues about drift.
+pub struct DriftMonitor {
+    quantum: f64,
+    session_state: SessionState,
+    last: Option<Reference>,
+    stats: DriftReport,
+}
+
+struct Reference {
+    time: i64,
+    beat: f64,
+    tempo: f64,
+}
+
+/// Drift and jitter statistics collected by a [DriftMonitor]. All values in microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DriftReport {
+    /// Number of compared samples.
+    pub samples: u64,
+    /// Time covered by the compared samples.
+    pub duration: i64,
+    /// Average difference between observed and predicted beat time.
+    pub mean_error: f64,
+    /// Standard deviation of the difference between observed and predicted beat time.
+    pub jitter: f64,
+    /// Largest absolute difference between observed and predicted beat time.
+    pub max_error: f64,
+    /// Sum of all differences, the accumulated drift over the whole duration.
+    pub total_drift: f64,
+    sum_squares: f64,
+}
+
+impl DriftMonitor {
+    pub fn new(quantum: f64) -> Self {
+        Self {
+            quantum,
+            session_state: SessionState::new(),
+            last: None,
+            stats: DriftReport::default(),
+        }
+    }
+
+    /// Capture the app Session State of `link` and compare it with the prediction from
+    /// the previous sample. Returns the error of this sample in microseconds, if there
+    /// was a prediction to compare with.
+    ///
+    /// Samples across tempo changes are not compared, the tempo change only moves the reference.
+    pub fn sample(&mut self, link: &AblLink) -> Option<f64> {
+        self.sample_at(link, link.clock_micros())
+    }
+
+    /// Same as [DriftMonitor::sample], at the Link clock time `time` instead of the current
+    /// time, e.g. a virtual time in tests.
+    pub fn sample_at(&mut self, link: &AblLink, time: i64) -> Option<f64> {
+        link.capture_app_session_state(&mut self.session_state);
+        let beat = self.session_state.beat_at_time(time, self.quantum);
+        let tempo = self.session_state.tempo();
+
+        let error = match &self.last {
+            Some(last) if last.tempo == tempo => {
+                let predicted = last.beat + (time - last.time) as f64 * tempo / 60e6;
+                let error = (beat - predicted) * 60e6 / tempo;
+                self.stats.add(error, time - last.time);
+                Some(error)
+            }
+            _ => None,
+        };
+
+        self.last = Some(Reference { time, beat, tempo });
+        error
+    }
+
+    /// Statistics of all samples since creation or the last [DriftMonitor::reset].
+    pub fn report(&self) -> DriftReport {
+        self.stats
+    }
+
+    /// Clear all collected statistics.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.stats = DriftReport::default();
+    }
+}
+
+impl DriftReport {
+    fn add(&mut self, error: f64, elapsed: i64) {
+        self.samples += 1;
+        self.duration += elapsed;
+        self.total_drift += error;
+        self.sum_squares += error * error;
+        self.max_error = self.max_error.max(error.abs());
+
+        let n = self.samples as f64;
+        self.mean_error = self.total_drift / n;
+        self.jitter = (self.sum_squares / n - self.mean_error * self.mean_error)
+            .max(0.)
+            .sqrt();
+    }
+}
+
+impl fmt::Display for DriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} samples over {:.1}s | mean error {:.2}us | jitter {:.2}us | max error {:.2}us | total drift {:.2}us",
+            self.samples,
+            self.duration as f64 / 1e6,
+            self.mean_error,
+            self.jitter,
+            self.max_error,
+            self.total_drift
+        )
+    }
+}
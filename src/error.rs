@@ -0,0 +1,72 @@
+use std::{io, time::Duration};
+
+/// Lowest tempo supported by Link, in Beats Per Minute. Link clamps lower tempi.
+pub const MIN_TEMPO: f64 = 20.;
+/// Highest tempo supported by Link, in Beats Per Minute. Link clamps higher tempi.
+pub const MAX_TEMPO: f64 = 999.;
+
+/// Errors of the fallible APIs of this crate.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The tempo is outside of [MIN_TEMPO]..=[MAX_TEMPO] or not a number.
+    #[error("tempo {bpm} bpm is out of range ({MIN_TEMPO}..={MAX_TEMPO} bpm)")]
+    TempoOutOfRange { bpm: f64 },
+
+    /// The quantum is not a positive, finite number of beats.
+    #[error("invalid quantum {quantum}, expected a positive number of beats")]
+    InvalidQuantum { quantum: f64 },
+
+    /// A function was called from a thread it must not be called from, e.g. a Session
+    /// State captured for the app thread was committed from the audio thread, see
+    /// [AblLink::check_capture](crate::AblLink::check_capture).
+    #[error("called from the wrong thread, expected the {expected} thread")]
+    WrongThread { expected: &'static str },
+
+    /// A Session State was captured more than [STALE_SESSION_STATE_AGE](crate::STALE_SESSION_STATE_AGE) ago.
+    #[error("Session State was captured {age:?} ago")]
+    StaleSessionState { age: Duration },
+
+    /// A background thread of the crate has already shut down.
+    #[error("{0} has shut down")]
+    Shutdown(&'static str),
+
+    /// [LinkRuntime::shutdown](crate::LinkRuntime::shutdown) did not finish in time.
+    #[error("shutdown timed out, still stopping: {}", pending.join(", "))]
+    ShutdownTimedOut { pending: Vec<&'static str> },
+
+    /// A file or stream in the recording format could not be parsed.
+    #[error("invalid recording: {0}")]
+    InvalidRecording(String),
+
+    /// A settings file could not be parsed.
+    #[error("invalid settings: {0}")]
+    InvalidSettings(String),
+
+    /// A network message could not be decoded.
+    #[error("invalid message: {0}")]
+    InvalidMessage(&'static str),
+
+    /// A transition of [Transport](crate::Transport) which is not possible in its state.
+    #[error("can not {action} transport, it is {state}")]
+    InvalidTransition {
+        action: &'static str,
+        state: crate::Transport,
+    },
+
+    /// I/O error, e.g. of files or network bridges.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Result type of the fallible APIs of this crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Check that `bpm` is a tempo supported by Link.
+pub(crate) fn check_tempo(bpm: f64) -> Result<f64> {
+    if (MIN_TEMPO..=MAX_TEMPO).contains(&bpm) {
+        Ok(bpm)
+    } else {
+        Err(Error::TempoOutOfRange { bpm })
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    collections::VecDeque,
+    mem::discriminant,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, TryLockError,
+    },
+    time::SystemTime,
+};
+
+/// Number of events the queue of an [AblLink](crate::AblLink) instance holds, before the
+/// oldest events are dropped.
+pub const EVENT_QUEUE_CAPACITY: usize = 64;
+
+/// Number of peer joins and leaves kept by an [AblLink](crate::AblLink) instance, see
+/// [AblLink::peer_history](crate::AblLink::peer_history).
+pub const PEER_HISTORY_CAPACITY: usize = 16;
+
+/// Origin of a [LinkEvent::TempoChanged].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempoSource {
+    /// The tempo was committed by this instance.
+    Local,
+    /// The tempo was changed by a peer.
+    Remote,
+}
+
+/// A change of the Link session, reported by [AblLink::poll_events](crate::AblLink::poll_events).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkEvent {
+    NumPeersChanged {
+        num_peers: u64,
+    },
+    TempoChanged {
+        bpm: f64,
+        source: TempoSource,
+    },
+    StartStopChanged {
+        is_playing: bool,
+    },
+    /// The number of peers increased, reported right after the [LinkEvent::NumPeersChanged].
+    PeerJoined {
+        num_peers: u64,
+    },
+    /// The number of peers decreased, reported right after the [LinkEvent::NumPeersChanged].
+    PeerLeft {
+        num_peers: u64,
+    },
+    /// The quantum of the app changed, see [AblLink::set_quantum](crate::AblLink::set_quantum).
+    /// Not reported by Link, the quantum is local to this instance.
+    QuantumChanged {
+        quantum: f64,
+    },
+    /// Start/stop sync was enabled or disabled, see
+    /// [AblLink::enable_start_stop_sync](crate::AblLink::enable_start_stop_sync).
+    /// Not reported by Link, the setting is local to this instance.
+    StartStopSyncChanged {
+        enabled: bool,
+    },
+    /// The audio Session State was not captured for longer than the timeout of an
+    /// [AudioWatchdog](crate::AudioWatchdog) while transport is playing, e.g. because the
+    /// audio device died. `last_capture` is the Link clock time of the last capture.
+    AudioStalled {
+        last_capture: i64,
+    },
+    /// The audio Session State is captured again after [LinkEvent::AudioStalled].
+    AudioResumed,
+    /// The Link multicast group became reachable or unreachable, reported by a
+    /// [NetworkMonitor](crate::NetworkMonitor).
+    NetworkChanged {
+        available: bool,
+    },
+}
+
+/// Bounded queue of [LinkEvent]s, filled from the callbacks invoked by Link.
+///
+/// The queue is allocated once, pushing never allocates. With coalescing enabled, a new
+/// event replaces a queued event of the same kind, so every poll only sees the latest value
+/// of each kind, e.g. one tempo change instead of dozens while Link refines the tempo.
+/// Events are polled in the order of their latest occurrence.
+pub(crate) struct EventQueue {
+    events: Mutex<VecDeque<LinkEvent>>,
+    coalesce: AtomicBool,
+}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self {
+            events: Mutex::new(VecDeque::with_capacity(EVENT_QUEUE_CAPACITY)),
+            coalesce: AtomicBool::new(true),
+        }
+    }
+}
+
+impl EventQueue {
+    pub fn set_coalescing(&self, coalesce: bool) {
+        self.coalesce.store(coalesce, Ordering::Release);
+    }
+
+    pub fn is_coalescing(&self) -> bool {
+        self.coalesce.load(Ordering::Acquire)
+    }
+
+    /// Called from Link-managed threads.
+    pub fn push(&self, event: LinkEvent) {
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+
+        // The replaced event moves to the back, so events of different kinds stay in order,
+        // e.g. a peer leaving and joining again ends with the join
+        if self.is_coalescing() {
+            let kind = discriminant(&event);
+            if let Some(index) = events.iter().position(|e| discriminant(e) == kind) {
+                events.remove(index);
+            }
+        }
+
+        if events.len() == EVENT_QUEUE_CAPACITY {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Hand all queued events to `f`, oldest first. Never blocks: returns early, if a
+    /// Link-managed thread is pushing an event at the same time.
+    pub fn poll<F: FnMut(LinkEvent)>(&self, mut f: F) {
+        loop {
+            let event = match self.events.try_lock() {
+                Ok(mut events) => events.pop_front(),
+                Err(TryLockError::Poisoned(e)) => e.into_inner().pop_front(),
+                Err(TryLockError::WouldBlock) => None,
+            };
+
+            match event {
+                // Call `f` outside of the lock, so Link is never blocked by user code
+                Some(event) => f(event),
+                None => break,
+            }
+        }
+    }
+}
+
+/// A [LinkEvent] with the time it was reported by Link.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimedEvent {
+    /// Link clock in microseconds.
+    pub time: i64,
+    /// Wall clock time, to match the event with user reports and other logs.
+    pub system_time: SystemTime,
+    pub event: LinkEvent,
+}
+
+/// Bounded history of the last events, disabled (capacity 0) by default.
+///
+/// Unlike [EventQueue], the history is never drained and events are not coalesced.
+#[derive(Default)]
+pub(crate) struct EventHistory {
+    inner: Mutex<History>,
+}
+
+#[derive(Default)]
+struct History {
+    events: VecDeque<TimedEvent>,
+    capacity: usize,
+}
+
+impl EventHistory {
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut history = self.lock();
+        history.capacity = capacity;
+
+        let excess = history.events.len().saturating_sub(capacity);
+        history.events.drain(..excess);
+        // Allocate up front, so `push` never allocates
+        let additional = capacity - history.events.len();
+        history.events.reserve_exact(additional);
+        history.events.shrink_to(capacity);
+    }
+
+    /// Called from Link-managed threads.
+    pub fn push(&self, event: TimedEvent) {
+        let mut history = self.lock();
+        if history.capacity == 0 {
+            return;
+        }
+
+        if history.events.len() == history.capacity {
+            history.events.pop_front();
+        }
+        history.events.push_back(event);
+    }
+
+    pub fn to_vec(&self) -> Vec<TimedEvent> {
+        self.lock().events.iter().copied().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, History> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
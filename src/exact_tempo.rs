@@ -0,0 +1,133 @@
+use std::fmt;
+
+const MICROS_PER_MINUTE: u128 = 60_000_000;
+
+/// A tempo as an exact fraction of beats per minute, e.g. `1001/8` bpm.
+///
+/// Link represents tempo as an `f64`. Sequencers which add up beat durations over hours
+/// accumulate rounding errors in floating point. With an exact tempo, the time of every
+/// tick is computed from the tick count directly, rounding only once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExactTempo {
+    numerator: u64,
+    denominator: u64,
+}
+
+impl ExactTempo {
+    /// `numerator / denominator` beats per minute, `None` if either is 0.
+    pub fn new(numerator: u64, denominator: u64) -> Option<Self> {
+        if numerator == 0 || denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator, denominator);
+        Some(Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        })
+    }
+
+    /// A whole number of beats per minute, `None` if 0.
+    pub fn from_bpm(bpm: u64) -> Option<Self> {
+        Self::new(bpm, 1)
+    }
+
+    /// The tempo of beats `micros` microseconds long, `None` if 0.
+    pub fn from_micros_per_beat(micros: u64) -> Option<Self> {
+        Self::new(60_000_000, micros)
+    }
+
+    /// A fraction close to `bpm` with a denominator of at most `max_denominator`: the last
+    /// convergent of its continued fraction. `None` if `bpm` is not positive and finite.
+    pub fn from_f64(bpm: f64, max_denominator: u64) -> Option<Self> {
+        if !(bpm.is_finite() && bpm > 0.) || bpm >= u64::MAX as f64 {
+            return None;
+        }
+
+        // Convergents of the continued fraction of `bpm`
+        let (mut p0, mut q0, mut p1, mut q1) = (0u64, 1u64, 1u64, 0u64);
+        let mut x = bpm;
+        loop {
+            let a = x.floor();
+            let (Some(p2), Some(q2)) = (
+                (a as u64).checked_mul(p1).and_then(|p| p.checked_add(p0)),
+                (a as u64).checked_mul(q1).and_then(|q| q.checked_add(q0)),
+            ) else {
+                break;
+            };
+            if q2 > max_denominator.max(1) {
+                break;
+            }
+            (p0, q0, p1, q1) = (p1, q1, p2, q2);
+
+            let fraction = x - a;
+            if fraction < 1e-12 {
+                break;
+            }
+            x = 1. / fraction;
+        }
+
+        Self::new(p1, q1).or_else(|| Self::new(1, max_denominator.max(1)))
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.denominator
+    }
+
+    /// The tempo in beats per minute, as used by Link.
+    pub fn bpm(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Length of a beat in microseconds.
+    pub fn micros_per_beat(&self) -> f64 {
+        MICROS_PER_MINUTE as f64 * self.denominator as f64 / self.numerator as f64
+    }
+
+    /// Microseconds from tick 0 to `ticks`, with `ticks_per_beat`, rounded to the nearest
+    /// microsecond.
+    pub fn micros_for_ticks(&self, ticks: u64, ticks_per_beat: u64) -> u64 {
+        self.scale(ticks, ticks_per_beat, MICROS_PER_MINUTE)
+    }
+
+    /// Audio frames from tick 0 to `ticks`, with `ticks_per_beat`, rounded to the nearest frame.
+    pub fn frames_for_ticks(&self, ticks: u64, ticks_per_beat: u64, sample_rate: u32) -> u64 {
+        self.scale(ticks, ticks_per_beat, 60 * u128::from(sample_rate))
+    }
+
+    /// `ticks / ticks_per_beat` beats in units of `per_minute`, rounded.
+    fn scale(&self, ticks: u64, ticks_per_beat: u64, per_minute: u128) -> u64 {
+        let numerator = u128::from(ticks) * per_minute * u128::from(self.denominator);
+        let denominator = u128::from(ticks_per_beat.max(1)) * u128::from(self.numerator);
+        ((numerator + denominator / 2) / denominator)
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+}
+
+impl From<ExactTempo> for f64 {
+    fn from(tempo: ExactTempo) -> Self {
+        tempo.bpm()
+    }
+}
+
+/// `120 bpm` or `1001/8 bpm`.
+impl fmt::Display for ExactTempo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{} bpm", self.numerator)
+        } else {
+            write!(f, "{}/{} bpm", self.numerator, self.denominator)
+        }
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
@@ -0,0 +1,41 @@
+use crate::rust_bindings::rusty_link_frame_info;
+use std::fmt;
+
+/// Tempo, beat, phase and transport state of the audio Session State at one point in time,
+/// as returned by [AblLink::audio_frame_info](crate::AblLink::audio_frame_info).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameInfo {
+    pub tempo: f64,
+    pub beat: f64,
+    pub phase: f64,
+    pub is_playing: bool,
+}
+
+impl From<rusty_link_frame_info> for FrameInfo {
+    fn from(info: rusty_link_frame_info) -> Self {
+        Self {
+            tempo: info.tempo,
+            beat: info.beat,
+            phase: info.phase,
+            is_playing: info.is_playing,
+        }
+    }
+}
+
+/// e.g. `120.00 bpm | beat 12.34 | phase 0.34 | playing`
+impl fmt::Display for FrameInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2} bpm | beat {:.2} | phase {:.2} | {}",
+            self.tempo,
+            self.beat,
+            self.phase,
+            if self.is_playing {
+                "playing"
+            } else {
+                "stopped"
+            }
+        )
+    }
+}
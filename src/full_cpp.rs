@@ -0,0 +1,366 @@
+//! Direct bindings to the C++ `ableton::Link` class, bypassing the `abl_link` C API.
+//!
+//! The C API has to allocate every Session State on the heap and can not copy them.
+//! Session States of this module are C++ values, which can be cloned and captured
+//! into existing instances without allocating. [Link] and [SessionState] mirror
+//! [AblLink](crate::AblLink) and [crate::SessionState], times are in microseconds
+//! of the Link clock.
+//!
+//! The C++ API also allows Link to run on a custom clock, see [Link::with_clock].
+//!
+//! Requires the `full-cpp` feature, which compiles a [cxx](https://cxx.rs) bridge
+//! (`src/full_cpp.rs` and `cmake/rusty_link_full.cpp`) against the Link headers.
+
+use crate::ClockSource;
+use cxx::UniquePtr;
+use std::fmt;
+
+/// A [ClockSource] passed to C++.
+struct BoxedClockSource(Box<dyn ClockSource>);
+
+fn boxed_clock_source_micros(source: &BoxedClockSource) -> i64 {
+    source.0.micros()
+}
+
+#[cxx::bridge(namespace = "rusty_link")]
+mod ffi {
+    extern "Rust" {
+        type BoxedClockSource;
+
+        fn boxed_clock_source_micros(source: &BoxedClockSource) -> i64;
+    }
+
+    unsafe extern "C++" {
+        include!("rusty_link/cmake/rusty_link_full.h");
+
+        type FullLink;
+        type SessionState;
+
+        fn new_full_link(bpm: f64) -> UniquePtr<FullLink>;
+        fn new_full_link_with_clock(bpm: f64, source: Box<BoxedClockSource>)
+            -> UniquePtr<FullLink>;
+
+        fn full_link_enable(link: &FullLink, enable: bool);
+        fn full_link_is_enabled(link: &FullLink) -> bool;
+        fn full_link_num_peers(link: &FullLink) -> usize;
+        fn full_link_enable_start_stop_sync(link: &FullLink, enable: bool);
+        fn full_link_is_start_stop_sync_enabled(link: &FullLink) -> bool;
+        fn full_link_clock_micros(link: &FullLink) -> i64;
+
+        fn full_link_capture_audio_session_state(link: &FullLink) -> UniquePtr<SessionState>;
+        fn full_link_capture_audio_session_state_into(link: &FullLink, out: Pin<&mut SessionState>);
+        fn full_link_commit_audio_session_state(link: &FullLink, state: &SessionState);
+        fn full_link_capture_app_session_state(link: &FullLink) -> UniquePtr<SessionState>;
+        fn full_link_capture_app_session_state_into(link: &FullLink, out: Pin<&mut SessionState>);
+        fn full_link_commit_app_session_state(link: &FullLink, state: &SessionState);
+
+        fn session_state_clone(state: &SessionState) -> UniquePtr<SessionState>;
+        fn session_state_tempo(state: &SessionState) -> f64;
+        fn session_state_set_tempo(state: Pin<&mut SessionState>, bpm: f64, at_time: i64);
+        fn session_state_beat_at_time(state: &SessionState, time: i64, quantum: f64) -> f64;
+        fn session_state_phase_at_time(state: &SessionState, time: i64, quantum: f64) -> f64;
+        fn session_state_time_at_beat(state: &SessionState, beat: f64, quantum: f64) -> i64;
+        fn session_state_request_beat_at_time(
+            state: Pin<&mut SessionState>,
+            beat: f64,
+            time: i64,
+            quantum: f64,
+        );
+        fn session_state_force_beat_at_time(
+            state: Pin<&mut SessionState>,
+            beat: f64,
+            time: i64,
+            quantum: f64,
+        );
+        fn session_state_set_is_playing(state: Pin<&mut SessionState>, is_playing: bool, time: i64);
+        fn session_state_is_playing(state: &SessionState) -> bool;
+        fn session_state_time_for_is_playing(state: &SessionState) -> i64;
+        fn session_state_request_beat_at_start_playing_time(
+            state: Pin<&mut SessionState>,
+            beat: f64,
+            quantum: f64,
+        );
+        fn session_state_set_is_playing_and_request_beat_at_time(
+            state: Pin<&mut SessionState>,
+            is_playing: bool,
+            time: i64,
+            beat: f64,
+            quantum: f64,
+        );
+    }
+}
+
+/// An `ableton::BasicLink` instance.
+pub struct Link {
+    link: UniquePtr<ffi::FullLink>,
+}
+
+// ableton::Link is thread-safe, the same as abl_link
+unsafe impl Send for Link {}
+unsafe impl Sync for Link {}
+
+impl Link {
+    ///  Construct a new Link instance with an initial tempo.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn new(bpm: f64) -> Link {
+        Link {
+            link: ffi::new_full_link(bpm),
+        }
+    }
+
+    /// Construct a new Link instance, which uses `clock` instead of the platform clock.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// All times of this instance, including [Link::clock_micros], are in the time of
+    /// `clock`. This keeps Link consistent with e.g. the word clock of an audio interface
+    /// or a PTP-disciplined clock. `clock` is read from Link's threads and has to be
+    /// monotonic and realtime-safe.
+    pub fn with_clock(bpm: f64, clock: impl ClockSource + 'static) -> Link {
+        Link {
+            link: ffi::new_full_link_with_clock(bpm, Box::new(BoxedClockSource(Box::new(clock)))),
+        }
+    }
+
+    fn inner(&self) -> &ffi::FullLink {
+        self.link.as_ref().expect("ableton::Link instance")
+    }
+
+    ///  Enable/disable Link.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn enable(&self, enable: bool) {
+        ffi::full_link_enable(self.inner(), enable)
+    }
+
+    /// Is Link currently enabled?
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn is_enabled(&self) -> bool {
+        ffi::full_link_is_enabled(self.inner())
+    }
+
+    /// How many peers are currently connected in a Link session?
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn num_peers(&self) -> usize {
+        ffi::full_link_num_peers(self.inner())
+    }
+
+    /// Enable start/stop synchronization.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn enable_start_stop_sync(&self, enable: bool) {
+        ffi::full_link_enable_start_stop_sync(self.inner(), enable)
+    }
+
+    /// Is start/stop synchronization enabled?
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn is_start_stop_sync_enabled(&self) -> bool {
+        ffi::full_link_is_start_stop_sync_enabled(self.inner())
+    }
+
+    /// Get the current link clock time in microseconds.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn clock_micros(&self) -> i64 {
+        ffi::full_link_clock_micros(self.inner())
+    }
+
+    /// Capture the current Link Session State from the audio thread into a new [SessionState].
+    ///
+    ///  Thread-safe: no
+    ///
+    ///  Realtime-safe: no
+    ///
+    ///  Allocates the returned Session State. Use [Link::capture_audio_session_state_into]
+    ///  in the audio callback.
+    pub fn capture_audio_session_state(&self) -> SessionState {
+        SessionState::from_ptr(ffi::full_link_capture_audio_session_state(self.inner()))
+    }
+
+    /// Capture the current Link Session State from the audio thread into `session_state`.
+    ///
+    ///  Thread-safe: no
+    ///
+    ///  Realtime-safe: yes
+    pub fn capture_audio_session_state_into(&self, session_state: &mut SessionState) {
+        ffi::full_link_capture_audio_session_state_into(self.inner(), session_state.pin_mut())
+    }
+
+    /// Commit the given Session State to the Link session from the audio thread.
+    ///
+    ///  Thread-safe: no
+    ///
+    ///  Realtime-safe: yes
+    pub fn commit_audio_session_state(&self, session_state: &SessionState) {
+        ffi::full_link_commit_audio_session_state(self.inner(), session_state.inner())
+    }
+
+    /// Capture the current Link Session State from an application thread into a new [SessionState].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn capture_app_session_state(&self) -> SessionState {
+        SessionState::from_ptr(ffi::full_link_capture_app_session_state(self.inner()))
+    }
+
+    /// Capture the current Link Session State from an application thread into `session_state`.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn capture_app_session_state_into(&self, session_state: &mut SessionState) {
+        ffi::full_link_capture_app_session_state_into(self.inner(), session_state.pin_mut())
+    }
+
+    /// Commit the given Session State to the Link session from an application thread.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn commit_app_session_state(&self, session_state: &SessionState) {
+        ffi::full_link_commit_app_session_state(self.inner(), session_state.inner())
+    }
+}
+
+impl fmt::Debug for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Link")
+            .field("is_enabled", &self.is_enabled())
+            .field("num_peers", &self.num_peers())
+            .finish()
+    }
+}
+
+/// A C++ `ableton::Link::SessionState`, see [crate::SessionState] for the documentation.
+///
+/// Unlike the Session States of the C API, these can be cloned.
+pub struct SessionState {
+    session_state: UniquePtr<ffi::SessionState>,
+}
+
+unsafe impl Send for SessionState {}
+
+impl SessionState {
+    fn from_ptr(session_state: UniquePtr<ffi::SessionState>) -> Self {
+        Self { session_state }
+    }
+
+    fn inner(&self) -> &ffi::SessionState {
+        self.session_state
+            .as_ref()
+            .expect("ableton::Link::SessionState")
+    }
+
+    fn pin_mut(&mut self) -> std::pin::Pin<&mut ffi::SessionState> {
+        self.session_state.pin_mut()
+    }
+
+    /// The tempo of the timeline, in Beats Per Minute.
+    pub fn tempo(&self) -> f64 {
+        ffi::session_state_tempo(self.inner())
+    }
+
+    /// Set the timeline tempo to the given bpm value, taking effect at the given time.
+    pub fn set_tempo(&mut self, bpm: f64, at_time: i64) {
+        ffi::session_state_set_tempo(self.pin_mut(), bpm, at_time)
+    }
+
+    /// Get the beat value corresponding to the given time for the given quantum.
+    pub fn beat_at_time(&self, time: i64, quantum: f64) -> f64 {
+        ffi::session_state_beat_at_time(self.inner(), time, quantum)
+    }
+
+    /// Get the session phase at the given time for the given quantum.
+    pub fn phase_at_time(&self, time: i64, quantum: f64) -> f64 {
+        ffi::session_state_phase_at_time(self.inner(), time, quantum)
+    }
+
+    /// Get the time at which the given beat occurs for the given quantum.
+    pub fn time_at_beat(&self, beat: f64, quantum: f64) -> i64 {
+        ffi::session_state_time_at_beat(self.inner(), beat, quantum)
+    }
+
+    /// Attempt to map the given beat to the given time in the context of the given quantum.
+    pub fn request_beat_at_time(&mut self, beat: f64, time: i64, quantum: f64) {
+        ffi::session_state_request_beat_at_time(self.pin_mut(), beat, time, quantum)
+    }
+
+    /// Rudely re-map the beat/time relationship for all peers in a session.
+    pub fn force_beat_at_time(&mut self, beat: f64, time: i64, quantum: f64) {
+        ffi::session_state_force_beat_at_time(self.pin_mut(), beat, time, quantum)
+    }
+
+    /// Set if transport should be playing or stopped, taking effect at the given time.
+    pub fn set_is_playing(&mut self, is_playing: bool, time: i64) {
+        ffi::session_state_set_is_playing(self.pin_mut(), is_playing, time)
+    }
+
+    /// Is transport playing?
+    pub fn is_playing(&self) -> bool {
+        ffi::session_state_is_playing(self.inner())
+    }
+
+    /// Get the time at which a transport start/stop occurs.
+    pub fn time_for_is_playing(&self) -> i64 {
+        ffi::session_state_time_for_is_playing(self.inner())
+    }
+
+    /// Attempt to map the given beat to the time when transport is starting to play.
+    pub fn request_beat_at_start_playing_time(&mut self, beat: f64, quantum: f64) {
+        ffi::session_state_request_beat_at_start_playing_time(self.pin_mut(), beat, quantum)
+    }
+
+    /// Start or stop transport at a given time and attempt to map the given beat to this time.
+    pub fn set_is_playing_and_request_beat_at_time(
+        &mut self,
+        is_playing: bool,
+        time: i64,
+        beat: f64,
+        quantum: f64,
+    ) {
+        ffi::session_state_set_is_playing_and_request_beat_at_time(
+            self.pin_mut(),
+            is_playing,
+            time,
+            beat,
+            quantum,
+        )
+    }
+}
+
+impl Clone for SessionState {
+    fn clone(&self) -> Self {
+        Self::from_ptr(ffi::session_state_clone(self.inner()))
+    }
+}
+
+impl fmt::Debug for SessionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionState")
+            .field("tempo", &self.tempo())
+            .field("is_playing", &self.is_playing())
+            .field("time_for_is_playing", &self.time_for_is_playing())
+            .finish()
+    }
+}
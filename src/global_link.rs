@@ -0,0 +1,81 @@
+use crate::{AblLink, LinkRegistry};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+/// A lazily created [AblLink] instance shared by all code using the same handle, e.g. the
+/// plugins of a host or the modules of a live-reloaded app.
+///
+/// The handle only holds a weak reference: the instance lives as long as one of the
+/// [Arc]s returned by [GlobalLink::get_or_init] does, and the next call after the last one
+/// was dropped creates a new instance. Creation is serialized, so racing callers never
+/// construct two instances. Instances are registered in [LinkRegistry::global].
+///
+/// A `static GLOBAL: GlobalLink = GlobalLink::new();` belongs to the library declaring it.
+/// When that library is reloaded, the new copy starts with an empty handle, so drop the
+/// [Arc]s of the old copy before unloading it, or let the host own the handle and pass it
+/// to the reloaded code.
+pub struct GlobalLink {
+    instance: Mutex<Option<Weak<AblLink>>>,
+}
+
+impl Default for GlobalLink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlobalLink {
+    /// An empty handle, usable in a `static`.
+    pub const fn new() -> Self {
+        Self {
+            instance: Mutex::new(None),
+        }
+    }
+
+    /// The handle of the process, shared by all code linking this crate statically.
+    pub fn global() -> &'static GlobalLink {
+        static GLOBAL: GlobalLink = GlobalLink::new();
+        &GLOBAL
+    }
+
+    /// The current instance, or a new one created with `init` if there is none.
+    pub fn get_or_init(&self, init: impl FnOnce() -> AblLink) -> Arc<AblLink> {
+        let mut instance = self.lock();
+        if let Some(link) = instance.as_ref().and_then(Weak::upgrade) {
+            return link;
+        }
+
+        debug!("create global Link instance");
+        let link = Arc::new(init());
+        LinkRegistry::global().register(&link);
+        *instance = Some(Arc::downgrade(&link));
+        link
+    }
+
+    /// The current instance, or a new one with an initial tempo, see [AblLink::new].
+    pub fn get_or_create(&self, bpm: f64) -> Arc<AblLink> {
+        self.get_or_init(|| AblLink::new(bpm))
+    }
+
+    /// The current instance, `None` if there is none.
+    pub fn get(&self) -> Option<Arc<AblLink>> {
+        self.lock().as_ref().and_then(Weak::upgrade)
+    }
+
+    /// Number of [Arc]s of the current instance, 0 if there is none.
+    pub fn handles(&self) -> usize {
+        self.lock().as_ref().map_or(0, Weak::strong_count)
+    }
+
+    /// Forget the current instance, so the next [GlobalLink::get_or_init] creates a new
+    /// one, e.g. with different settings after a reload. Holders of the old instance keep
+    /// using it until they drop it. Returns the old instance, if it was still alive.
+    pub fn reset(&self) -> Option<Arc<AblLink>> {
+        let link = self.lock().take().and_then(|weak| weak.upgrade());
+        debug!(alive = link.is_some(), "reset global Link instance");
+        link
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Weak<AblLink>>> {
+        self.instance.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -11,16 +11,180 @@
 #[allow(non_camel_case_types)]
 mod rust_bindings {
     // Rust bindings of all functions and types starting with 'abl_link_' from
-    // 'abl_link.h' (generated by bindgen)
+    // 'abl_link.h' and 'rusty_link_shim_' from 'cmake/rusty_link_shim.h' (generated by bindgen)
     include!(concat!(env!("OUT_DIR"), "/link_bindings.rs"));
 }
 
+#[macro_use]
+mod trace;
+
 mod abl_link;
+mod activity;
+mod audio_buffer;
+mod audio_watchdog;
+mod beat_grid;
+#[cfg(feature = "bevy")]
+mod bevy_plugin;
+mod callbacks;
+mod clock_source;
+mod commit_policy;
+mod conductor;
+mod crossfade;
+mod delivery;
+mod diagnostics;
+mod display_tempo;
+mod drift_monitor;
+mod error;
+mod events;
+mod exact_tempo;
+mod frame_info;
+mod global_link;
 mod host_time_filter;
+mod link_handlers;
+mod link_registry;
+mod link_runtime;
+mod link_settings;
+mod midi_clock;
+#[cfg(feature = "midir")]
+mod midi_remote;
+mod msc;
+mod network_status;
+mod note_scheduler;
+mod nudge;
+mod osc_clock;
+mod panic;
+#[cfg(feature = "mdns")]
+mod peer_directory;
+mod phase_buffer;
+mod phase_provider;
+mod position;
+mod power_policy;
+mod quantum;
+mod ramp;
+mod recording_markers;
+mod session_key;
+mod session_recorder;
+mod session_replayer;
 mod session_state;
-mod split;
+mod snapshot_cell;
+mod step_clock;
+mod sync_probe;
+#[cfg(feature = "metrics")]
+mod telemetry;
+mod tempo_clock;
+mod tempo_history;
+mod tempo_limiter;
+mod tempo_scaler;
+mod thread_config;
+mod timeline_map;
+mod transport;
+mod transport_gate;
+mod transport_policy;
+#[cfg(feature = "chrono")]
+mod wall_clock;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "compat")]
+pub mod compat;
+#[cfg(feature = "full-cpp")]
+pub mod full_cpp;
+#[cfg(feature = "native")]
+pub mod native;
+#[cfg(feature = "relay")]
+pub mod relay;
+#[cfg(feature = "rt-check")]
+pub mod rt_check;
+
+#[cfg(feature = "rt-check")]
+use rt_check::{realtime, realtime_callback};
+
+/// Wrapper for the bodies of functions documented as "Realtime-safe: yes".
+/// Checks for allocations with the `rt-check` feature.
+#[cfg(not(feature = "rt-check"))]
+#[inline(always)]
+fn realtime<R, F: FnOnce() -> R>(f: F) -> R {
+    f()
+}
+
+/// Wrapper for the invocation of callbacks on Link-managed threads.
+/// Counts allocations with the `rt-check` feature.
+#[cfg(not(feature = "rt-check"))]
+#[inline(always)]
+fn realtime_callback<R, F: FnOnce() -> R>(f: F) -> R {
+    f()
+}
 
 // PUBLIC API
-pub use abl_link::AblLink;
+pub use abl_link::{AblLink, STALE_SESSION_STATE_AGE};
+pub use activity::{SessionActivity, SETTLE_GRACE_PERIOD};
+pub use audio_buffer::AudioBuffer;
+pub use audio_watchdog::AudioWatchdog;
+pub use beat_grid::{BeatGrid, GridLine, GridLineKind, MAX_GRID_LINES};
+#[cfg(feature = "bevy")]
+pub use bevy_plugin::{LinkBeat, LinkInstance, LinkPlugin};
+pub use clock_source::ClockSource;
+pub use commit_policy::CommitPolicy;
+pub use conductor::{Conductor, Correction};
+pub use crossfade::{Crossfade, FadeCurve};
+pub use delivery::Delivery;
+pub use diagnostics::Diagnostics;
+pub use display_tempo::DisplayTempo;
+pub use drift_monitor::{DriftMonitor, DriftReport};
+pub use error::{Error, Result, MAX_TEMPO, MIN_TEMPO};
+pub use events::{LinkEvent, TempoSource, TimedEvent, EVENT_QUEUE_CAPACITY, PEER_HISTORY_CAPACITY};
+pub use exact_tempo::ExactTempo;
+pub use frame_info::FrameInfo;
+pub use global_link::GlobalLink;
 pub use host_time_filter::HostTimeFilter;
-pub use session_state::SessionState;
+pub use link_handlers::LinkHandlers;
+pub use link_registry::LinkRegistry;
+pub use link_runtime::LinkRuntime;
+pub use link_settings::LinkSettings;
+pub use midi_clock::{MidiClockOut, MidiSink, MIDI_CLOCK_PPQN};
+#[cfg(feature = "midir")]
+pub use midi_remote::{MidiRemote, RemoteAction, Trigger};
+pub use msc::{
+    MscCommand, MscCue, MscCueOut, MscEncoder, MSC_ALL_CALL, MSC_ALL_TYPES, MSC_LIGHTING,
+};
+pub use network_status::{EnableStatus, NetworkMonitor};
+pub use note_scheduler::{NoteScheduler, ScheduledNote, StopPolicy, MAX_SCHEDULED_MESSAGE};
+pub use nudge::Nudge;
+pub use osc_clock::{OscClock, OSC_IMMEDIATELY};
+pub use panic::panic_all;
+#[cfg(feature = "mdns")]
+pub use peer_directory::{PeerDirectory, PeerInfo, PEER_SERVICE_TYPE};
+pub use phase_buffer::render_phase_buffer;
+pub use phase_provider::PhaseProvider;
+pub use position::{format_position, Position};
+pub use power_policy::{PowerPolicy, PowerState};
+pub use quantum::{Quantum, TimeSignature, DEFAULT_QUANTUM};
+pub use ramp::Ramp;
+pub use recording_markers::{Marker, RecordingMarkers};
+/// Beat, phase and timeline math without FFI, see [rusty_link_core].
+pub use rusty_link_core as math;
+#[cfg(feature = "derive")]
+pub use rusty_link_derive::link_handlers;
+pub use session_key::SessionKey;
+pub use session_recorder::{Recording, SessionRecorder, TimelineSample};
+pub use session_replayer::SessionReplayer;
+pub use session_state::{CaptureRole, SessionState};
+pub use snapshot_cell::{SnapshotCell, SnapshotReader, SnapshotWriter};
+pub use step_clock::{StepClock, StepEvent};
+pub use sync_probe::{SyncProbe, SyncReport};
+pub use tempo_clock::TempoClock;
+pub use tempo_history::TempoHistory;
+pub use tempo_limiter::TempoCommitLimiter;
+pub use tempo_scaler::TempoScaler;
+pub use thread_config::{ThreadConfig, ThreadPriority, DEFAULT_THREAD_NAME_PREFIX};
+pub use timeline_map::TimelineMap;
+pub use transport::Transport;
+pub use transport_gate::{GateMode, GateState, ResumeAlignment, TransportGate};
+pub use transport_policy::TransportPolicy;
+#[cfg(feature = "chrono")]
+pub use wall_clock::WallClock;
+
+// Exposed for the loom model of callback registration in `tests/loom_callbacks.rs`
+#[cfg(rusty_link_loom)]
+#[doc(hidden)]
+pub use callbacks::CallbackSlot;
@@ -0,0 +1,43 @@
+use crate::AblLink;
+use std::sync::{Arc, Mutex};
+
+/// A type whose methods handle Link callbacks, see [AblLink::set_handlers].
+///
+/// Usually implemented with the `link_handlers` attribute macro of the `derive` feature:
+///
+/// ```ignore
+/// struct App {
+///     tempo: f64,
+/// }
+///
+/// #[rusty_link::link_handlers]
+/// impl App {
+///     #[on_tempo]
+///     fn tempo_changed(&mut self, bpm: f64) {
+///         self.tempo = bpm;
+///     }
+/// }
+///
+/// let app = Arc::new(Mutex::new(App { tempo: 120. }));
+/// link.set_handlers(&app);
+/// ```
+pub trait LinkHandlers: Send + 'static {
+    /// Register the callbacks of `link`, which lock `this` and call its handler methods.
+    fn register_handlers(this: &Arc<Mutex<Self>>, link: &AblLink)
+    where
+        Self: Sized;
+}
+
+impl AblLink {
+    /// Register the handler methods of `handlers` as callbacks, see [LinkHandlers].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// The callbacks keep `handlers` alive until they are deleted or replaced. Handlers are
+    /// invoked on Link-managed threads while `handlers` is locked.
+    pub fn set_handlers<H: LinkHandlers>(&self, handlers: &Arc<Mutex<H>>) {
+        H::register_handlers(handlers, self)
+    }
+}
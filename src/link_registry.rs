@@ -0,0 +1,96 @@
+use crate::{AblLink, LinkSettings, Result};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, Weak};
+
+/// Coordination point for several [AblLink] instances in one process, e.g. plugins of one
+/// host which each create their own instance.
+///
+/// The registry only holds weak references, instances are dropped as usual and disappear
+/// from the registry. [LinkRegistry::global] is shared by all code in the process, so
+/// plugins can find each other without knowing about each other.
+#[derive(Default)]
+pub struct LinkRegistry {
+    instances: Mutex<Vec<Weak<AblLink>>>,
+}
+
+impl LinkRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The registry of the process.
+    pub fn global() -> &'static LinkRegistry {
+        static GLOBAL: OnceLock<LinkRegistry> = OnceLock::new();
+        GLOBAL.get_or_init(LinkRegistry::new)
+    }
+
+    /// Create a new instance with an initial tempo, see [AblLink::new], and register it.
+    pub fn create(&self, bpm: f64) -> Arc<AblLink> {
+        let link = Arc::new(AblLink::new(bpm));
+        self.register(&link);
+        link
+    }
+
+    /// Register an instance created elsewhere. Registering an instance twice has no effect.
+    pub fn register(&self, link: &Arc<AblLink>) {
+        let mut instances = self.lock();
+        if !instances
+            .iter()
+            .any(|weak| weak.as_ptr() == Arc::as_ptr(link))
+        {
+            debug!(instances = instances.len() + 1, "register Link instance");
+            instances.push(Arc::downgrade(link));
+        }
+    }
+
+    /// Remove an instance from the registry.
+    pub fn unregister(&self, link: &AblLink) {
+        self.lock()
+            .retain(|weak| !std::ptr::eq(weak.as_ptr(), link));
+    }
+
+    /// All registered instances which are still alive, in order of registration.
+    pub fn instances(&self) -> Vec<Arc<AblLink>> {
+        self.lock().iter().filter_map(Weak::upgrade).collect()
+    }
+
+    /// Number of registered instances which are still alive.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Enable or disable all registered instances, e.g. so only one plugin of a host keeps
+    /// Link enabled.
+    pub fn enable_all(&self, enable: bool) {
+        for link in self.instances() {
+            link.enable(enable);
+        }
+    }
+
+    /// Enable one registered instance and disable all others.
+    pub fn enable_only(&self, link: &AblLink) {
+        for other in self.instances() {
+            other.enable(std::ptr::eq(Arc::as_ptr(&other), link));
+        }
+    }
+
+    /// Apply `settings` to all registered instances, see [AblLink::apply]. Nothing is
+    /// applied, if the settings do not pass [LinkSettings::check].
+    pub fn apply_all(&self, settings: &LinkSettings) -> Result<()> {
+        settings.check()?;
+        for link in self.instances() {
+            link.apply(settings)?;
+        }
+        Ok(())
+    }
+
+    /// Lock the list and drop the references of instances which no longer exist.
+    fn lock(&self) -> MutexGuard<'_, Vec<Weak<AblLink>>> {
+        let mut instances = self.instances.lock().unwrap_or_else(|e| e.into_inner());
+        instances.retain(|weak| weak.strong_count() > 0);
+        instances
+    }
+}
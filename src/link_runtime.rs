@@ -0,0 +1,141 @@
+use crate::{AblLink, Error, Result};
+use std::{
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
+
+type Stop = Box<dyn FnOnce() + Send>;
+
+/// Owner of an [AblLink] instance and the crate-spawned threads built on it, e.g.
+/// [MidiClockOut](crate::MidiClockOut), [NetworkMonitor](crate::NetworkMonitor) or a
+/// [RelayServer](crate::RelayServer), which shuts all of them down in one go.
+///
+/// Dropping interdependent pieces one by one can let Link invoke callbacks which feed
+/// subsystems that are already gone. [LinkRuntime::shutdown] stops everything in an order
+/// where that can not happen:
+///
+/// 1. the num_peers, tempo and start/stop callbacks are deleted,
+/// 2. the managed subsystems are stopped, in reverse order of [LinkRuntime::manage],
+/// 3. Link is disabled,
+/// 4. the instance is dropped, unless there are other [Arc]s of it, which also joins the
+///    callback dispatcher thread, see [Delivery::Dispatcher](crate::Delivery::Dispatcher).
+///
+/// Dropping the runtime does the same, without a timeout.
+pub struct LinkRuntime {
+    link: Option<Arc<AblLink>>,
+    subsystems: Vec<(&'static str, Stop)>,
+}
+
+impl LinkRuntime {
+    pub fn new(link: AblLink) -> Self {
+        Self::from_arc(Arc::new(link))
+    }
+
+    /// Take over an instance which is already shared, e.g. with [GlobalLink](crate::GlobalLink).
+    pub fn from_arc(link: Arc<AblLink>) -> Self {
+        Self {
+            link: Some(link),
+            subsystems: Vec::new(),
+        }
+    }
+
+    /// The managed instance, to start subsystems with.
+    pub fn link(&self) -> &Arc<AblLink> {
+        self.link.as_ref().expect("Link runtime has shut down.")
+    }
+
+    /// Stop a subsystem with `stop` during shutdown, e.g. `move || clock.stop()`.
+    /// `name` identifies the subsystem when the shutdown times out.
+    pub fn manage<F: FnOnce() + Send + 'static>(&mut self, name: &'static str, stop: F) {
+        debug!(name, "manage subsystem");
+        self.subsystems.push((name, Box::new(stop)));
+    }
+
+    /// Same as [LinkRuntime::manage], for subsystems which stop when they are dropped.
+    pub fn manage_drop<T: Send + 'static>(&mut self, name: &'static str, subsystem: T) {
+        self.manage(name, move || drop(subsystem));
+    }
+
+    /// Number of managed subsystems.
+    pub fn len(&self) -> usize {
+        self.subsystems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subsystems.is_empty()
+    }
+
+    /// Stop all subsystems and Link in order, see [LinkRuntime].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// Returns [Error::ShutdownTimedOut] with the steps still running after `timeout`.
+    /// They keep running on a background thread, configured by [AblLink::set_thread_config],
+    /// and finish in order.
+    pub fn shutdown(mut self, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        let config = self.link().thread_config();
+        let steps = self.take_steps();
+        let mut pending: Vec<&'static str> = steps.iter().map(|(name, _)| *name).collect();
+
+        let (done, finished) = mpsc::channel();
+        config.spawn("shutdown", move || {
+            for (name, step) in steps {
+                step();
+                let _ = done.send(name);
+            }
+        });
+
+        while !pending.is_empty() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match finished.recv_timeout(remaining) {
+                Ok(_) => {
+                    pending.remove(0);
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    warn!(?pending, "Link runtime shutdown timed out");
+                    return Err(Error::ShutdownTimedOut { pending });
+                }
+                // A step panicked, the remaining steps will not run
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(Error::Shutdown("Link runtime shutdown thread"))
+                }
+            }
+        }
+        debug!("Link runtime shut down");
+        Ok(())
+    }
+
+    /// The steps of the shutdown in order, named for [Error::ShutdownTimedOut].
+    fn take_steps(&mut self) -> Vec<(&'static str, Stop)> {
+        let Some(link) = self.link.take() else {
+            return Vec::new();
+        };
+
+        let mut steps: Vec<(&'static str, Stop)> = Vec::new();
+        let callbacks = Arc::clone(&link);
+        steps.push((
+            "callbacks",
+            Box::new(move || {
+                callbacks.delete_num_peers_callback();
+                callbacks.delete_tempo_callback();
+                callbacks.delete_start_stop_callback();
+            }),
+        ));
+        steps.extend(self.subsystems.drain(..).rev());
+        let disable = Arc::clone(&link);
+        steps.push(("link", Box::new(move || disable.enable(false))));
+        steps.push(("release", Box::new(move || drop(link))));
+        steps
+    }
+}
+
+impl Drop for LinkRuntime {
+    fn drop(&mut self) {
+        for (_, step) in self.take_steps() {
+            step();
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{error::check_tempo, AblLink, Error, Result, SessionState};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// Settings of a Link app, which can be stored between runs, so small tools come back up
+/// with the tempo of the last session.
+///
+/// Stored as TOML:
+///
+/// ```toml
+/// tempo = 120
+/// quantum = 4
+/// start_stop_sync = false
+/// enabled = true
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkSettings {
+    pub tempo: f64,
+    pub quantum: f64,
+    pub start_stop_sync: bool,
+    pub enabled: bool,
+}
+
+impl Default for LinkSettings {
+    fn default() -> Self {
+        Self {
+            tempo: 120.,
+            quantum: 4.,
+            start_stop_sync: false,
+            enabled: false,
+        }
+    }
+}
+
+impl LinkSettings {
+    /// The current settings of `link`. The quantum is not known to Link and has to be passed.
+    pub fn capture(link: &AblLink, quantum: f64) -> LinkSettings {
+        let mut session_state = SessionState::new();
+        link.capture_app_session_state(&mut session_state);
+
+        LinkSettings {
+            tempo: session_state.tempo(),
+            quantum,
+            start_stop_sync: link.is_start_stop_sync_enabled(),
+            enabled: link.is_enabled(),
+        }
+    }
+
+    /// Default settings, overridden by the environment variables `ABL_LINK_TEMPO`,
+    /// `ABL_LINK_QUANTUM` and `ABL_LINK_ENABLE` (`true`/`false` or `1`/`0`), see
+    /// [AblLink::from_env].
+    pub fn from_env() -> Result<LinkSettings> {
+        let mut settings = LinkSettings::default();
+
+        if let Some(tempo) = env_value("ABL_LINK_TEMPO")? {
+            settings.tempo = tempo;
+        }
+        if let Some(quantum) = env_value("ABL_LINK_QUANTUM")? {
+            settings.quantum = quantum;
+        }
+        if let Some(enabled) = env_value::<String>("ABL_LINK_ENABLE")? {
+            settings.enabled = match enabled.as_str() {
+                "1" => true,
+                "0" => false,
+                _ => parse_value(&enabled)?,
+            };
+        }
+
+        settings.check()?;
+        Ok(settings)
+    }
+
+    /// Check that the tempo is supported by Link and the quantum is a positive number of
+    /// beats. Returns [Error::TempoOutOfRange] or [Error::InvalidQuantum] otherwise.
+    pub fn check(&self) -> Result<()> {
+        check_tempo(self.tempo)?;
+        if !(self.quantum.is_finite() && self.quantum > 0.) {
+            return Err(Error::InvalidQuantum {
+                quantum: self.quantum,
+            });
+        }
+        Ok(())
+    }
+
+    /// Save the settings to a file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Load settings from a file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<LinkSettings> {
+        Self::read_from(File::open(path)?)
+    }
+
+    /// Write the settings as TOML.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        writeln!(writer, "tempo = {}", self.tempo)?;
+        writeln!(writer, "quantum = {}", self.quantum)?;
+        writeln!(writer, "start_stop_sync = {}", self.start_stop_sync)?;
+        writeln!(writer, "enabled = {}", self.enabled)?;
+        Ok(())
+    }
+
+    /// Read settings written by [LinkSettings::write_to]. Missing keys keep their default
+    /// values, unknown keys and comments are ignored. Fails with [Error::InvalidSettings] if
+    /// a value can not be parsed or does not pass [LinkSettings::check].
+    pub fn read_from<R: Read>(reader: R) -> Result<LinkSettings> {
+        let mut settings = LinkSettings::default();
+
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid(&format!("expected 'key = value', got '{}'", line)))?;
+
+            match key.trim() {
+                "tempo" => settings.tempo = parse_value(value)?,
+                "quantum" => settings.quantum = parse_value(value)?,
+                "start_stop_sync" => settings.start_stop_sync = parse_value(value)?,
+                "enabled" => settings.enabled = parse_value(value)?,
+                _ => {}
+            }
+        }
+
+        settings.check().map_err(|e| invalid(&e.to_string()))?;
+        Ok(settings)
+    }
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::InvalidSettings(msg.to_string())
+}
+
+fn parse_value<T: std::str::FromStr>(value: &str) -> Result<T> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| invalid(&format!("could not parse value '{}'", value.trim())))
+}
+
+/// Value of the environment variable `key`, if it is set.
+fn env_value<T: std::str::FromStr>(key: &str) -> Result<Option<T>> {
+    match std::env::var(key) {
+        Ok(value) => parse_value(&value)
+            .map(Some)
+            .map_err(|_| invalid(&format!("could not parse {}='{}'", key, value))),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(_)) => Err(invalid(&format!("{} is not unicode", key))),
+    }
+}
+
+impl AblLink {
+    /// Construct a new AblLink instance configured by environment variables, for
+    /// containerized bridges and quick experiments without recompiling.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// | Variable | Default | |
+    /// |---|---|---|
+    /// | `ABL_LINK_TEMPO` | 120 | Initial tempo in bpm |
+    /// | `ABL_LINK_QUANTUM` | 4 | Quantum of the app, see [AblLink::quantum] |
+    /// | `ABL_LINK_ENABLE` | false | Enable Link right away, `true`/`false` or `1`/`0` |
+    ///
+    /// Like [AblLink::new], `ABL_LINK_ISOLATED=1` creates an offline instance. Fails if a
+    /// variable is set to an invalid value.
+    pub fn from_env() -> Result<AblLink> {
+        let settings = LinkSettings::from_env()?;
+        let link = AblLink::new(settings.tempo);
+        link.set_quantum(settings.quantum)?;
+        link.enable(settings.enabled);
+        Ok(link)
+    }
+
+    /// Apply tempo, quantum, start/stop sync and enabled state of `settings`. The quantum is
+    /// only used by the app itself, see [AblLink::set_quantum]. Nothing is applied, if the
+    /// settings do not pass [LinkSettings::check].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn apply(&self, settings: &LinkSettings) -> Result<()> {
+        settings.check()?;
+
+        let mut session_state = SessionState::new();
+        self.capture_app_session_state(&mut session_state);
+        session_state.set_tempo(settings.tempo, self.clock_micros());
+        self.commit_app_session_state(&session_state);
+
+        self.set_quantum(settings.quantum)?;
+        self.enable_start_stop_sync(settings.start_stop_sync);
+        self.enable(settings.enabled);
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{AblLink, GateState, SessionState, ThreadConfig, TransportGate};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// MIDI clock ticks per quarter note (beat).
+pub const MIDI_CLOCK_PPQN: f64 = 24.;
+
+const SONG_POSITION: u8 = 0xF2;
+const CLOCK: u8 = 0xF8;
+const START: u8 = 0xFA;
+const CONTINUE: u8 = 0xFB;
+const STOP: u8 = 0xFC;
+
+/// MIDI clock ticks per sixteenth note, the unit of the song position pointer.
+const TICKS_PER_SIXTEENTH: i64 = 6;
+
+/// Longest time the clock thread sleeps, before looking at the Session State again.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Destination for MIDI messages sent by the crate, e.g. a MIDI output port.
+///
+/// Implemented for closures and, with the `midir` feature, for `midir::MidiOutputConnection`.
+pub trait MidiSink: Send + 'static {
+    fn send(&mut self, message: &[u8]);
+}
+
+impl<F: FnMut(&[u8]) + Send + 'static> MidiSink for F {
+    fn send(&mut self, message: &[u8]) {
+        self(message)
+    }
+}
+
+#[cfg(feature = "midir")]
+impl MidiSink for midir::MidiOutputConnection {
+    fn send(&mut self, message: &[u8]) {
+        // A dropped clock tick is better than stopping the clock thread
+        let _ = midir::MidiOutputConnection::send(self, message);
+    }
+}
+
+/// Sends MIDI clock (24 ticks per beat) and start/stop messages, which follow the
+/// Link session, from a background thread.
+///
+/// By default ticks are only sent while transport is playing and the beat is not negative,
+/// so a quantized start of transport results in a MIDI start message right on the bar.
+/// See [MidiClockOut::start_with_gate] for a free running clock.
+///
+/// If transport is already playing when the clock starts or the session is joined, a
+/// song position pointer to the next sixteenth note and a MIDI continue message are sent
+/// instead, so receivers pick up in the middle of the song.
+pub struct MidiClockOut {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MidiClockOut {
+    /// Start sending MIDI clock to `sink`, following the app Session State of `link`.
+    pub fn start<S: MidiSink>(link: Arc<AblLink>, sink: S, quantum: f64) -> MidiClockOut {
+        Self::start_with_config(link, sink, quantum, ThreadConfig::default())
+    }
+
+    /// Same as [MidiClockOut::start], with a custom configuration of the clock thread,
+    /// e.g. realtime priority.
+    pub fn start_with_config<S: MidiSink>(
+        link: Arc<AblLink>,
+        sink: S,
+        quantum: f64,
+        config: ThreadConfig,
+    ) -> MidiClockOut {
+        Self::start_with_gate(link, sink, quantum, TransportGate::default(), config)
+    }
+
+    /// Same as [MidiClockOut::start_with_config], with the behavior while transport is
+    /// stopped. A free running clock keeps sending ticks without a MIDI start message, a
+    /// muted clock sends the MIDI stop message and no ticks.
+    pub fn start_with_gate<S: MidiSink>(
+        link: Arc<AblLink>,
+        sink: S,
+        quantum: f64,
+        gate: TransportGate,
+        config: ThreadConfig,
+    ) -> MidiClockOut {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = Arc::clone(&running);
+
+        let handle = config.spawn("midi-clock", move || {
+            let mut clock = ClockState::new(sink, quantum, gate);
+            let mut session_state = SessionState::new();
+
+            while running_clone.load(Ordering::Acquire) {
+                if link.is_panicked() {
+                    clock.stop();
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+                link.capture_app_session_state(&mut session_state);
+                link.apply_transport_policy(&mut session_state);
+                let sleep_time = clock.update(&session_state, link.clock_micros());
+                thread::sleep(sleep_time.min(POLL_INTERVAL));
+            }
+
+            clock.stop();
+        });
+
+        MidiClockOut {
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop the clock thread. Sends a MIDI stop message, if transport was running.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for MidiClockOut {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// State of the clock thread.
+struct ClockState<S: MidiSink> {
+    sink: S,
+    quantum: f64,
+    gate: TransportGate,
+    /// Index of the tick at the last update, counted from beat 0, unless the gate pauses
+    last_tick: Option<i64>,
+    /// A MIDI start or continue message was sent without the matching stop message
+    started: bool,
+    /// Tick of the song position sent with the last continue message, until it is reached
+    resume_tick: Option<i64>,
+    /// Link clock time of the previous update
+    last_update: Option<i64>,
+}
+
+impl<S: MidiSink> ClockState<S> {
+    fn new(sink: S, quantum: f64, gate: TransportGate) -> Self {
+        Self {
+            sink,
+            quantum,
+            gate,
+            last_tick: None,
+            started: false,
+            resume_tick: None,
+            last_update: None,
+        }
+    }
+
+    /// Send all messages due at `time`. Returns the time until the next tick is due.
+    fn update(&mut self, session_state: &SessionState, time: i64) -> Duration {
+        let last_update = self.last_update.replace(time);
+        let state = self.gate.state(session_state, time, self.quantum);
+        if state == GateState::Paused {
+            // Stopped or count-in of a quantized start
+            self.stop();
+            return POLL_INTERVAL;
+        }
+
+        let beat = session_state.beat_at_time(time, self.quantum);
+        let playing = state == GateState::Open && session_state.is_playing();
+        if playing != self.started {
+            // Transport started before the previous update, e.g. before the clock was
+            // started or by the session which was joined
+            let start_time = session_state.time_for_is_playing() as i64;
+            let already_playing = last_update.is_none_or(|last_update| start_time <= last_update);
+
+            if !playing {
+                debug!("send MIDI stop");
+                self.sink.send(&[STOP]);
+                self.resume_tick = None;
+            } else if already_playing {
+                // Song positions beyond the 14 bit range are clamped, the ticks continue
+                let sixteenth = (beat * 4.).ceil().max(0.) as i64;
+                let position = sixteenth.min(0x3FFF) as u16;
+                debug!(position, "send MIDI song position and continue");
+                self.sink.send(&[
+                    SONG_POSITION,
+                    (position & 0x7F) as u8,
+                    (position >> 7) as u8,
+                ]);
+                self.sink.send(&[CONTINUE]);
+                self.resume_tick = Some(sixteenth * TICKS_PER_SIXTEENTH);
+            } else {
+                debug!("send MIDI start");
+                self.sink.send(&[START]);
+                // Start sends a single tick, even if a free running clock was ticking
+                self.last_tick = None;
+            }
+            self.started = playing;
+        }
+
+        let tick = (beat * MIDI_CLOCK_PPQN).floor() as i64;
+
+        // After a continue message, the first tick is due at the song position
+        if let Some(resume_tick) = self.resume_tick {
+            if tick < resume_tick {
+                let resume_time =
+                    session_state.time_at_beat(resume_tick as f64 / MIDI_CLOCK_PPQN, self.quantum);
+                return Duration::from_micros((resume_time - time).max(0) as u64);
+            }
+            self.resume_tick = None;
+            self.last_tick = Some(resume_tick - 1);
+        }
+
+        if state == GateState::Open {
+            match self.last_tick {
+                None => self.sink.send(&[CLOCK]),
+                Some(last_tick) => {
+                    #[cfg(feature = "metrics")]
+                    if tick > last_tick {
+                        let due =
+                            session_state.time_at_beat(tick as f64 / MIDI_CLOCK_PPQN, self.quantum);
+                        crate::telemetry::record_clock_jitter(time - due);
+                    }
+                    if tick - last_tick > 1 {
+                        trace!(
+                            missed = tick - last_tick - 1,
+                            "catch up on MIDI clock ticks"
+                        );
+                    }
+                    for _ in last_tick..tick {
+                        self.sink.send(&[CLOCK]);
+                    }
+                }
+            }
+        }
+        self.last_tick = Some(tick);
+
+        let next_tick_time =
+            session_state.time_at_beat((tick + 1) as f64 / MIDI_CLOCK_PPQN, self.quantum);
+        Duration::from_micros((next_tick_time - time).max(0) as u64)
+    }
+
+    fn stop(&mut self) {
+        self.last_tick = None;
+        self.resume_tick = None;
+        if self.started {
+            debug!("send MIDI stop");
+            self.sink.send(&[STOP]);
+            self.started = false;
+        }
+    }
+}
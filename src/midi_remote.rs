@@ -0,0 +1,217 @@
+use crate::{AblLink, SessionState};
+use std::sync::Arc;
+
+/// Taps further apart than this start a new tap tempo measurement.
+const TAP_TIMEOUT: i64 = 2_000_000;
+/// Number of tap intervals averaged by tap tempo.
+const TAP_HISTORY: usize = 4;
+
+/// Incoming MIDI which triggers a [RemoteAction].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    /// Note on with a velocity above 0. Any channel if `channel` is `None`.
+    Note { channel: Option<u8>, note: u8 },
+    /// Control change with a value of 64 or more. Any channel if `channel` is `None`.
+    ControlChange { channel: Option<u8>, controller: u8 },
+    /// MIDI Machine Control play or deferred play.
+    MmcPlay,
+    /// MIDI Machine Control stop.
+    MmcStop,
+    /// MIDI realtime start or continue.
+    Start,
+    /// MIDI realtime stop.
+    Stop,
+}
+
+/// What a [MidiRemote] does with the Link session.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemoteAction {
+    /// Set the tempo from the intervals between the last taps.
+    TapTempo,
+    /// Shift the beat of this peer by the given number of beats.
+    Nudge(f64),
+    /// Start transport on the next bar, with start stop sync.
+    Start,
+    /// Stop transport.
+    Stop,
+    /// Start if stopped, stop if playing.
+    ToggleTransport,
+}
+
+/// Controls Link from MIDI input, e.g. from a hardware controller: tap tempo, nudging
+/// and transport.
+///
+/// By default MMC and MIDI realtime start/stop messages control transport. Map notes and
+/// control changes with [MidiRemote::bind]. Messages are passed to [MidiRemote::handle],
+/// or are read from a MIDI input port with [MidiRemote::connect].
+#[derive(Debug, Clone)]
+pub struct MidiRemote {
+    quantum: f64,
+    bindings: Vec<(Trigger, RemoteAction)>,
+    /// Link clock times of the last taps
+    taps: Vec<i64>,
+}
+
+impl MidiRemote {
+    /// A remote with the default transport bindings, for bars of `quantum` beats.
+    pub fn new(quantum: f64) -> Self {
+        Self {
+            quantum,
+            bindings: vec![
+                (Trigger::MmcPlay, RemoteAction::Start),
+                (Trigger::MmcStop, RemoteAction::Stop),
+                (Trigger::Start, RemoteAction::Start),
+                (Trigger::Stop, RemoteAction::Stop),
+            ],
+            taps: Vec::with_capacity(TAP_HISTORY + 1),
+        }
+    }
+
+    /// Perform `action` on `trigger`, in addition to existing bindings of the trigger.
+    pub fn bind(mut self, trigger: Trigger, action: RemoteAction) -> Self {
+        self.bindings.push((trigger, action));
+        self
+    }
+
+    /// Remove all bindings, including the default ones.
+    pub fn clear_bindings(&mut self) {
+        self.bindings.clear();
+    }
+
+    /// Perform the actions bound to `message` on `link`. Returns the performed actions.
+    pub fn handle(&mut self, link: &AblLink, message: &[u8]) -> Vec<RemoteAction> {
+        let Some(trigger) = parse(message) else {
+            return Vec::new();
+        };
+
+        let actions: Vec<RemoteAction> = self
+            .bindings
+            .iter()
+            .filter(|(binding, _)| matches(*binding, trigger))
+            .map(|&(_, action)| action)
+            .collect();
+
+        for &action in &actions {
+            debug!(?action, "MIDI remote action");
+            self.perform(link, action);
+        }
+        actions
+    }
+
+    /// Connect to a MIDI input port and handle its messages on midir's thread.
+    pub fn connect(
+        self,
+        input: midir::MidiInput,
+        port: &midir::MidiInputPort,
+        link: Arc<AblLink>,
+    ) -> Result<midir::MidiInputConnection<MidiRemote>, midir::ConnectError<midir::MidiInput>> {
+        input.connect(
+            port,
+            "rusty_link remote",
+            move |_, message, remote| {
+                remote.handle(&link, message);
+            },
+            self,
+        )
+    }
+
+    fn perform(&mut self, link: &AblLink, action: RemoteAction) {
+        let now = link.clock_micros();
+        let mut session_state = SessionState::new();
+        link.capture_app_session_state(&mut session_state);
+
+        match action {
+            RemoteAction::TapTempo => match self.tap(now) {
+                Some(bpm) => session_state.set_tempo(bpm, now),
+                None => return,
+            },
+            RemoteAction::Nudge(beats) => {
+                link.nudge_beats(beats, self.quantum);
+                return;
+            }
+            RemoteAction::Start => start(&mut session_state, now, self.quantum),
+            RemoteAction::Stop => session_state.set_is_playing(false, now as u64),
+            RemoteAction::ToggleTransport if session_state.is_playing() => {
+                session_state.set_is_playing(false, now as u64)
+            }
+            RemoteAction::ToggleTransport => start(&mut session_state, now, self.quantum),
+        }
+
+        link.commit_app_session_state(&session_state);
+    }
+
+    /// Record a tap and return the tapped tempo, once there are at least two taps.
+    fn tap(&mut self, now: i64) -> Option<f64> {
+        if self
+            .taps
+            .last()
+            .is_some_and(|&last| now - last > TAP_TIMEOUT)
+        {
+            self.taps.clear();
+        }
+        if self.taps.len() > TAP_HISTORY {
+            self.taps.remove(0);
+        }
+        self.taps.push(now);
+
+        let (first, last) = (self.taps.first()?, self.taps.last()?);
+        let intervals = self.taps.len() - 1;
+        if intervals == 0 || last <= first {
+            return None;
+        }
+        let interval = (last - first) as f64 / intervals as f64;
+        Some((60e6 / interval).clamp(crate::MIN_TEMPO, crate::MAX_TEMPO))
+    }
+}
+
+fn start(session_state: &mut SessionState, now: i64, quantum: f64) {
+    session_state.set_is_playing_and_request_beat_at_time(true, now as u64, 0., quantum);
+}
+
+fn matches(binding: Trigger, trigger: Trigger) -> bool {
+    match (binding, trigger) {
+        (
+            Trigger::Note { channel, note },
+            Trigger::Note {
+                channel: Some(received_channel),
+                note: received_note,
+            },
+        ) => note == received_note && channel.is_none_or(|channel| channel == received_channel),
+        (
+            Trigger::ControlChange {
+                channel,
+                controller,
+            },
+            Trigger::ControlChange {
+                channel: Some(received_channel),
+                controller: received_controller,
+            },
+        ) => {
+            controller == received_controller
+                && channel.is_none_or(|channel| channel == received_channel)
+        }
+        _ => binding == trigger,
+    }
+}
+
+/// The trigger of a MIDI message, if it can be one.
+fn parse(message: &[u8]) -> Option<Trigger> {
+    match *message {
+        [0xFA] | [0xFB] => Some(Trigger::Start),
+        [0xFC] => Some(Trigger::Stop),
+        // MMC: F0 7F <device> 06 <command> F7
+        [0xF0, 0x7F, _, 0x06, 0x02 | 0x03, 0xF7] => Some(Trigger::MmcPlay),
+        [0xF0, 0x7F, _, 0x06, 0x01, 0xF7] => Some(Trigger::MmcStop),
+        [status, note, velocity] if status & 0xF0 == 0x90 && velocity > 0 => Some(Trigger::Note {
+            channel: Some(status & 0x0F),
+            note,
+        }),
+        [status, controller, value] if status & 0xF0 == 0xB0 && value >= 64 => {
+            Some(Trigger::ControlChange {
+                channel: Some(status & 0x0F),
+                controller,
+            })
+        }
+        _ => None,
+    }
+}
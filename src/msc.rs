@@ -0,0 +1,242 @@
+use crate::{AblLink, MidiSink, SessionState, ThreadConfig};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Longest time the cue thread sleeps, before looking at the Session State again.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Command format addressing all types of devices.
+pub const MSC_ALL_TYPES: u8 = 0x7F;
+/// Command format of general lighting devices, e.g. lighting desks.
+pub const MSC_LIGHTING: u8 = 0x01;
+/// Device id addressing all devices.
+pub const MSC_ALL_CALL: u8 = 0x7F;
+
+/// A MIDI Show Control command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MscCommand {
+    Go,
+    Stop,
+    Resume,
+}
+
+impl MscCommand {
+    fn code(self) -> u8 {
+        match self {
+            MscCommand::Go => 0x01,
+            MscCommand::Stop => 0x02,
+            MscCommand::Resume => 0x03,
+        }
+    }
+}
+
+/// Encodes MIDI Show Control system exclusive messages for one device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MscEncoder {
+    device_id: u8,
+    command_format: u8,
+}
+
+impl Default for MscEncoder {
+    /// All call to all types of devices.
+    fn default() -> Self {
+        Self::new(MSC_ALL_CALL, MSC_ALL_TYPES)
+    }
+}
+
+impl MscEncoder {
+    /// Encoder addressing `device_id` (0-127, [MSC_ALL_CALL] for all) with
+    /// `command_format`, e.g. [MSC_LIGHTING]. Both are truncated to 7 bits.
+    pub fn new(device_id: u8, command_format: u8) -> Self {
+        Self {
+            device_id: device_id & 0x7F,
+            command_format: command_format & 0x7F,
+        }
+    }
+
+    /// Encode `command` for the cue number `cue`, e.g. `"12.5"`, or for the current cue if
+    /// `cue` is empty. Characters other than digits and `.` are dropped.
+    pub fn encode(&self, command: MscCommand, cue: &str) -> Vec<u8> {
+        let mut message = vec![
+            0xF0,
+            0x7F,
+            self.device_id,
+            0x02,
+            self.command_format,
+            command.code(),
+        ];
+        message.extend(
+            cue.bytes()
+                .filter(|byte| byte.is_ascii_digit() || *byte == b'.'),
+        );
+        message.push(0xF7);
+        message
+    }
+}
+
+/// An MSC command sent by [MscCueOut] when the session reaches `beat`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MscCue {
+    pub beat: f64,
+    pub command: MscCommand,
+    /// Cue number, empty for the current cue.
+    pub cue: String,
+}
+
+impl MscCue {
+    pub fn go(beat: f64, cue: &str) -> Self {
+        Self {
+            beat,
+            command: MscCommand::Go,
+            cue: cue.to_owned(),
+        }
+    }
+
+    pub fn stop(beat: f64, cue: &str) -> Self {
+        Self {
+            beat,
+            command: MscCommand::Stop,
+            cue: cue.to_owned(),
+        }
+    }
+}
+
+/// Sends MIDI Show Control cues at beats of the Link session from a background thread,
+/// e.g. to fire lighting desk cues on the bar.
+///
+/// Cues are sent while transport is playing, when the beat of the session passes the beat
+/// of the cue. Cues whose beat passed before transport started are not sent, unless the
+/// beat jumps back before them. With [MscCueOut::start_with_config] a STOP for the current
+/// cue can be sent whenever transport stops.
+pub struct MscCueOut {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MscCueOut {
+    /// Start sending `cues` to `sink`, following the app Session State of `link`.
+    pub fn start<S: MidiSink>(
+        link: Arc<AblLink>,
+        sink: S,
+        encoder: MscEncoder,
+        cues: Vec<MscCue>,
+    ) -> MscCueOut {
+        Self::start_with_config(link, sink, encoder, cues, false, ThreadConfig::default())
+    }
+
+    /// Same as [MscCueOut::start], with a custom configuration of the cue thread.
+    /// `stop_with_transport` sends a STOP for the current cue when transport stops.
+    pub fn start_with_config<S: MidiSink>(
+        link: Arc<AblLink>,
+        sink: S,
+        encoder: MscEncoder,
+        mut cues: Vec<MscCue>,
+        stop_with_transport: bool,
+        config: ThreadConfig,
+    ) -> MscCueOut {
+        cues.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = Arc::clone(&running);
+
+        let handle = config.spawn("msc-cues", move || {
+            let mut state = CueState {
+                sink,
+                encoder,
+                cues,
+                stop_with_transport,
+                last_beat: None,
+            };
+            let mut session_state = SessionState::new();
+
+            while running_clone.load(Ordering::Acquire) {
+                if link.is_panicked() {
+                    // Cues passed during the panic are not sent afterwards
+                    state.last_beat = None;
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+                link.capture_app_session_state(&mut session_state);
+                link.apply_transport_policy(&mut session_state);
+                let sleep_time = state.update(&session_state, link.clock_micros(), link.quantum());
+                thread::sleep(sleep_time.min(POLL_INTERVAL));
+            }
+        });
+
+        MscCueOut {
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop the cue thread.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for MscCueOut {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// State of the cue thread.
+struct CueState<S: MidiSink> {
+    sink: S,
+    encoder: MscEncoder,
+    /// Sorted by beat
+    cues: Vec<MscCue>,
+    stop_with_transport: bool,
+    /// Beat of the previous update, while transport is playing
+    last_beat: Option<f64>,
+}
+
+impl<S: MidiSink> CueState<S> {
+    /// Send all cues passed since the last update. Returns the time until the next cue.
+    fn update(&mut self, session_state: &SessionState, time: i64, quantum: f64) -> Duration {
+        if !session_state.is_playing() {
+            if self.last_beat.take().is_some() && self.stop_with_transport {
+                debug!("send MSC stop");
+                self.sink.send(&self.encoder.encode(MscCommand::Stop, ""));
+            }
+            return POLL_INTERVAL;
+        }
+
+        let beat = session_state.beat_at_time(time, quantum);
+        let last_beat = match self.last_beat {
+            Some(last_beat) if last_beat <= beat => last_beat,
+            // Transport started or the beat jumped back
+            _ => beat,
+        };
+
+        for cue in &self.cues {
+            if cue.beat > last_beat && cue.beat <= beat {
+                debug!(beat = cue.beat, cue = cue.cue.as_str(), "send MSC cue");
+                self.sink.send(&self.encoder.encode(cue.command, &cue.cue));
+            }
+        }
+        self.last_beat = Some(beat);
+
+        match self.cues.iter().find(|cue| cue.beat > beat) {
+            Some(next) => {
+                let next_time = session_state.time_at_beat(next.beat, quantum);
+                Duration::from_micros((next_time - time).max(0) as u64)
+            }
+            None => POLL_INTERVAL,
+        }
+    }
+}
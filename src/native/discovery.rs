@@ -0,0 +1,250 @@
+use super::{
+    protocol::{
+        self, MessageType, NodeId, PeerState, DISCOVERY_PORT, MAX_MESSAGE_SIZE, MULTICAST_ADDR,
+    },
+    NetworkConfig,
+};
+use crate::Result;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::{
+    collections::HashMap,
+    io,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket},
+    time::{Duration, Instant},
+};
+
+/// Alive messages are sent this many times per TTL, like Link does.
+const TTL_RATIO: u32 = 20;
+
+/// A peer found by [Discovery].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Peer {
+    pub state: PeerState,
+    /// Address the peer sends its discovery messages from.
+    pub address: SocketAddr,
+    expires: Instant,
+}
+
+impl Peer {
+    /// When the peer is forgotten, unless it sends another alive message.
+    pub fn expires(&self) -> Instant {
+        self.expires
+    }
+}
+
+/// UDP multicast peer discovery, compatible with Link.
+///
+/// Announces the state of this peer to the multicast group, answers announcements of new
+/// peers and keeps track of all peers on the network. Call [Discovery::update] regularly
+/// (e.g. every 10ms), it never blocks.
+pub struct Discovery {
+    state: PeerState,
+    ttl: u8,
+    /// Group of the session, 0 unless discovery is restricted by a session key
+    group_id: u16,
+    /// Send alive messages and receive responses, one per interface
+    unicast: Vec<UdpSocket>,
+    /// Receives alive and bye bye messages of other peers
+    multicast: UdpSocket,
+    peers: HashMap<NodeId, Peer>,
+    next_broadcast: Instant,
+}
+
+impl Discovery {
+    /// Join the Link multicast group on the default interface and start announcing `state`.
+    pub fn new(state: PeerState) -> Result<Discovery> {
+        Self::with_interfaces(state, &[Ipv4Addr::UNSPECIFIED])
+    }
+
+    /// Join the Link multicast group only on the interfaces with the given addresses and
+    /// start announcing `state` on each of them.
+    ///
+    /// Restricting discovery keeps peers from being found on the wrong interface (e.g. a
+    /// VPN or virtual adapter) and keeps multicast traffic off networks where it is not
+    /// wanted. [Ipv4Addr::UNSPECIFIED] selects the default interface.
+    pub fn with_interfaces(state: PeerState, interfaces: &[Ipv4Addr]) -> Result<Discovery> {
+        Self::with_config(state, &NetworkConfig::default().with_interfaces(interfaces))
+    }
+
+    /// Join the Link multicast group and start announcing `state` as configured by `config`.
+    pub fn with_config(state: PeerState, config: &NetworkConfig) -> Result<Discovery> {
+        if config.interfaces.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no interfaces").into());
+        }
+        if config.ipv6 {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "IPv6 discovery").into());
+        }
+
+        let unicast = config
+            .interfaces
+            .iter()
+            .map(|&interface| unicast_socket(interface, config))
+            .collect::<io::Result<_>>()?;
+
+        Ok(Discovery {
+            state,
+            ttl: config.peer_ttl,
+            group_id: config.session_key.map_or(0, |key| key.group_id()),
+            unicast,
+            multicast: multicast_socket(&config.interfaces)?,
+            peers: HashMap::new(),
+            next_broadcast: Instant::now(),
+        })
+    }
+
+    /// The state announced by this peer.
+    pub fn state(&self) -> &PeerState {
+        &self.state
+    }
+
+    /// Change the announced state. Other peers are informed with the next [Discovery::update].
+    pub fn set_state(&mut self, state: PeerState) {
+        self.state = state;
+        self.next_broadcast = Instant::now();
+    }
+
+    /// All currently known peers, excluding this one.
+    pub fn peers(&self) -> impl Iterator<Item = &Peer> {
+        self.peers.values()
+    }
+
+    /// Number of currently known peers, excluding this one.
+    pub fn num_peers(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Handle all received messages, forget expired peers and announce this peer, if due.
+    pub fn update(&mut self) -> Result<()> {
+        let now = Instant::now();
+
+        self.receive_all(now)?;
+        self.peers.retain(|_, peer| peer.expires > now);
+
+        if now >= self.next_broadcast {
+            self.send(MessageType::Alive, multicast_endpoint())?;
+            self.next_broadcast = now + Duration::from_secs(self.ttl as u64) / TTL_RATIO;
+        }
+
+        Ok(())
+    }
+
+    fn receive_all(&mut self, now: Instant) -> Result<()> {
+        let mut buffer = [0; MAX_MESSAGE_SIZE];
+
+        for index in 0..=self.unicast.len() {
+            loop {
+                let socket = match index {
+                    0 => &self.multicast,
+                    _ => &self.unicast[index - 1],
+                };
+                let (len, from) = match socket.recv_from(&mut buffer) {
+                    Ok(received) => received,
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                    // Windows reports an ICMP port unreachable for an earlier send to a
+                    // peer that has gone on the next receive, skip it like the stale peer
+                    Err(e)
+                        if matches!(
+                            e.kind(),
+                            io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionRefused
+                        ) =>
+                    {
+                        continue
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                self.handle(&buffer[..len], from, now)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle(&mut self, message: &[u8], from: SocketAddr, now: Instant) -> Result<()> {
+        // Ignore anything that is not a valid discovery message
+        let Ok(message) = protocol::decode(message) else {
+            return Ok(());
+        };
+        let header = message.header;
+        if header.ident == self.state.node_id || header.group_id != self.group_id {
+            return Ok(());
+        }
+
+        match (header.message_type, message.state) {
+            (MessageType::ByeBye, _) => {
+                self.peers.remove(&header.ident);
+            }
+            (message_type, Some(state)) => {
+                let is_new = self
+                    .peers
+                    .insert(
+                        header.ident,
+                        Peer {
+                            state,
+                            address: from,
+                            expires: now + Duration::from_secs(header.ttl as u64),
+                        },
+                    )
+                    .is_none();
+
+                // Introduce ourselves to new peers right away, instead of letting them
+                // wait for our next alive message
+                if message_type == MessageType::Alive && is_new {
+                    self.send(MessageType::Response, from)?;
+                }
+            }
+            (_, None) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Send a message on every interface.
+    fn send(&self, message_type: MessageType, to: SocketAddr) -> Result<()> {
+        let message = protocol::encode_in_group(message_type, self.ttl, self.group_id, &self.state);
+        for socket in &self.unicast {
+            match socket.send_to(&message, to) {
+                Ok(_) => {}
+                // A full send buffer only delays discovery
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Discovery {
+    fn drop(&mut self) {
+        // Let other peers forget us right away, instead of waiting for the TTL
+        let _ = self.send(MessageType::ByeBye, multicast_endpoint());
+    }
+}
+
+fn multicast_endpoint() -> SocketAddr {
+    SocketAddrV4::new(MULTICAST_ADDR, DISCOVERY_PORT).into()
+}
+
+/// Socket sending to the multicast group on `interface`.
+fn unicast_socket(interface: Ipv4Addr, config: &NetworkConfig) -> io::Result<UdpSocket> {
+    let socket = UdpSocket::bind(SocketAddrV4::new(interface, 0))?;
+    socket2::SockRef::from(&socket).set_multicast_if_v4(&interface)?;
+    socket.set_multicast_ttl_v4(config.multicast_ttl)?;
+    socket.set_multicast_loop_v4(config.multicast_loop)?;
+    socket.set_nonblocking(true)?;
+    Ok(socket)
+}
+
+/// Socket bound to the discovery port, which can be shared with other Link peers
+/// on the same machine.
+fn multicast_socket(interfaces: &[Ipv4Addr]) -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT).into())?;
+    for interface in interfaces {
+        socket.join_multicast_v4(&MULTICAST_ADDR, interface)?;
+    }
+    socket.set_nonblocking(true)?;
+    Ok(socket.into())
+}
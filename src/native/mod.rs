@@ -0,0 +1,20 @@
+//! Pure Rust implementation of the Link protocol (feature `native`).
+//!
+//! This backend is developed incrementally and does not replace the bundled C++ Link yet.
+//! Currently implemented:
+//!
+//! - Encoding and decoding of the discovery messages ([protocol])
+//! - Timeline math ([Timeline], shared with `rusty_link_core`)
+//! - UDP multicast peer discovery ([Discovery]), configurable with [NetworkConfig]
+//!
+//! Clock measurement between peers and session timeline merging are missing, so the
+//! native backend can find and track peers and their timelines, but can not join a session.
+
+mod discovery;
+mod network_config;
+pub mod protocol;
+
+pub use discovery::{Discovery, Peer};
+pub use network_config::NetworkConfig;
+pub use protocol::{NodeId, PeerState, StartStopState};
+pub use rusty_link_core::Timeline;
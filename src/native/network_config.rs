@@ -0,0 +1,74 @@
+use super::protocol::DEFAULT_TTL;
+use crate::SessionKey;
+use std::net::Ipv4Addr;
+
+/// Networking options of [Discovery](super::Discovery), applied at construction with
+/// [Discovery::with_config](super::Discovery::with_config), e.g. for venue networks with
+/// unusual multicast policies.
+///
+/// The bundled C++ Link has no networking options in its C API, these only apply to the
+/// native backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkConfig {
+    /// Addresses of the interfaces to discover peers on. [Ipv4Addr::UNSPECIFIED] selects
+    /// the default interface.
+    pub interfaces: Vec<Ipv4Addr>,
+    /// IP time to live of multicast messages, i.e. the number of routers they may cross.
+    /// 1 keeps them on the local network.
+    pub multicast_ttl: u32,
+    /// Receive our own multicast messages, needed for peers in other processes on this
+    /// machine.
+    pub multicast_loop: bool,
+    /// Seconds after which other peers forget this peer, unless it announces itself again.
+    pub peer_ttl: u8,
+    /// Discover peers over IPv6. Not supported by the native backend yet, constructing
+    /// [Discovery](super::Discovery) fails if set.
+    pub ipv6: bool,
+    /// Only discover peers with the same key. Such peers are invisible to Link.
+    pub session_key: Option<SessionKey>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            interfaces: vec![Ipv4Addr::UNSPECIFIED],
+            multicast_ttl: 1,
+            multicast_loop: true,
+            peer_ttl: DEFAULT_TTL,
+            ipv6: false,
+            session_key: None,
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn with_interfaces(mut self, interfaces: &[Ipv4Addr]) -> Self {
+        self.interfaces = interfaces.to_vec();
+        self
+    }
+
+    pub fn with_multicast_ttl(mut self, ttl: u32) -> Self {
+        self.multicast_ttl = ttl;
+        self
+    }
+
+    pub fn with_multicast_loop(mut self, enable: bool) -> Self {
+        self.multicast_loop = enable;
+        self
+    }
+
+    pub fn with_peer_ttl(mut self, seconds: u8) -> Self {
+        self.peer_ttl = seconds;
+        self
+    }
+
+    pub fn with_ipv6(mut self, enable: bool) -> Self {
+        self.ipv6 = enable;
+        self
+    }
+
+    pub fn with_session_key(mut self, key: SessionKey) -> Self {
+        self.session_key = Some(key);
+        self
+    }
+}
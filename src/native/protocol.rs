@@ -0,0 +1,300 @@
+//! Wire format of the Link discovery protocol (version 1).
+//!
+//! Every message starts with [PROTOCOL_HEADER], followed by a message header and a list of
+//! payload entries. Entries are prefixed with a four character key and their size, so
+//! unknown entries can be skipped. All integers are big endian.
+
+use super::Timeline;
+use crate::{Error, Result};
+use std::{
+    fmt,
+    net::{Ipv4Addr, SocketAddrV4},
+};
+
+/// Multicast group of Link discovery.
+pub const MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(224, 76, 78, 75);
+/// Port of Link discovery.
+pub const DISCOVERY_PORT: u16 = 20808;
+/// First bytes of every discovery message.
+pub const PROTOCOL_HEADER: [u8; 8] = *b"_asdp_v\x01";
+/// Largest discovery message sent by Link.
+pub const MAX_MESSAGE_SIZE: usize = 512;
+/// Seconds a peer stays known without a new alive message.
+pub const DEFAULT_TTL: u8 = 5;
+
+const TIMELINE_KEY: u32 = u32::from_be_bytes(*b"tmln");
+const SESSION_MEMBERSHIP_KEY: u32 = u32::from_be_bytes(*b"sess");
+const START_STOP_STATE_KEY: u32 = u32::from_be_bytes(*b"stst");
+const MEASUREMENT_ENDPOINT_V4_KEY: u32 = u32::from_be_bytes(*b"mep4");
+
+/// Identifier of a peer or, for the peer which founded it, of a session.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(pub [u8; 8]);
+
+impl NodeId {
+    /// A random alphanumeric id, like the ones generated by Link.
+    pub fn random() -> Self {
+        use std::{
+            collections::hash_map::RandomState,
+            hash::{BuildHasher, Hasher},
+            time::SystemTime,
+        };
+
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos(),
+        );
+        hasher.write_u32(std::process::id());
+        let mut bits = hasher.finish();
+
+        let mut id = [0; 8];
+        for byte in &mut id {
+            *byte = CHARS[(bits % CHARS.len() as u64) as usize];
+            bits /= CHARS.len() as u64;
+        }
+        NodeId(id)
+    }
+}
+
+impl fmt::Debug for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NodeId({})", self)
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.0))
+    }
+}
+
+/// Kind of a discovery message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageType {
+    /// Periodic announcement of a peer, sent to the multicast group.
+    Alive = 1,
+    /// Answer to an alive message of a new peer, sent directly to it.
+    Response = 2,
+    /// A peer leaves, sent to the multicast group.
+    ByeBye = 3,
+}
+
+/// Header of every discovery message, following [PROTOCOL_HEADER].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageHeader {
+    pub message_type: MessageType,
+    /// Seconds the receiver should remember the sender.
+    pub ttl: u8,
+    pub group_id: u16,
+    pub ident: NodeId,
+}
+
+/// Transport state shared between peers with start/stop sync enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartStopState {
+    pub is_playing: bool,
+    /// Beat of the state change, in micro beats.
+    pub beats: i64,
+    /// Time of the state change (Link clock in microseconds).
+    pub timestamp: i64,
+}
+
+/// State announced by a peer in its alive and response messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerState {
+    pub node_id: NodeId,
+    pub session_id: NodeId,
+    pub timeline: Timeline,
+    pub start_stop_state: Option<StartStopState>,
+    /// Endpoint for clock measurements of the peer.
+    pub measurement_endpoint: Option<SocketAddrV4>,
+}
+
+/// A decoded discovery message. ByeBye messages carry no state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Message {
+    pub header: MessageHeader,
+    pub state: Option<PeerState>,
+}
+
+/// Encode a message of type `message_type` announcing `state`.
+pub fn encode(message_type: MessageType, ttl: u8, state: &PeerState) -> Vec<u8> {
+    encode_in_group(message_type, ttl, 0, state)
+}
+
+/// [encode] with the group id of a private session, see
+/// [SessionKey::group_id](crate::SessionKey::group_id). Link only talks to group 0.
+pub fn encode_in_group(
+    message_type: MessageType,
+    ttl: u8,
+    group_id: u16,
+    state: &PeerState,
+) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(MAX_MESSAGE_SIZE);
+    buffer.extend_from_slice(&PROTOCOL_HEADER);
+    buffer.push(message_type as u8);
+    buffer.push(ttl);
+    buffer.extend_from_slice(&group_id.to_be_bytes());
+    buffer.extend_from_slice(&state.node_id.0);
+
+    if message_type == MessageType::ByeBye {
+        return buffer;
+    }
+
+    let timeline = &state.timeline;
+    entry(&mut buffer, TIMELINE_KEY, |b| {
+        b.extend_from_slice(&timeline.micros_per_beat.to_be_bytes());
+        b.extend_from_slice(&timeline.beat_origin.to_be_bytes());
+        b.extend_from_slice(&timeline.time_origin.to_be_bytes());
+    });
+    entry(&mut buffer, SESSION_MEMBERSHIP_KEY, |b| {
+        b.extend_from_slice(&state.session_id.0)
+    });
+    if let Some(start_stop) = &state.start_stop_state {
+        entry(&mut buffer, START_STOP_STATE_KEY, |b| {
+            b.push(start_stop.is_playing as u8);
+            b.extend_from_slice(&start_stop.beats.to_be_bytes());
+            b.extend_from_slice(&start_stop.timestamp.to_be_bytes());
+        });
+    }
+    if let Some(endpoint) = &state.measurement_endpoint {
+        entry(&mut buffer, MEASUREMENT_ENDPOINT_V4_KEY, |b| {
+            b.extend_from_slice(&endpoint.ip().octets());
+            b.extend_from_slice(&endpoint.port().to_be_bytes());
+        });
+    }
+
+    buffer
+}
+
+fn entry<F: FnOnce(&mut Vec<u8>)>(buffer: &mut Vec<u8>, key: u32, write: F) {
+    buffer.extend_from_slice(&key.to_be_bytes());
+    let size_position = buffer.len();
+    buffer.extend_from_slice(&0u32.to_be_bytes());
+    write(buffer);
+    let size = (buffer.len() - size_position - 4) as u32;
+    buffer[size_position..size_position + 4].copy_from_slice(&size.to_be_bytes());
+}
+
+/// Decode a discovery message. Unknown payload entries are skipped.
+pub fn decode(message: &[u8]) -> Result<Message> {
+    let mut reader = Reader(message);
+
+    if reader.bytes(PROTOCOL_HEADER.len())? != PROTOCOL_HEADER {
+        return Err(invalid("not a Link discovery message"));
+    }
+
+    let message_type = match reader.u8()? {
+        1 => MessageType::Alive,
+        2 => MessageType::Response,
+        3 => MessageType::ByeBye,
+        _ => return Err(invalid("unknown message type")),
+    };
+    let header = MessageHeader {
+        message_type,
+        ttl: reader.u8()?,
+        group_id: reader.u16()?,
+        ident: NodeId(reader.array()?),
+    };
+
+    if message_type == MessageType::ByeBye {
+        return Ok(Message {
+            header,
+            state: None,
+        });
+    }
+
+    let mut timeline = None;
+    let mut session_id = None;
+    let mut start_stop_state = None;
+    let mut measurement_endpoint = None;
+
+    while !reader.0.is_empty() {
+        let key = reader.u32()?;
+        let size = reader.u32()? as usize;
+        let mut value = Reader(reader.bytes(size)?);
+
+        match key {
+            TIMELINE_KEY => {
+                timeline = Some(Timeline {
+                    micros_per_beat: value.i64()?,
+                    beat_origin: value.i64()?,
+                    time_origin: value.i64()?,
+                })
+            }
+            SESSION_MEMBERSHIP_KEY => session_id = Some(NodeId(value.array()?)),
+            START_STOP_STATE_KEY => {
+                start_stop_state = Some(StartStopState {
+                    is_playing: value.u8()? != 0,
+                    beats: value.i64()?,
+                    timestamp: value.i64()?,
+                })
+            }
+            MEASUREMENT_ENDPOINT_V4_KEY => {
+                let ip: [u8; 4] = value.array()?;
+                measurement_endpoint = Some(SocketAddrV4::new(ip.into(), value.u16()?));
+            }
+            _ => {}
+        }
+    }
+
+    let timeline = timeline.ok_or_else(|| invalid("missing timeline"))?;
+    if timeline.micros_per_beat <= 0 {
+        return Err(invalid("invalid tempo"));
+    }
+
+    Ok(Message {
+        header,
+        state: Some(PeerState {
+            node_id: header.ident,
+            session_id: session_id.ok_or_else(|| invalid("missing session membership"))?,
+            timeline,
+            start_stop_state,
+            measurement_endpoint,
+        }),
+    })
+}
+
+fn invalid(msg: &'static str) -> Error {
+    Error::InvalidMessage(msg)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid("truncated message"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        Ok(i64::from_be_bytes(self.array()?))
+    }
+}
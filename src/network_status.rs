@@ -0,0 +1,150 @@
+use crate::{AblLink, LinkEvent, ThreadConfig};
+use std::{
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Multicast group and port of Link discovery, see `native::protocol`.
+const MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(224, 76, 78, 75);
+const DISCOVERY_PORT: u16 = 20808;
+
+/// Whether an [AblLink] instance can find peers, returned by [AblLink::enable_status].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnableStatus {
+    Disabled,
+    /// Enabled with a network interface which can reach the Link multicast group.
+    Enabled,
+    /// Enabled, but constructed offline, see [AblLink::is_offline].
+    Offline,
+    /// Enabled, but no network interface can reach the Link multicast group, e.g. without
+    /// network or on Wi-Fi blocking multicast. Link never finds peers in this state.
+    IsolatedNoNetwork,
+}
+
+/// Is there a network interface, which can join the Link multicast group?
+///
+/// No packets are sent: connecting a UDP socket only picks the interface of the route.
+pub(crate) fn probe_network() -> bool {
+    let interface = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((MULTICAST_ADDR, DISCOVERY_PORT))?;
+            socket.local_addr()
+        })
+        .ok()
+        .and_then(|addr| match addr {
+            SocketAddr::V4(addr) if !addr.ip().is_loopback() && !addr.ip().is_unspecified() => {
+                Some(*addr.ip())
+            }
+            _ => None,
+        });
+
+    let Some(interface) = interface else {
+        return false;
+    };
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| socket.join_multicast_v4(&MULTICAST_ADDR, &interface))
+        .is_ok()
+}
+
+impl AblLink {
+    /// Is Link enabled and able to find peers?
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// Probes the network, see [EnableStatus::IsolatedNoNetwork]. Use a [NetworkMonitor] to
+    /// be notified when the network comes back.
+    pub fn enable_status(&self) -> EnableStatus {
+        if !self.is_enabled() {
+            EnableStatus::Disabled
+        } else if self.is_offline() {
+            EnableStatus::Offline
+        } else if probe_network() {
+            EnableStatus::Enabled
+        } else {
+            EnableStatus::IsolatedNoNetwork
+        }
+    }
+}
+
+/// Probes the network periodically and queues a [LinkEvent::NetworkChanged] when the Link
+/// multicast group becomes reachable or unreachable, see [AblLink::enable_status].
+///
+/// The network is assumed to be available when the monitor starts, so the first probe
+/// queues an event only if it is not.
+pub struct NetworkMonitor {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl NetworkMonitor {
+    /// Start probing the network for `link` every `interval`.
+    pub fn start(link: Arc<AblLink>, interval: Duration) -> NetworkMonitor {
+        Self::start_with_config(link, interval, ThreadConfig::default())
+    }
+
+    /// Same as [NetworkMonitor::start], with a custom configuration of the monitor thread.
+    pub fn start_with_config(
+        link: Arc<AblLink>,
+        interval: Duration,
+        config: ThreadConfig,
+    ) -> NetworkMonitor {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = Arc::clone(&running);
+
+        let handle = config.spawn("network", move || {
+            let mut available = true;
+
+            while running_clone.load(Ordering::Acquire) {
+                let probed = probe_network();
+                if probed != available {
+                    available = probed;
+                    debug!(available, "network changed");
+                    let event = LinkEvent::NetworkChanged { available };
+                    #[cfg(feature = "metrics")]
+                    crate::telemetry::record_event(event);
+                    link.callbacks.record(event, link.clock_micros());
+                }
+
+                // Woken up early by `stop`
+                let deadline = Instant::now() + interval;
+                while running_clone.load(Ordering::Acquire) {
+                    let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                        break;
+                    };
+                    thread::park_timeout(remaining);
+                }
+            }
+        });
+
+        NetworkMonitor {
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop the monitor thread.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for NetworkMonitor {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
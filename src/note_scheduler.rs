@@ -0,0 +1,307 @@
+use crate::{AblLink, Error, GateMode, GateState, Result, SessionState, TransportGate};
+use std::{cmp::Ordering, collections::BinaryHeap, time::Duration};
+
+/// How a [NoteScheduler] handles pending messages when transport stops, while its
+/// [TransportGate] pauses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StopPolicy {
+    /// Drop pending messages and send note offs for all sounding notes.
+    #[default]
+    Flush,
+    /// Keep pending messages until transport plays again. Messages of beats which passed
+    /// in the meantime are emitted late, right after transport starts.
+    Hold,
+}
+
+/// Longest MIDI message a [NoteScheduler] can schedule, in bytes.
+pub const MAX_SCHEDULED_MESSAGE: usize = 3;
+
+/// A MIDI message emitted by a [NoteScheduler].
+///
+/// The message is stored inline, so emitting never allocates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScheduledNote {
+    pub beat: f64,
+    /// Link clock time at which the message should be sent, in microseconds.
+    pub time: i64,
+    message: Message,
+}
+
+impl ScheduledNote {
+    /// The bytes of the MIDI message.
+    pub fn message(&self) -> &[u8] {
+        self.message.as_bytes()
+    }
+}
+
+/// A MIDI channel message of up to [MAX_SCHEDULED_MESSAGE] bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Message {
+    bytes: [u8; MAX_SCHEDULED_MESSAGE],
+    len: u8,
+}
+
+impl Message {
+    fn new(message: &[u8]) -> Option<Message> {
+        let mut bytes = [0; MAX_SCHEDULED_MESSAGE];
+        bytes.get_mut(..message.len())?.copy_from_slice(message);
+        Some(Message {
+            bytes,
+            len: message.len() as u8,
+        })
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+}
+
+struct Pending {
+    beat: f64,
+    sequence: u64,
+    message: Message,
+}
+
+// BinaryHeap is a max-heap, so the earliest message has to compare as the greatest
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .beat
+            .total_cmp(&self.beat)
+            .then(other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Pending {}
+
+/// Converts MIDI messages scheduled at beats into messages with Link clock timestamps.
+///
+/// Messages are emitted to `emit` a lookahead ahead of their time, with the time computed
+/// from the current Session State, so tempo changes between scheduling and emitting are
+/// taken into account. Pass a closure sending into a channel to receive them elsewhere.
+/// By default messages are only emitted while transport plays, see [TransportGate] and
+/// [StopPolicy].
+pub struct NoteScheduler<F: FnMut(ScheduledNote)> {
+    emit: F,
+    quantum: f64,
+    lookahead: Duration,
+    stop_policy: StopPolicy,
+    gate: TransportGate,
+    queue: BinaryHeap<Pending>,
+    next_sequence: u64,
+    /// Bit per note of every channel, set between note on and note off
+    sounding: [u128; 16],
+    session_state: SessionState,
+}
+
+impl<F: FnMut(ScheduledNote)> NoteScheduler<F> {
+    /// A scheduler with bars of `quantum` beats and 10ms of lookahead.
+    pub fn new(quantum: f64, emit: F) -> Self {
+        Self {
+            emit,
+            quantum,
+            lookahead: Duration::from_millis(10),
+            stop_policy: StopPolicy::default(),
+            gate: TransportGate::default(),
+            queue: BinaryHeap::new(),
+            next_sequence: 0,
+            sounding: [0; 16],
+            session_state: SessionState::new(),
+        }
+    }
+
+    /// How far ahead of their time messages are emitted.
+    pub fn with_lookahead(mut self, lookahead: Duration) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    pub fn with_stop_policy(mut self, stop_policy: StopPolicy) -> Self {
+        self.stop_policy = stop_policy;
+        self
+    }
+
+    /// Behavior while transport is stopped. Muted messages are dropped, except note offs
+    /// of sounding notes.
+    pub fn with_transport_gate(mut self, gate: TransportGate) -> Self {
+        self.gate = gate;
+        self
+    }
+
+    /// Schedule `message` at `beat`. Fails with [Error::InvalidMessage] for messages longer
+    /// than [MAX_SCHEDULED_MESSAGE] bytes, e.g. SysEx.
+    pub fn schedule(&mut self, beat: f64, message: &[u8]) -> Result<()> {
+        let message =
+            Message::new(message).ok_or(Error::InvalidMessage("MIDI message too long"))?;
+        self.push(beat, message);
+        Ok(())
+    }
+
+    /// Schedule a note on at `beat` and its note off `length` beats later.
+    pub fn schedule_note(&mut self, beat: f64, length: f64, channel: u8, note: u8, velocity: u8) {
+        let channel = channel & 0x0F;
+        let note = note & 0x7F;
+        self.push(beat, note_message(0x90 | channel, note, velocity & 0x7F));
+        self.push(beat + length, note_message(0x80 | channel, note, 0));
+    }
+
+    fn push(&mut self, beat: f64, message: Message) {
+        self.queue.push(Pending {
+            beat,
+            sequence: self.next_sequence,
+            message,
+        });
+        self.next_sequence += 1;
+    }
+
+    /// Number of messages which were not emitted yet.
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Drop all pending messages and send note offs for all sounding notes at `now`.
+    pub fn panic(&mut self, now: i64) {
+        self.queue.clear();
+        let beat = self.session_state.beat_at_time(now, self.quantum);
+        self.release_all(beat, now);
+    }
+
+    /// Capture the app Session State of `link` and emit the messages due at the current
+    /// time plus the lookahead. Returns the number of emitted messages.
+    ///
+    /// Calls [NoteScheduler::panic] after [AblLink::panic].
+    pub fn poll(&mut self, link: &AblLink) -> usize {
+        self.poll_at(link, link.clock_micros())
+    }
+
+    /// Same as [NoteScheduler::poll], at the Link clock time `now` instead of the current
+    /// time, e.g. a virtual time in tests. Uses the app Session State, so it does not fit
+    /// the audio thread: call [NoteScheduler::advance_to] with the audio Session State instead.
+    pub fn poll_at(&mut self, link: &AblLink, now: i64) -> usize {
+        if link.is_panicked() {
+            self.panic(now);
+            return 0;
+        }
+        link.capture_app_session_state(&mut self.session_state);
+        link.apply_transport_policy(&mut self.session_state);
+        let lookahead = i64::try_from(self.lookahead.as_micros()).unwrap_or(i64::MAX);
+        self.emit_until(now.saturating_add(lookahead))
+    }
+
+    /// Emit the messages due up to the Link clock time `until` according to
+    /// `session_state`, without lookahead. Returns the number of emitted messages.
+    ///
+    /// Pull-based alternative to [NoteScheduler::poll] for contexts which can not run a
+    /// timing thread, e.g. plugins: call it from the audio callback with the audio Session
+    /// State and the end of the buffer, see [AudioBuffer](crate::AudioBuffer).
+    ///
+    ///  Realtime-safe: yes
+    pub fn advance_to(&mut self, session_state: &SessionState, until: i64) -> usize {
+        self.session_state.copy_from(session_state);
+        self.emit_until(until)
+    }
+
+    fn emit_until(&mut self, until: i64) -> usize {
+        if !self.session_state.is_playing() && self.gate.mode == GateMode::Pause {
+            self.on_stop();
+            return 0;
+        }
+
+        let until = self.session_state.beat_at_time(until, self.quantum);
+        let mut count = 0;
+        while let Some(next) = self.queue.peek() {
+            if next.beat > until {
+                break;
+            }
+            let state = self
+                .gate
+                .state_at_beat(&self.session_state, next.beat, self.quantum);
+            let Some(pending) = self.queue.pop() else {
+                break;
+            };
+            let send = match state {
+                GateState::Open => true,
+                // Before the resume beat of a started transport
+                GateState::Paused if self.stop_policy == StopPolicy::Hold => true,
+                GateState::Paused | GateState::Muted => {
+                    self.releases_sounding_note(pending.message.as_bytes())
+                }
+            };
+            if send {
+                let time = self.session_state.time_at_beat(pending.beat, self.quantum);
+                self.send(pending.beat, time, pending.message);
+                count += 1;
+            }
+        }
+        count
+    }
+
+    fn releases_sounding_note(&self, message: &[u8]) -> bool {
+        match *message {
+            [status, note, velocity, ..]
+                if status & 0xF0 == 0x80 || status & 0xF0 == 0x90 && velocity == 0 =>
+            {
+                self.sounding[(status & 0x0F) as usize] & (1u128 << (note & 0x7F)) != 0
+            }
+            _ => false,
+        }
+    }
+
+    fn on_stop(&mut self) {
+        if self.stop_policy == StopPolicy::Hold {
+            return;
+        }
+        self.queue.clear();
+
+        let now = self.session_state.time_for_is_playing() as i64;
+        let beat = self.session_state.beat_at_time(now, self.quantum);
+        self.release_all(beat, now);
+    }
+
+    /// Send note offs for all sounding notes.
+    fn release_all(&mut self, beat: f64, now: i64) {
+        for channel in 0..16u8 {
+            while self.sounding[channel as usize] != 0 {
+                let note = self.sounding[channel as usize].trailing_zeros() as u8;
+                self.send(beat, now, note_message(0x80 | channel, note, 0));
+            }
+        }
+    }
+
+    fn send(&mut self, beat: f64, time: i64, message: Message) {
+        if let [status, note, velocity] = *message.as_bytes() {
+            let channel = (status & 0x0F) as usize;
+            let bit = 1u128 << (note & 0x7F);
+            match status & 0xF0 {
+                0x90 if velocity > 0 => self.sounding[channel] |= bit,
+                0x80 | 0x90 => self.sounding[channel] &= !bit,
+                _ => {}
+            }
+        }
+
+        (self.emit)(ScheduledNote {
+            beat,
+            time,
+            message,
+        });
+    }
+}
+
+fn note_message(status: u8, note: u8, velocity: u8) -> Message {
+    Message {
+        bytes: [status, note, velocity],
+        len: 3,
+    }
+}
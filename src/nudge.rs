@@ -0,0 +1,66 @@
+use crate::{AblLink, SessionState};
+
+impl AblLink {
+    /// Shift the beat of this peer by `delta` beats at the current time, e.g. for a DJ's
+    /// nudge buttons. Returns the shift which was applied.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// Uses [SessionState::request_beat_at_time], so the shift is only exact while this
+    /// instance is alone in its session. With peers, Link keeps the phase of the session
+    /// and maps the requested beat to the next time with the same phase. The beat numbering
+    /// changes, but the phase does not. Apps which need to shift their own playback against
+    /// a session with peers should offset it instead, e.g. with
+    /// [TimelineMap::set_offset](crate::TimelineMap::set_offset).
+    pub fn nudge_beats(&self, delta: f64, quantum: f64) -> f64 {
+        let mut session_state = SessionState::new();
+        self.capture_app_session_state(&mut session_state);
+        let now = self.clock_micros();
+        let before = session_state.beat_at_time(now, quantum);
+
+        session_state.request_beat_at_time(before + delta, now, quantum);
+        self.commit_app_session_state(&session_state);
+
+        let after = session_state.beat_at_time(now, quantum);
+        debug!(delta, applied = after - before, "nudge");
+        after - before
+    }
+
+    /// [AblLink::nudge_beats] with the quantum of the app, see [AblLink::quantum].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn nudge(&self, delta: f64) -> f64 {
+        self.nudge_beats(delta, self.quantum())
+    }
+}
+
+/// A momentary nudge, which is returned when released, like holding a DJ's nudge button.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Nudge {
+    quantum: f64,
+    applied: f64,
+}
+
+impl Nudge {
+    /// Shift the beat of `link` by `delta` beats, see [AblLink::nudge_beats].
+    pub fn start(link: &AblLink, delta: f64, quantum: f64) -> Nudge {
+        Nudge {
+            quantum,
+            applied: link.nudge_beats(delta, quantum),
+        }
+    }
+
+    /// The shift which was applied by [Nudge::start].
+    pub fn applied(&self) -> f64 {
+        self.applied
+    }
+
+    /// Undo the shift.
+    pub fn release(self, link: &AblLink) {
+        link.nudge_beats(-self.applied, self.quantum);
+    }
+}
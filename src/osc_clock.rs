@@ -0,0 +1,99 @@
+use crate::{AblLink, SessionState};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Number of clock reads when calibrating an [OscClock] or a [WallClock](crate::WallClock).
+const CALIBRATION_ROUNDS: usize = 16;
+
+/// Seconds from the NTP epoch (1900) to the UNIX epoch (1970).
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// The OSC time tag meaning "immediately".
+pub const OSC_IMMEDIATELY: u64 = 1;
+
+/// Conversions from Link time to OSC time tags (64 bit NTP format), so OSC receivers
+/// honoring time tags, e.g. SuperCollider, render scheduled events sample-accurately
+/// instead of on arrival.
+///
+/// Like [WallClock](crate::WallClock), the offset between the Link clock and the system
+/// clock is measured once by [OscClock::calibrate]. Time tags refer to the system clock of
+/// the receiver, so sender and receiver should be synchronized, e.g. by NTP. Bundles have
+/// to be sent ahead of their time tag, by more than the network latency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OscClock {
+    /// System time since the UNIX epoch minus Link host time, in microseconds
+    offset: i64,
+    /// Output latency of the receiver in microseconds, subtracted from time tags
+    latency: i64,
+}
+
+impl OscClock {
+    /// Measure the offset between the Link clock of `link` and the system clock.
+    pub fn calibrate(link: &AblLink) -> Self {
+        Self::from_offset(system_clock_offset(link))
+    }
+
+    /// A clock with a known offset in microseconds of the system time since the UNIX
+    /// epoch minus the Link host time.
+    pub fn from_offset(offset: i64) -> Self {
+        Self { offset, latency: 0 }
+    }
+
+    /// Compensate the output latency of the receiver: time tags are earlier by `latency`,
+    /// so events are heard at their Link time.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = i64::try_from(latency.as_micros()).unwrap_or(i64::MAX);
+        self
+    }
+
+    /// The OSC time tag of a Link host time in microseconds, e.g. [AblLink::clock_micros].
+    pub fn time_tag_at(&self, host: i64) -> u64 {
+        let micros = host
+            .saturating_add(self.offset)
+            .saturating_sub(self.latency);
+        let seconds = micros.div_euclid(1_000_000) as u64 + NTP_UNIX_OFFSET;
+        let fraction = ((micros.rem_euclid(1_000_000) as u64) << 32) / 1_000_000;
+        (seconds << 32) | fraction
+    }
+
+    /// The Link host time in microseconds of an OSC time tag.
+    pub fn host_at_time_tag(&self, time_tag: u64) -> i64 {
+        let seconds = (time_tag >> 32) as i64 - NTP_UNIX_OFFSET as i64;
+        let fraction = (((time_tag & 0xFFFF_FFFF) * 1_000_000 + (1 << 31)) >> 32) as i64;
+        (seconds * 1_000_000 + fraction) - self.offset + self.latency
+    }
+
+    /// The OSC time tag at which `beat` occurs on the timeline of `session_state`.
+    pub fn time_tag_at_beat(&self, session_state: &SessionState, beat: f64, quantum: f64) -> u64 {
+        self.time_tag_at(session_state.time_at_beat(beat, quantum))
+    }
+}
+
+/// Measure the system time since the UNIX epoch minus the Link host time of `link`, in
+/// microseconds.
+///
+/// Uses the fastest of a few paired clock reads, which are least disturbed by preemption.
+pub(crate) fn system_clock_offset(link: &AblLink) -> i64 {
+    let mut best = None;
+    for _ in 0..CALIBRATION_ROUNDS {
+        let before = link.clock_micros();
+        let system = unix_micros(SystemTime::now());
+        let after = link.clock_micros();
+
+        let round_trip = after - before;
+        let offset = system - (before + round_trip / 2);
+        match best {
+            Some((best_round_trip, _)) if best_round_trip <= round_trip => {}
+            _ => best = Some((round_trip, offset)),
+        }
+    }
+
+    let (_, offset) = best.expect("at least one calibration round");
+    offset
+}
+
+fn unix_micros(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_micros() as i64,
+        Err(before) => -(before.duration().as_micros() as i64),
+    }
+}
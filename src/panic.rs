@@ -0,0 +1,55 @@
+use crate::{AblLink, LinkRegistry, SessionState};
+use std::sync::atomic::Ordering;
+
+impl AblLink {
+    /// Silence all crate-managed outputs following this instance, e.g. from a big red
+    /// button of a live performance, and stop transport if `stop_transport` is set.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// [MidiClockOut](crate::MidiClockOut) sends a MIDI stop message,
+    /// [MscCueOut](crate::MscCueOut) stops sending cues, and the next poll of a
+    /// [NoteScheduler](crate::NoteScheduler), [StepClock](crate::StepClock) or
+    /// [TempoClock](crate::TempoClock) drops everything scheduled, sending note offs for
+    /// sounding notes. Outputs stay silent until [AblLink::clear_panic].
+    pub fn panic(&self, stop_transport: bool) {
+        warn!(stop_transport, "panic");
+        self.panicked.store(true, Ordering::Release);
+
+        if stop_transport {
+            let mut session_state = SessionState::new();
+            self.capture_app_session_state(&mut session_state);
+            if session_state.is_playing() {
+                session_state.set_is_playing(false, self.clock_micros() as u64);
+                self.commit_app_session_state(&session_state);
+            }
+        }
+    }
+
+    /// Did [AblLink::panic] silence the outputs of this instance?
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn is_panicked(&self) -> bool {
+        self.panicked.load(Ordering::Acquire)
+    }
+
+    /// Let the outputs silenced by [AblLink::panic] follow the session again.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn clear_panic(&self) {
+        self.panicked.store(false, Ordering::Release);
+    }
+}
+
+/// [AblLink::panic] for every instance of [LinkRegistry::global].
+pub fn panic_all(stop_transport: bool) {
+    for link in LinkRegistry::global().instances() {
+        link.panic(stop_transport);
+    }
+}
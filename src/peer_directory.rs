@@ -0,0 +1,218 @@
+use crate::{Error, Result, ThreadConfig};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::{
+    collections::HashMap,
+    fmt, io,
+    net::IpAddr,
+    process,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// DNS-SD service type announced by [PeerDirectory].
+pub const PEER_SERVICE_TYPE: &str = "_rusty-link._udp.local.";
+
+/// Port announced with the service. Nothing listens on it, it is the port of Link discovery.
+const ANNOUNCED_PORT: u16 = 20808;
+
+/// An app announced by a [PeerDirectory] on the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerInfo {
+    /// Unique per running instance.
+    pub instance_id: String,
+    pub app_name: String,
+    /// Host name without the `.local.` suffix.
+    pub host_name: String,
+    pub addresses: Vec<IpAddr>,
+}
+
+/// `app name (host name)`, e.g. `Ableton Live (studio-mac)`.
+impl fmt::Display for PeerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.app_name, self.host_name)
+    }
+}
+
+type Peers = Arc<Mutex<HashMap<String, PeerInfo>>>;
+
+/// Names of the apps in a Link session, which Link itself does not share.
+///
+/// Announces this app with mDNS/DNS-SD and browses for other announcing apps. Only apps
+/// using a [PeerDirectory] can be listed, so there can be fewer named peers than
+/// [AblLink::num_peers](crate::AblLink::num_peers).
+pub struct PeerDirectory {
+    daemon: ServiceDaemon,
+    fullname: String,
+    instance_id: String,
+    peers: Peers,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PeerDirectory {
+    /// Announce this app as `app_name` and start looking for other apps.
+    pub fn start(app_name: &str) -> Result<PeerDirectory> {
+        Self::start_with_config(app_name, ThreadConfig::default())
+    }
+
+    /// Same as [PeerDirectory::start], with a custom configuration of the browsing thread.
+    pub fn start_with_config(app_name: &str, config: ThreadConfig) -> Result<PeerDirectory> {
+        let daemon = ServiceDaemon::new().map_err(mdns_error)?;
+        let host_name = host_name();
+        let instance_id = instance_id();
+
+        let properties = [
+            ("app", app_name),
+            ("host", host_name.as_str()),
+            ("id", instance_id.as_str()),
+        ];
+        let service = ServiceInfo::new(
+            PEER_SERVICE_TYPE,
+            &instance_id,
+            &format!("{}.local.", host_name),
+            "",
+            ANNOUNCED_PORT,
+            &properties[..],
+        )
+        .map_err(mdns_error)?
+        .enable_addr_auto();
+        let fullname = service.get_fullname().to_string();
+        daemon.register(service).map_err(mdns_error)?;
+
+        let events = daemon.browse(PEER_SERVICE_TYPE).map_err(mdns_error)?;
+        let peers = Peers::default();
+        let peers_clone = Arc::clone(&peers);
+        let own_fullname = fullname.clone();
+
+        // Ends when the daemon shuts down and closes the channel
+        let handle = config.spawn("peers", move || {
+            while let Ok(event) = events.recv() {
+                let mut peers = peers_clone.lock().unwrap_or_else(|e| e.into_inner());
+                match event {
+                    ServiceEvent::ServiceResolved(info) if info.get_fullname() != own_fullname => {
+                        let peer = peer_info(&info);
+                        debug!(%peer, "peer announced");
+                        peers.insert(info.get_fullname().to_string(), peer);
+                    }
+                    ServiceEvent::ServiceRemoved(_, fullname) => {
+                        peers.remove(&fullname);
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        Ok(PeerDirectory {
+            daemon,
+            fullname,
+            instance_id,
+            peers,
+            handle: Some(handle),
+        })
+    }
+
+    /// The instance id announced for this app.
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// The other announced apps, ordered by app and host name.
+    pub fn peers(&self) -> Vec<PeerInfo> {
+        let mut peers: Vec<PeerInfo> = self
+            .peers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        peers.sort_by(|a, b| {
+            (&a.app_name, &a.host_name, &a.instance_id).cmp(&(
+                &b.app_name,
+                &b.host_name,
+                &b.instance_id,
+            ))
+        });
+        peers
+    }
+
+    /// The other announced apps as a list for display, e.g.
+    /// `Ableton Live (studio-mac), rusty_link-cli (pi4)`.
+    pub fn summary(&self) -> String {
+        self.peers()
+            .iter()
+            .map(PeerInfo::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Withdraw the announcement and stop looking for other apps.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+        if let Ok(status) = self.daemon.unregister(&self.fullname) {
+            // Wait for the goodbye packets, so peers remove this app right away
+            let _ = status.recv();
+        }
+        let _ = self.daemon.shutdown();
+        let _ = handle.join();
+    }
+}
+
+impl Drop for PeerDirectory {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn peer_info(info: &ServiceInfo) -> PeerInfo {
+    let property = |key| {
+        info.get_property_val_str(key)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let mut host_name = property("host");
+    if host_name.is_empty() {
+        host_name = info.get_hostname().trim_end_matches(".local.").to_string();
+    }
+
+    PeerInfo {
+        instance_id: property("id"),
+        app_name: property("app"),
+        host_name,
+        addresses: info.get_addresses().iter().copied().collect(),
+    }
+}
+
+fn mdns_error(error: mdns_sd::Error) -> Error {
+    Error::Io(io::Error::other(error))
+}
+
+/// Unique enough among the instances on a network: process id and start time.
+fn instance_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.subsec_nanos())
+        .unwrap_or_default();
+    format!("rusty-link-{:x}-{:x}", process::id(), nanos)
+}
+
+#[cfg(unix)]
+fn host_name() -> String {
+    let mut buffer = [0u8; 256];
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+    let length = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    match std::str::from_utf8(&buffer[..length]) {
+        Ok(name) if result == 0 && !name.is_empty() => name.trim_end_matches(".local").to_string(),
+        _ => "localhost".to_string(),
+    }
+}
+
+#[cfg(not(unix))]
+fn host_name() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| "localhost".to_string())
+}
@@ -0,0 +1,33 @@
+use crate::SessionState;
+
+/// Fill `out` with the phase of every sample in `0..1`, e.g. as a control signal to sync
+/// oscillators or beat-locked effects of a DSP graph.
+///
+///  Realtime-safe: yes
+///
+/// `buffer_begin` is the Link clock time in microseconds at which the first sample hits
+/// the speaker, i.e. the host time of the callback plus the output latency, see
+/// [AblLink::set_output_latency](crate::AblLink::set_output_latency). The tempo is constant within a Session State, so the phase of the
+/// buffer is computed from a single beat lookup instead of one FFI call per sample.
+/// The phase advances regardless of transport, like [SessionState::phase_at_time].
+/// The buffer is filled with zeros if `quantum` is not positive.
+pub fn render_phase_buffer(
+    session_state: &SessionState,
+    out: &mut [f32],
+    sample_rate: f64,
+    buffer_begin: i64,
+    quantum: f64,
+) {
+    if quantum <= 0. || sample_rate <= 0. {
+        out.fill(0.);
+        return;
+    }
+
+    let beat = session_state.beat_at_time(buffer_begin, quantum);
+    let beats_per_sample = session_state.tempo() / 60. / sample_rate;
+    for (index, sample) in out.iter_mut().enumerate() {
+        let phase = (beat + index as f64 * beats_per_sample).rem_euclid(quantum) / quantum;
+        // Rounding of the division may yield exactly 1 for phases just below the bar line
+        *sample = (phase as f32).min(1. - f32::EPSILON);
+    }
+}
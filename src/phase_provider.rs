@@ -0,0 +1,134 @@
+use crate::{AblLink, ClockSource, SessionState};
+use std::{sync::Arc, time::Duration};
+
+/// Cheap beat and phase values for UI widgets, polled at frame rate.
+///
+/// Capturing a Session State for every frame is wasteful. The provider captures at most
+/// once per [PhaseProvider::capture_interval] and extrapolates the beat from the last
+/// capture with the tempo in between.
+pub struct PhaseProvider {
+    link: Arc<AblLink>,
+    clock: Arc<dyn ClockSource>,
+    /// Longest time between two captures of the Session State.
+    pub capture_interval: Duration,
+    session_state: SessionState,
+    last_capture: Option<Capture>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Capture {
+    /// Time of the capture according to the clock of the provider
+    time: i64,
+    quantum: f64,
+    beat: f64,
+    tempo: f64,
+    is_playing: bool,
+}
+
+impl PhaseProvider {
+    /// A provider capturing the app Session State of `link` every 100ms.
+    pub fn new(link: Arc<AblLink>) -> Self {
+        Self {
+            clock: Arc::clone(&link) as Arc<dyn ClockSource>,
+            link,
+            capture_interval: Duration::from_millis(100),
+            session_state: SessionState::new(),
+            last_capture: None,
+        }
+    }
+
+    pub fn with_capture_interval(mut self, interval: Duration) -> Self {
+        self.capture_interval = interval;
+        self
+    }
+
+    /// Read the current time from `clock` instead of the Link clock, e.g. a virtual time
+    /// in tests. The time has to be on the Link clock, in microseconds.
+    pub fn with_clock(mut self, clock: Arc<dyn ClockSource>) -> Self {
+        self.clock = clock;
+        self.last_capture = None;
+        self
+    }
+
+    /// The phase as a fraction of the quantum, in `[0, 1)`, e.g. for a progress bar.
+    pub fn phase01(&mut self, quantum: f64) -> f32 {
+        if quantum <= 0. {
+            return 0.;
+        }
+        (self.beat(quantum).rem_euclid(quantum) / quantum) as f32
+    }
+
+    /// The phase in `[0, quantum)`.
+    pub fn phase(&mut self, quantum: f64) -> f64 {
+        self.beat(quantum).rem_euclid(quantum)
+    }
+
+    /// The current beat.
+    pub fn beat(&mut self, quantum: f64) -> f64 {
+        self.beat_at(self.clock.micros(), quantum)
+    }
+
+    /// The phase in `[0, quantum)` at the time the frame being rendered is displayed.
+    ///
+    /// `vsync_latency` is the delay from rendering to the frame on the screen, e.g. one or
+    /// two frame periods plus the latency of the display. Visuals rendered with the current
+    /// phase appear late by that delay, like audio without output latency compensation.
+    pub fn phase_at_predicted_display_time(
+        &mut self,
+        vsync_latency: Duration,
+        quantum: f64,
+    ) -> f64 {
+        self.beat_at_predicted_display_time(vsync_latency, quantum)
+            .rem_euclid(quantum)
+    }
+
+    /// The beat at the time the frame being rendered is displayed, see
+    /// [PhaseProvider::phase_at_predicted_display_time].
+    pub fn beat_at_predicted_display_time(&mut self, vsync_latency: Duration, quantum: f64) -> f64 {
+        let latency = i64::try_from(vsync_latency.as_micros()).unwrap_or(i64::MAX);
+        self.beat_at(self.clock.micros().saturating_add(latency), quantum)
+    }
+
+    /// The tempo of the last capture.
+    pub fn tempo(&mut self) -> f64 {
+        self.capture(None).tempo
+    }
+
+    /// Is transport playing, according to the last capture?
+    pub fn is_playing(&mut self) -> bool {
+        self.capture(None).is_playing
+    }
+
+    /// The beat at `time`, extrapolated from the last capture.
+    fn beat_at(&mut self, time: i64, quantum: f64) -> f64 {
+        let capture = self.capture(Some(quantum));
+        let elapsed = (time - capture.time) as f64 / 1e6;
+        capture.beat + elapsed * capture.tempo / 60.
+    }
+
+    /// The last capture, renewed if it is too old or was made for another quantum.
+    fn capture(&mut self, quantum: Option<f64>) -> Capture {
+        let now = self.clock.micros();
+        if let Some(capture) = self.last_capture {
+            let age = Duration::from_micros(now.saturating_sub(capture.time).max(0) as u64);
+            let fresh = age < self.capture_interval;
+            if fresh && quantum.is_none_or(|quantum| quantum == capture.quantum) {
+                return capture;
+            }
+        }
+
+        let quantum = quantum
+            .or(self.last_capture.map(|capture| capture.quantum))
+            .unwrap_or(4.);
+        self.link.capture_app_session_state(&mut self.session_state);
+        let capture = Capture {
+            time: now,
+            quantum,
+            beat: self.session_state.beat_at_time(now, quantum),
+            tempo: self.session_state.tempo(),
+            is_playing: self.session_state.is_playing(),
+        };
+        self.last_capture = Some(capture);
+        capture
+    }
+}
@@ -0,0 +1,6 @@
+pub use rusty_link_core::Position;
+
+/// Format a beat as `bar.beat.tick`, see [Position].
+pub fn format_position(beat: f64, quantum: f64, ticks_per_beat: u32) -> String {
+    Position::new(beat, quantum, ticks_per_beat).to_string()
+}
@@ -0,0 +1,109 @@
+use crate::AblLink;
+use std::time::{Duration, Instant};
+
+/// State of an [AblLink] instance managed by a [PowerPolicy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerState {
+    /// Link is enabled, or was disabled by the app itself.
+    Active,
+    /// Link was disabled by the policy, because there were no peers.
+    Sleeping,
+    /// Link is enabled for a short time, to look for new peers.
+    Probing,
+}
+
+/// Disables Link after a period without peers and re-enables it on demand, reducing
+/// network chatter and battery usage of apps which keep Link around all day.
+///
+/// Call [PowerPolicy::update] regularly (e.g. once per second). While sleeping, Link can not
+/// discover new peers. Set a probe interval to look for peers from time to time, or call
+/// [PowerPolicy::wake] when the user interacts with Link.
+#[derive(Debug, Clone)]
+pub struct PowerPolicy {
+    /// Time without peers, after which Link is disabled.
+    pub idle_timeout: Duration,
+    /// How often a sleeping instance is enabled to look for peers, never if `None`.
+    pub probe_interval: Option<Duration>,
+    /// How long a probe looks for peers.
+    pub probe_duration: Duration,
+    state: PowerState,
+    /// Start of the current idle period, sleep or probe
+    since: Instant,
+}
+
+impl PowerPolicy {
+    pub fn new(idle_timeout: Duration) -> Self {
+        Self {
+            idle_timeout,
+            probe_interval: None,
+            probe_duration: Duration::from_secs(5),
+            state: PowerState::Active,
+            since: Instant::now(),
+        }
+    }
+
+    /// Look for peers every `interval` while sleeping.
+    pub fn with_probe_interval(mut self, interval: Duration) -> Self {
+        self.probe_interval = Some(interval);
+        self
+    }
+
+    pub fn state(&self) -> PowerState {
+        self.state
+    }
+
+    /// Disable or re-enable `link` according to the policy and return the new state.
+    ///
+    /// Instances disabled by the app itself are left alone.
+    pub fn update(&mut self, link: &AblLink) -> PowerState {
+        let now = Instant::now();
+
+        match self.state {
+            PowerState::Active => {
+                if !link.is_enabled() || link.num_peers() > 0 {
+                    self.since = now;
+                } else if now - self.since >= self.idle_timeout {
+                    debug!("no peers, disable Link");
+                    link.enable(false);
+                    self.enter(PowerState::Sleeping, now);
+                }
+            }
+            PowerState::Sleeping => {
+                if link.is_enabled() {
+                    // Enabled by the app
+                    self.enter(PowerState::Active, now);
+                } else if self
+                    .probe_interval
+                    .is_some_and(|interval| now - self.since >= interval)
+                {
+                    link.enable(true);
+                    self.enter(PowerState::Probing, now);
+                }
+            }
+            PowerState::Probing => {
+                if link.num_peers() > 0 || !link.is_enabled() {
+                    self.enter(PowerState::Active, now);
+                } else if now - self.since >= self.probe_duration {
+                    link.enable(false);
+                    self.enter(PowerState::Sleeping, now);
+                }
+            }
+        }
+
+        self.state
+    }
+
+    /// Re-enable a sleeping `link`, e.g. when the user opens the Link settings.
+    pub fn wake(&mut self, link: &AblLink) {
+        if self.state != PowerState::Active {
+            debug!("wake Link");
+            link.enable(true);
+            self.enter(PowerState::Active, Instant::now());
+        }
+    }
+
+    fn enter(&mut self, state: PowerState, now: Instant) {
+        self.state = state;
+        self.since = now;
+    }
+}
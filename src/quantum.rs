@@ -0,0 +1,140 @@
+use crate::{AblLink, Error, LinkEvent, Result};
+use std::{fmt, sync::atomic::Ordering};
+
+/// Quantum of a new [AblLink] instance, one bar of 4/4.
+pub const DEFAULT_QUANTUM: f64 = 4.;
+
+/// A time signature, e.g. 6/8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeSignature {
+    /// Notes per bar.
+    pub numerator: u32,
+    /// Note value of the numerator, e.g. 8 for eighth notes.
+    pub denominator: u32,
+}
+
+impl TimeSignature {
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+}
+
+/// e.g. `6/8`
+impl fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+/// A quantum in beats, see [AblLink::quantum]. Link beats are quarter notes.
+///
+/// Use the presets or [Quantum::from_time_signature] instead of magic numbers. Converts
+/// into `f64`, so it can be passed to [AblLink::set_quantum] and used with Session States.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Quantum(f64);
+
+impl Quantum {
+    /// One beat, i.e. phase aligned quarter notes without bars.
+    pub const BEAT: Quantum = Quantum(1.);
+    pub const BAR_2_4: Quantum = Quantum(2.);
+    pub const BAR_3_4: Quantum = Quantum(3.);
+    pub const BAR_4_4: Quantum = Quantum(4.);
+    pub const BAR_5_4: Quantum = Quantum(5.);
+    pub const BAR_6_8: Quantum = Quantum(3.);
+    pub const BAR_7_8: Quantum = Quantum(3.5);
+    pub const BAR_12_8: Quantum = Quantum(6.);
+
+    /// A quantum of `beats` beats. Fails for quanta which are not positive and finite.
+    pub fn new(beats: f64) -> Result<Quantum> {
+        if beats.is_finite() && beats > 0. {
+            Ok(Quantum(beats))
+        } else {
+            Err(Error::InvalidQuantum { quantum: beats })
+        }
+    }
+
+    /// One bar of `time_signature`, e.g. 3 beats for 6/8. Fails for a numerator or
+    /// denominator of 0.
+    pub fn from_time_signature(time_signature: TimeSignature) -> Result<Quantum> {
+        Self::new(time_signature.numerator as f64 * 4. / time_signature.denominator as f64)
+    }
+
+    /// `bars` bars of `time_signature`, e.g. to align phrases of several bars.
+    pub fn bars(time_signature: TimeSignature, bars: u32) -> Result<Quantum> {
+        Self::new(Self::from_time_signature(time_signature)?.0 * bars as f64)
+    }
+
+    pub fn beats(self) -> f64 {
+        self.0
+    }
+}
+
+impl Default for Quantum {
+    fn default() -> Self {
+        Quantum(DEFAULT_QUANTUM)
+    }
+}
+
+impl From<Quantum> for f64 {
+    fn from(quantum: Quantum) -> Self {
+        quantum.0
+    }
+}
+
+impl TryFrom<TimeSignature> for Quantum {
+    type Error = Error;
+
+    fn try_from(time_signature: TimeSignature) -> Result<Self> {
+        Self::from_time_signature(time_signature)
+    }
+}
+
+/// e.g. `3.5 beats`
+impl fmt::Display for Quantum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} beats", self.0)
+    }
+}
+
+impl AblLink {
+    /// The quantum of the app, [DEFAULT_QUANTUM] until changed with [AblLink::set_quantum].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    ///
+    /// Link takes the quantum as a parameter of every beat and phase query, but apps treat it
+    /// as a setting. Storing it with the instance lets all parts of an app agree on the bar
+    /// length. Helpers like [AblLink::nudge] and [AblLink::current_transport] use it.
+    pub fn quantum(&self) -> f64 {
+        f64::from_bits(self.quantum.load(Ordering::Acquire))
+    }
+
+    /// Change the quantum of the app, see [AblLink::quantum].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    ///
+    /// Queues a [LinkEvent::QuantumChanged], if the quantum changed. Fails for quanta which
+    /// are not positive and finite. Takes `f64`s and [Quantum]s.
+    pub fn set_quantum<Q: Into<f64>>(&self, quantum: Q) -> Result<()> {
+        let quantum = quantum.into();
+        if !(quantum.is_finite() && quantum > 0.) {
+            return Err(Error::InvalidQuantum { quantum });
+        }
+
+        let previous = f64::from_bits(self.quantum.swap(quantum.to_bits(), Ordering::AcqRel));
+        if previous != quantum {
+            debug!(quantum, previous, "quantum changed");
+            let event = LinkEvent::QuantumChanged { quantum };
+            #[cfg(feature = "metrics")]
+            crate::telemetry::record_event(event);
+            self.callbacks.record(event, self.clock_micros());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use crate::SessionState;
+
+/// One-shot linear envelope over a number of beats, e.g. for automating volume or filter
+/// parameters in sync with the beat grid.
+///
+/// The ramp is evaluated against beats of the Link timeline, so peers starting the same
+/// ramp at the same beat (e.g. the next bar with [Ramp::start_at_next_bar]) follow the same
+/// curve, regardless of tempo changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ramp {
+    pub from: f64,
+    pub to: f64,
+    /// Length of the ramp in beats.
+    pub beats: f64,
+    /// Beat at which the ramp starts.
+    pub start: f64,
+}
+
+impl Ramp {
+    /// A ramp from `from` to `to` over `n_beats` beats, starting at beat 0.
+    pub fn over_beats(from: f64, to: f64, n_beats: f64) -> Self {
+        Self {
+            from,
+            to,
+            beats: n_beats,
+            start: 0.,
+        }
+    }
+
+    /// Start the ramp at `beat`.
+    pub fn starting_at(mut self, beat: f64) -> Self {
+        self.start = beat;
+        self
+    }
+
+    /// Start the ramp at the beginning of the next bar after `time`, for a bar of `quantum`
+    /// beats.
+    pub fn start_at_next_bar(&mut self, session_state: &SessionState, time: i64, quantum: f64) {
+        let beat = session_state.beat_at_time(time, quantum);
+        self.start = (beat / quantum).floor() * quantum + quantum;
+    }
+
+    /// Value at `beat`: `from` before the start, `to` after the end.
+    pub fn value_at_beat(&self, beat: f64) -> f64 {
+        self.from + (self.to - self.from) * self.progress(beat)
+    }
+
+    /// Value at `time` according to `session_state`.
+    pub fn value_at_time(&self, session_state: &SessionState, time: i64, quantum: f64) -> f64 {
+        self.value_at_beat(session_state.beat_at_time(time, quantum))
+    }
+
+    /// Progress at `beat`, from 0 at the start to 1 at the end.
+    pub fn progress(&self, beat: f64) -> f64 {
+        if self.beats <= 0. {
+            return if beat >= self.start { 1. } else { 0. };
+        }
+        ((beat - self.start) / self.beats).clamp(0., 1.)
+    }
+
+    /// Has the ramp reached its end at `beat`?
+    pub fn is_finished(&self, beat: f64) -> bool {
+        beat >= self.start + self.beats
+    }
+}
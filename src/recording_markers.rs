@@ -0,0 +1,141 @@
+use crate::{Result, SessionState};
+use std::io::Write;
+
+/// A marker of [RecordingMarkers].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Marker {
+    pub beat: f64,
+    /// Bar of the marker, counted from beat 0 of the timeline. Can be negative.
+    pub bar: i64,
+    /// Beat of the marker within its bar, 0 at the start of the bar.
+    pub beat_in_bar: f64,
+    /// Link host time of the marker in microseconds.
+    pub time: i64,
+    /// Position of the marker in the recording, in samples from its start.
+    pub sample: u64,
+}
+
+/// Bar (or beat) markers for a recording of a Link session, e.g. to chop a recorded jam on
+/// its bars afterwards.
+///
+/// Call [RecordingMarkers::update] regularly during the recording, e.g. once per second.
+/// Markers up to the given time are added with the timeline of that moment, so tempo
+/// changes during the recording are taken into account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordingMarkers {
+    start: i64,
+    sample_rate: f64,
+    quantum: f64,
+    interval: f64,
+    markers: Vec<Marker>,
+    /// Number of the next marker, counted in intervals from beat 0
+    next: Option<i64>,
+}
+
+impl RecordingMarkers {
+    /// Markers for a recording starting at the Link host time `start` (microseconds) with
+    /// `sample_rate`, at the start of every bar of `quantum` beats.
+    pub fn new(start: i64, sample_rate: f64, quantum: f64) -> Self {
+        Self {
+            start,
+            sample_rate,
+            quantum,
+            interval: quantum,
+            markers: Vec::new(),
+            next: None,
+        }
+    }
+
+    /// Place markers every `beats` beats instead of every bar, e.g. 1 for every beat.
+    pub fn with_interval(mut self, beats: f64) -> Self {
+        self.interval = beats;
+        self
+    }
+
+    /// Add the markers between the last update (or the start) and `until`, according to
+    /// the timeline of `session_state`.
+    pub fn update(&mut self, session_state: &SessionState, until: i64) {
+        if self.interval <= 0. {
+            return;
+        }
+
+        let mut next = match self.next {
+            Some(next) => next,
+            None => {
+                let beat = session_state.beat_at_time(self.start, self.quantum);
+                (beat / self.interval).ceil() as i64
+            }
+        };
+
+        loop {
+            let beat = next as f64 * self.interval;
+            let time = session_state.time_at_beat(beat, self.quantum);
+            if time > until {
+                break;
+            }
+
+            let bar = (beat / self.quantum).floor();
+            self.markers.push(Marker {
+                beat,
+                bar: bar as i64,
+                beat_in_bar: beat - bar * self.quantum,
+                time,
+                sample: self.sample_at(time),
+            });
+            next += 1;
+        }
+        self.next = Some(next);
+    }
+
+    /// The markers added so far.
+    pub fn markers(&self) -> &[Marker] {
+        &self.markers
+    }
+
+    /// Write the markers as an Audacity label track: start and end in seconds and a label,
+    /// separated by tabs.
+    pub fn write_labels<W: Write>(&self, mut writer: W) -> Result<()> {
+        for marker in &self.markers {
+            let seconds = marker.sample as f64 / self.sample_rate;
+            writeln!(writer, "{:.6}\t{:.6}\t{}", seconds, seconds, label(marker))?;
+        }
+        Ok(())
+    }
+
+    /// Write the markers as a CUE sheet for the recorded `file_name`, one track per marker.
+    ///
+    /// CUE sheets have a resolution of 1/75 s, positions are rounded down.
+    pub fn write_cue_sheet<W: Write>(&self, mut writer: W, file_name: &str) -> Result<()> {
+        writeln!(writer, "FILE \"{}\" WAVE", file_name)?;
+        for (index, marker) in self.markers.iter().take(99).enumerate() {
+            let frames = (marker.sample as f64 / self.sample_rate * 75.) as u64;
+            writeln!(writer, "  TRACK {:02} AUDIO", index + 1)?;
+            writeln!(writer, "    TITLE \"{}\"", label(marker))?;
+            writeln!(
+                writer,
+                "    INDEX 01 {:02}:{:02}:{:02}",
+                frames / 75 / 60,
+                frames / 75 % 60,
+                frames % 75
+            )?;
+        }
+        Ok(())
+    }
+
+    fn sample_at(&self, time: i64) -> u64 {
+        ((time - self.start).max(0) as f64 * self.sample_rate / 1e6).round() as u64
+    }
+}
+
+/// e.g. `Bar 12` or `Bar 12.3` for the third beat of bar 12, bars counted from 1.
+fn label(marker: &Marker) -> String {
+    if marker.beat_in_bar == 0. {
+        format!("Bar {}", marker.bar + 1)
+    } else {
+        format!(
+            "Bar {}.{}",
+            marker.bar + 1,
+            marker.beat_in_bar.floor() as i64 + 1
+        )
+    }
+}
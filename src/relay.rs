@@ -0,0 +1,614 @@
+//! Relay of Link timeline and transport state over TCP (feature `relay`), for jams across
+//! networks which multicast discovery can not reach.
+//!
+//! A [RelayServer] forwards every message it receives from one client to all other clients.
+//! A [RelayClient] sends changes of the local session to the server and applies the state
+//! of remote sessions locally.
+//!
+//! The protocol is line based text:
+//!
+//! - `state <tempo> <beat> <quantum> <is_playing> <latency> [<session>]`: state of the sender's
+//!   session, `beat` at the time of sending, the sender's estimated latency to the relay in
+//!   microseconds and the [SessionKey::id] of its private session, 0 or missing for none
+//! - `ping <id>`: answered by the server with `pong <id>`, to measure the round trip time
+//!
+//! Clock times can not be compared across networks, so the age of a received state is
+//! estimated from the latencies of sender and receiver. See [LatencyModel].
+
+use crate::{error::check_tempo, AblLink, Error, Result, SessionKey, SessionState, ThreadConfig};
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+/// Longest time the client thread blocks on the connection.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Longest accepted line in bytes, including the newline. Connections sending longer lines
+/// are closed, so a peer which never sends a newline can not grow the buffer without bound.
+pub const MAX_LINE: usize = 1024;
+
+/// Number of messages queued for a client of a [RelayServer], before new messages to it are
+/// dropped.
+const OUTBOUND_CAPACITY: usize = 64;
+
+/// Estimates the one way latency between this client and the relay server.
+///
+/// Implement it to plug in better latency compensation than [HalfRoundTrip], e.g. with
+/// smoothing or a manual offset per venue.
+pub trait LatencyModel: Send + 'static {
+    /// Called with every measured round trip time to the server.
+    fn update(&mut self, round_trip: Duration);
+
+    /// The current estimate of the one way latency to the server.
+    fn one_way_latency(&self) -> Duration;
+}
+
+/// Half of the latest round trip time plus a fixed offset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HalfRoundTrip {
+    pub offset: Duration,
+    latest: Duration,
+}
+
+impl HalfRoundTrip {
+    pub fn new(offset: Duration) -> Self {
+        Self {
+            offset,
+            latest: Duration::ZERO,
+        }
+    }
+}
+
+impl LatencyModel for HalfRoundTrip {
+    fn update(&mut self, round_trip: Duration) {
+        self.latest = round_trip / 2;
+    }
+
+    fn one_way_latency(&self) -> Duration {
+        self.latest + self.offset
+    }
+}
+
+/// Configuration of a [RelayClient].
+pub struct RelayConfig {
+    /// Quantum used to align the phase of the local session with remote sessions.
+    pub quantum: f64,
+    /// How often local changes are looked for.
+    pub send_interval: Duration,
+    /// How often the state is sent without changes, so phase drift is corrected.
+    pub heartbeat_interval: Duration,
+    /// How often the round trip time to the server is measured.
+    pub ping_interval: Duration,
+    /// Phase differences to remote sessions below this are not corrected.
+    pub phase_tolerance: Duration,
+    pub latency_model: Box<dyn LatencyModel>,
+    /// Only exchange states with clients using the same key, so several sessions can share
+    /// one relay server.
+    pub session_key: Option<SessionKey>,
+    pub thread: ThreadConfig,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            quantum: 4.,
+            send_interval: Duration::from_millis(50),
+            heartbeat_interval: Duration::from_secs(1),
+            ping_interval: Duration::from_secs(1),
+            phase_tolerance: Duration::from_millis(10),
+            latency_model: Box::new(HalfRoundTrip::default()),
+            session_key: None,
+            thread: ThreadConfig::default(),
+        }
+    }
+}
+
+/// State of a session, as sent over the relay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelayState {
+    pub tempo: f64,
+    /// Beat at the time of sending
+    pub beat: f64,
+    pub quantum: f64,
+    pub is_playing: bool,
+    /// Latency of the sender to the relay in microseconds
+    pub latency: u64,
+    /// [SessionKey::id] of the sender's session, 0 for none
+    pub session: u64,
+}
+
+/// A message of the relay protocol, see the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RelayMessage {
+    State(RelayState),
+    Ping(u64),
+    Pong(u64),
+}
+
+impl RelayMessage {
+    /// Parse a line of the protocol, with or without the trailing newline. Lines of
+    /// [MAX_LINE] bytes or more, unknown messages and states with an invalid tempo, beat
+    /// or quantum are rejected with [Error::InvalidMessage].
+    pub fn parse(line: &str) -> Result<RelayMessage> {
+        if line.len() >= MAX_LINE {
+            return Err(Error::InvalidMessage("relay line too long"));
+        }
+        let mut fields = line.split_whitespace();
+        let message = match fields.next() {
+            Some("state") => {
+                let state = RelayState {
+                    tempo: parse_field(fields.next())?,
+                    beat: parse_field(fields.next())?,
+                    quantum: parse_field(fields.next())?,
+                    is_playing: parse_field::<u8>(fields.next())? != 0,
+                    latency: parse_field(fields.next())?,
+                    // Missing for clients without session keys
+                    session: match fields.next() {
+                        Some(field) => parse_field(Some(field))?,
+                        None => 0,
+                    },
+                };
+                // Link lets NaN through its tempo clamp, one bad peer would corrupt all sessions
+                if check_tempo(state.tempo).is_err()
+                    || !state.beat.is_finite()
+                    || !(state.quantum.is_finite() && state.quantum > 0.)
+                {
+                    return Err(Error::InvalidMessage("invalid relay state"));
+                }
+                RelayMessage::State(state)
+            }
+            Some("ping") => RelayMessage::Ping(parse_field(fields.next())?),
+            Some("pong") => RelayMessage::Pong(parse_field(fields.next())?),
+            _ => return Err(Error::InvalidMessage("unknown relay message")),
+        };
+        Ok(message)
+    }
+
+    /// The message as a line of the protocol, including the newline.
+    pub fn to_line(&self) -> String {
+        match self {
+            RelayMessage::State(s) => format!(
+                "state {} {} {} {} {} {}\n",
+                s.tempo, s.beat, s.quantum, s.is_playing as u8, s.latency, s.session
+            ),
+            RelayMessage::Ping(id) => format!("ping {}\n", id),
+            RelayMessage::Pong(id) => format!("pong {}\n", id),
+        }
+    }
+
+    /// Write the message as one line with a single write, so messages written to a shared
+    /// stream do not interleave.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_line().as_bytes())
+    }
+}
+
+fn parse_field<T: std::str::FromStr>(field: Option<&str>) -> Result<T> {
+    field
+        .and_then(|field| field.parse().ok())
+        .ok_or(Error::InvalidMessage("invalid relay message field"))
+}
+
+/// Forwards relay messages between all connected [RelayClient]s.
+pub struct RelayServer {
+    local_addr: SocketAddr,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// A client connected to a [RelayServer].
+struct Client {
+    id: u64,
+    /// Lines to send, written by the writer thread of the client
+    outbound: SyncSender<Arc<str>>,
+    stream: TcpStream,
+}
+
+impl Client {
+    /// Queue `line` without blocking. Returns false, if the client is gone.
+    fn send(&self, line: Arc<str>) -> bool {
+        match self.outbound.try_send(line) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                trace!(id = self.id, "relay client is behind, dropped message");
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+}
+
+type Clients = Arc<Mutex<Vec<Client>>>;
+
+impl RelayServer {
+    /// Listen for clients on `addr` and forward messages between them on background threads.
+    pub fn start<A: ToSocketAddrs>(addr: A, config: ThreadConfig) -> Result<RelayServer> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        listener.set_nonblocking(true)?;
+
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = Arc::clone(&running);
+
+        let handle = config.clone().spawn("relay-server", move || {
+            let clients: Clients = Arc::default();
+            let mut next_id = 0;
+
+            while running_clone.load(Ordering::Acquire) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let id = next_id;
+                        next_id += 1;
+                        if let Err(_error) = add_client(id, stream, &clients, &config) {
+                            debug!(error = %_error, "could not add relay client");
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(POLL_INTERVAL)
+                    }
+                    Err(_error) => {
+                        debug!(error = %_error, "relay accept failed");
+                    }
+                }
+            }
+
+            for client in clients.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+                let _ = client.stream.shutdown(Shutdown::Both);
+            }
+        });
+
+        Ok(RelayServer {
+            local_addr,
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    /// Address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Disconnect all clients and stop the server.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for RelayServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn add_client(id: u64, stream: TcpStream, clients: &Clients, config: &ThreadConfig) -> Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_nodelay(true)?;
+    stream.set_write_timeout(Some(Duration::from_secs(1)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream.try_clone()?;
+    let (outbound, queued) = mpsc::sync_channel::<Arc<str>>(OUTBOUND_CAPACITY);
+
+    // Only the writer thread writes to the stream, so lines never interleave and a stalled
+    // client only delays its own messages
+    config.spawn("relay-out", move || {
+        for line in queued {
+            if writer.write_all(line.as_bytes()).is_err() {
+                // Ends the reader thread, which removes the client
+                let _ = writer.shutdown(Shutdown::Both);
+                break;
+            }
+        }
+    });
+
+    let client = Client {
+        id,
+        outbound,
+        stream,
+    };
+    let reply = client.outbound.clone();
+    clients
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(client);
+
+    let clients = Arc::clone(clients);
+    config.spawn("relay-conn", move || {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match (&mut reader).take(MAX_LINE as u64).read_line(&mut line) {
+                // A line without newline is either cut off by the limit or by the end of the
+                // connection
+                Ok(_) if line.ends_with('\n') => {}
+                _ => break,
+            }
+
+            match RelayMessage::parse(&line) {
+                Ok(RelayMessage::Ping(ping)) => {
+                    if reply
+                        .send(RelayMessage::Pong(ping).to_line().into())
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                Ok(RelayMessage::State(_)) => {
+                    let line: Arc<str> = line.as_str().into();
+                    let mut clients = clients.lock().unwrap_or_else(|e| e.into_inner());
+                    // Forward to everybody else, drop clients which are gone
+                    clients.retain(|client| client.id == id || client.send(Arc::clone(&line)));
+                }
+                Ok(RelayMessage::Pong(_)) | Err(_) => {}
+            }
+        }
+
+        // Dropping the queue ends the writer thread
+        let mut clients = clients.lock().unwrap_or_else(|e| e.into_inner());
+        clients.retain(|client| client.id != id);
+    });
+
+    Ok(())
+}
+
+/// Connects an [AblLink] instance to a [RelayServer].
+///
+/// Tempo and transport changes of the local session are sent to the relay. States received
+/// from the relay are applied to the local session: tempo and transport are taken over and
+/// the phase is aligned with [SessionState::force_beat_at_time], if it differs by more than
+/// [RelayConfig::phase_tolerance].
+pub struct RelayClient {
+    running: Arc<AtomicBool>,
+    connected: Arc<AtomicBool>,
+    latency: Arc<AtomicU64>,
+    stream: TcpStream,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl RelayClient {
+    /// Connect `link` to the relay server at `addr`.
+    pub fn connect<A: ToSocketAddrs>(
+        addr: A,
+        link: Arc<AblLink>,
+        config: RelayConfig,
+    ) -> Result<RelayClient> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+
+        let running = Arc::new(AtomicBool::new(true));
+        let connected = Arc::new(AtomicBool::new(true));
+        let latency = Arc::new(AtomicU64::new(0));
+
+        let mut connection = Connection {
+            link,
+            reader: BufReader::new(stream.try_clone()?),
+            line: String::new(),
+            writer: stream.try_clone()?,
+            session_state: SessionState::new(),
+            last_sent: None,
+            last_send_time: Instant::now(),
+            last_check: Instant::now(),
+            pings: Vec::new(),
+            next_ping: 0,
+            last_ping: Instant::now() - config.ping_interval,
+            latency: Arc::clone(&latency),
+            config,
+        };
+
+        let running_clone = Arc::clone(&running);
+        let connected_clone = Arc::clone(&connected);
+        let thread = connection.config.thread.clone();
+        let handle = thread.spawn("relay-client", move || {
+            while running_clone.load(Ordering::Acquire) {
+                if let Err(_error) = connection.update() {
+                    debug!(error = %_error, "relay connection lost");
+                    break;
+                }
+            }
+            connected_clone.store(false, Ordering::Release);
+        });
+
+        Ok(RelayClient {
+            running,
+            connected,
+            latency,
+            stream,
+            handle: Some(handle),
+        })
+    }
+
+    /// Is the client still connected to the server?
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Acquire)
+    }
+
+    /// The current one way latency estimate to the server, see [LatencyModel].
+    pub fn latency(&self) -> Duration {
+        Duration::from_micros(self.latency.load(Ordering::Relaxed))
+    }
+
+    /// Disconnect from the server.
+    pub fn disconnect(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.running.store(false, Ordering::Release);
+        let _ = self.stream.shutdown(Shutdown::Both);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for RelayClient {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// State of the client thread.
+struct Connection {
+    link: Arc<AblLink>,
+    reader: BufReader<TcpStream>,
+    /// Received part of the current line, which may be split by read timeouts
+    line: String,
+    writer: TcpStream,
+    session_state: SessionState,
+    /// Tempo and transport last sent or received, to send only local changes
+    last_sent: Option<(f64, bool)>,
+    last_send_time: Instant,
+    last_check: Instant,
+    /// Ids and send times of unanswered pings
+    pings: Vec<(u64, Instant)>,
+    next_ping: u64,
+    last_ping: Instant,
+    latency: Arc<AtomicU64>,
+    config: RelayConfig,
+}
+
+impl Connection {
+    fn update(&mut self) -> Result<()> {
+        if self.line.len() >= MAX_LINE {
+            return Err(Error::InvalidMessage("relay line too long"));
+        }
+        let limit = (MAX_LINE - self.line.len()) as u64;
+        match (&mut self.reader).take(limit).read_line(&mut self.line) {
+            Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            Ok(_) if self.line.ends_with('\n') => {
+                if let Ok(message) = RelayMessage::parse(&self.line) {
+                    self.handle(message);
+                }
+                self.line.clear();
+            }
+            Ok(_) => {}
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let now = Instant::now();
+        if now - self.last_ping >= self.config.ping_interval {
+            self.last_ping = now;
+            self.pings.push((self.next_ping, now));
+            self.pings
+                .retain(|(_, sent)| now - *sent < Duration::from_secs(10));
+            RelayMessage::Ping(self.next_ping).write_to(&mut self.writer)?;
+            self.next_ping += 1;
+        }
+
+        if now - self.last_check >= self.config.send_interval {
+            self.last_check = now;
+            self.send_local_changes(now)?;
+        }
+
+        Ok(())
+    }
+
+    fn handle(&mut self, message: RelayMessage) {
+        match message {
+            RelayMessage::Pong(id) => {
+                if let Some(index) = self.pings.iter().position(|(ping, _)| *ping == id) {
+                    let (_, sent) = self.pings.swap_remove(index);
+                    self.config.latency_model.update(sent.elapsed());
+                    self.latency.store(
+                        self.config.latency_model.one_way_latency().as_micros() as u64,
+                        Ordering::Relaxed,
+                    );
+                }
+            }
+            RelayMessage::State(state) if state.session == self.session() => self.apply(state),
+            RelayMessage::State(_) => {
+                trace!("ignore state of another session");
+            }
+            RelayMessage::Ping(_) => {}
+        }
+    }
+
+    /// [SessionKey::id] of the configured session, 0 for none.
+    fn session(&self) -> u64 {
+        self.config.session_key.map_or(0, |key| key.id())
+    }
+
+    /// Apply the state of a remote session to the local session.
+    fn apply(&mut self, state: RelayState) {
+        let quantum = self.config.quantum;
+        let age =
+            state.latency as i64 + self.config.latency_model.one_way_latency().as_micros() as i64;
+
+        self.link.capture_app_session_state(&mut self.session_state);
+        let now = self.link.clock_micros();
+        let session_state = &mut self.session_state;
+
+        if session_state.tempo() != state.tempo {
+            session_state.set_tempo(state.tempo, now);
+        }
+        if session_state.is_playing() != state.is_playing {
+            session_state.set_is_playing(state.is_playing, now.max(0) as u64);
+        }
+
+        // Align the phase with the remote session, compensating the age of the message
+        let beat = state.beat + age as f64 * state.tempo / 60e6;
+        let local_beat = session_state.beat_at_time(now, quantum);
+        let phase_error = phase_distance(local_beat, beat, quantum);
+        let tolerance = self.config.phase_tolerance.as_micros() as f64 * state.tempo / 60e6;
+        if phase_error > tolerance {
+            session_state.force_beat_at_time(beat, now.max(0) as u64, quantum);
+        }
+
+        self.link.commit_app_session_state(session_state);
+        self.last_sent = Some((state.tempo, state.is_playing));
+    }
+
+    /// Send the local state, if tempo or transport changed locally or a heartbeat is due.
+    fn send_local_changes(&mut self, now: Instant) -> Result<()> {
+        self.link.capture_app_session_state(&mut self.session_state);
+        let tempo = self.session_state.tempo();
+        let is_playing = self.session_state.is_playing();
+
+        let changed = self.last_sent != Some((tempo, is_playing));
+        if !changed && now - self.last_send_time < self.config.heartbeat_interval {
+            return Ok(());
+        }
+
+        let time = self.link.clock_micros();
+        RelayMessage::State(RelayState {
+            tempo,
+            beat: self.session_state.beat_at_time(time, self.config.quantum),
+            quantum: self.config.quantum,
+            is_playing,
+            latency: self.latency.load(Ordering::Relaxed),
+            session: self.session(),
+        })
+        .write_to(&mut self.writer)?;
+
+        self.last_sent = Some((tempo, is_playing));
+        self.last_send_time = now;
+        Ok(())
+    }
+}
+
+/// Distance between the phases of two beats, in beats.
+fn phase_distance(a: f64, b: f64, quantum: f64) -> f64 {
+    let distance = (a - b).rem_euclid(quantum);
+    distance.min(quantum - distance)
+}
@@ -0,0 +1,127 @@
+//! Enforcement of the "Realtime-safe: yes" claims of this crate (feature `rt-check`).
+//!
+//! Install [RtCheckAllocator] as the global allocator (usually in tests) and every
+//! realtime-safe function of this crate panics, if it allocated through the Rust
+//! allocator. Allocations inside of Link's C++ code do not go through the Rust
+//! allocator and can not be detected this way.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Allocations counted in callbacks invoked by Link.
+static CALLBACK_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static IN_REALTIME_SECTION: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A global allocator which counts allocations made inside of [assert_no_alloc].
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: rusty_link::rt_check::RtCheckAllocator = rusty_link::rt_check::RtCheckAllocator::new();
+/// ```
+pub struct RtCheckAllocator<A = System> {
+    inner: A,
+}
+
+impl RtCheckAllocator<System> {
+    pub const fn new() -> Self {
+        Self { inner: System }
+    }
+}
+
+impl Default for RtCheckAllocator<System> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> RtCheckAllocator<A> {
+    /// Count allocations on top of another allocator.
+    pub const fn wrap(inner: A) -> Self {
+        Self { inner }
+    }
+}
+
+fn record_allocation() {
+    let _ = IN_REALTIME_SECTION.try_with(|in_section| {
+        if in_section.get() {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        }
+    });
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for RtCheckAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        self.inner.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        self.inner.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation();
+        self.inner.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record_allocation();
+        self.inner.dealloc(ptr, layout)
+    }
+}
+
+/// Run `f` and panic if it allocated or deallocated memory through [RtCheckAllocator].
+///
+/// Without [RtCheckAllocator] installed as the global allocator nothing is counted.
+pub fn assert_no_alloc<R, F: FnOnce() -> R>(f: F) -> R {
+    let (result, allocations) = count_allocations(f);
+    assert!(
+        allocations == 0,
+        "{} allocation(s) in a realtime-safe section",
+        allocations
+    );
+    result
+}
+
+/// Number of allocations made while invoking callbacks registered with
+/// [AblLink](crate::AblLink), including allocations of the registered closures themselves.
+///
+/// Callbacks run on Link-managed threads, where a panic would abort the process,
+/// so they are counted instead of asserted.
+pub fn callback_allocations() -> usize {
+    CALLBACK_ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+fn count_allocations<R, F: FnOnce() -> R>(f: F) -> (R, usize) {
+    let was_in_section = IN_REALTIME_SECTION.with(|in_section| in_section.replace(true));
+    let before = ALLOCATIONS.with(Cell::get);
+
+    let result = f();
+
+    let allocations = ALLOCATIONS.with(Cell::get) - before;
+    IN_REALTIME_SECTION.with(|in_section| in_section.set(was_in_section));
+
+    (result, allocations)
+}
+
+/// Wrapper for the bodies of functions documented as "Realtime-safe: yes".
+#[inline(always)]
+pub(crate) fn realtime<R, F: FnOnce() -> R>(f: F) -> R {
+    assert_no_alloc(f)
+}
+
+/// Wrapper for the invocation of callbacks on Link-managed threads.
+#[inline(always)]
+pub(crate) fn realtime_callback<R, F: FnOnce() -> R>(f: F) -> R {
+    let (result, allocations) = count_allocations(f);
+    CALLBACK_ALLOCATIONS.fetch_add(allocations, Ordering::Relaxed);
+    result
+}
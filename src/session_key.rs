@@ -0,0 +1,41 @@
+use std::fmt;
+
+/// Key of a private session, e.g. so two bands on one venue LAN do not merge into a single
+/// tempo session. Peers with different keys ignore each other.
+///
+/// Applied by the [relay](crate::relay) and the discovery of the
+/// [native](crate::native) backend, the bundled C++ Link has no way to filter peers. Only a
+/// hash of the key is kept and sent, but this is no access control: anyone on the network
+/// can join a session whose hash they observed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    id: u64,
+}
+
+impl SessionKey {
+    pub fn new(key: &str) -> Self {
+        // FNV-1a, stable across platforms and versions unlike `DefaultHasher`
+        let id = key.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+        Self { id }
+    }
+
+    /// Hash of the key, identifying the session on the relay.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Group id of native discovery messages. Never 0, the group of regular Link peers.
+    pub fn group_id(&self) -> u16 {
+        let folded = (self.id ^ self.id >> 16 ^ self.id >> 32 ^ self.id >> 48) as u16;
+        folded.max(1)
+    }
+}
+
+/// Shows the hash only, not the key.
+impl fmt::Debug for SessionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SessionKey({:016x})", self.id)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{AblLink, Error, Result, SessionState, ThreadConfig};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+const FILE_HEADER: &str = "# rusty_link recording v1";
+
+/// A single sample of the Link Session State, taken at `time` (Link clock in microseconds).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimelineSample {
+    pub time: i64,
+    pub tempo: f64,
+    pub beat: f64,
+    pub phase: f64,
+    pub is_playing: bool,
+    pub num_peers: u64,
+}
+
+/// A timeline of [TimelineSample]s recorded by a [SessionRecorder].
+///
+/// Recordings can be written to and read from a simple line based text format,
+/// so they can be attached to bug reports and replayed later with a [SessionReplayer](crate::SessionReplayer).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    pub quantum: f64,
+    pub samples: Vec<TimelineSample>,
+}
+
+impl Recording {
+    /// Save the recording to a file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Load a recording from a file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Recording> {
+        Self::read_from(File::open(path)?)
+    }
+
+    /// Write the recording in text format. One sample per line:
+    /// `time,tempo,beat,phase,is_playing,num_peers`
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        writeln!(writer, "{} quantum={}", FILE_HEADER, self.quantum)?;
+        for s in &self.samples {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                s.time, s.tempo, s.beat, s.phase, s.is_playing as u8, s.num_peers
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Read a recording in the format produced by [Recording::write_to].
+    pub fn read_from<R: Read>(reader: R) -> Result<Recording> {
+        let mut lines = BufReader::new(reader).lines();
+
+        let header = match lines.next() {
+            Some(line) => line?,
+            None => return Err(invalid_data("empty recording")),
+        };
+        let quantum = header
+            .strip_prefix(FILE_HEADER)
+            .and_then(|rest| rest.trim().strip_prefix("quantum="))
+            .ok_or_else(|| invalid_data("missing recording header"))?;
+
+        let mut recording = Recording {
+            quantum: parse_field(quantum)?,
+            samples: Vec::new(),
+        };
+
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() != 6 {
+                return Err(invalid_data("expected 6 fields per sample"));
+            }
+
+            recording.samples.push(TimelineSample {
+                time: parse_field(fields[0])?,
+                tempo: parse_field(fields[1])?,
+                beat: parse_field(fields[2])?,
+                phase: parse_field(fields[3])?,
+                is_playing: parse_field::<u8>(fields[4])? != 0,
+                num_peers: parse_field(fields[5])?,
+            });
+        }
+
+        Ok(recording)
+    }
+}
+
+fn invalid_data(msg: &str) -> Error {
+    Error::InvalidRecording(msg.to_string())
+}
+
+fn parse_field<T: std::str::FromStr>(field: &str) -> Result<T> {
+    field
+        .trim()
+        .parse()
+        .map_err(|_| invalid_data(&format!("could not parse field '{}'", field)))
+}
+
+/// Samples the app Session State of an [AblLink] instance at a fixed interval
+/// on a background thread and collects the results into a [Recording].
+///
+/// Useful to find out what happened to the timeline when debugging drift or
+/// tempo jumps. Dropping the recorder without [SessionRecorder::stop] discards the recording.
+pub struct SessionRecorder {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Vec<TimelineSample>>>,
+    quantum: f64,
+}
+
+impl SessionRecorder {
+    /// Start recording the Session State of `link` every `interval`, using `quantum`
+    /// to calculate beat and phase values.
+    pub fn start(link: Arc<AblLink>, interval: Duration, quantum: f64) -> SessionRecorder {
+        Self::start_with_config(link, interval, quantum, ThreadConfig::default())
+    }
+
+    /// Same as [SessionRecorder::start], with a custom configuration of the recording thread.
+    pub fn start_with_config(
+        link: Arc<AblLink>,
+        interval: Duration,
+        quantum: f64,
+        config: ThreadConfig,
+    ) -> SessionRecorder {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = Arc::clone(&running);
+
+        let handle = config.spawn("recorder", move || {
+            let mut session_state = SessionState::new();
+            let mut samples = Vec::new();
+
+            while running_clone.load(Ordering::Acquire) {
+                link.capture_app_session_state(&mut session_state);
+                let time = link.clock_micros();
+
+                samples.push(TimelineSample {
+                    time,
+                    tempo: session_state.tempo(),
+                    beat: session_state.beat_at_time(time, quantum),
+                    phase: session_state.phase_at_time(time, quantum),
+                    is_playing: session_state.is_playing(),
+                    num_peers: link.num_peers(),
+                });
+
+                thread::sleep(interval);
+            }
+
+            samples
+        });
+
+        SessionRecorder {
+            running,
+            handle: Some(handle),
+            quantum,
+        }
+    }
+
+    /// Stop the recording thread and return everything recorded so far.
+    pub fn stop(mut self) -> Recording {
+        self.running.store(false, Ordering::Release);
+        let handle = self.handle.take().expect("Recording thread is running.");
+
+        Recording {
+            quantum: self.quantum,
+            samples: handle.join().expect("Recording thread panicked."),
+        }
+    }
+}
+
+impl Drop for SessionRecorder {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
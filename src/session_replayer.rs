@@ -0,0 +1,62 @@
+use crate::{session_recorder::TimelineSample, AblLink, Recording, SessionState};
+use std::{thread, time::Duration};
+
+/// Plays a [Recording] back onto an [AblLink] instance in real time.
+///
+/// There is no simulated Link session in this crate, so replaying happens on a
+/// regular [AblLink]. Every sample forces the recorded beat onto the timeline with
+/// [SessionState::force_beat_at_time], which is very anti-social behavior in a
+/// real session. Only replay onto a disabled instance!
+pub struct SessionReplayer {
+    recording: Recording,
+}
+
+impl SessionReplayer {
+    pub fn new(recording: Recording) -> SessionReplayer {
+        SessionReplayer { recording }
+    }
+
+    /// The recording played back by this replayer.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Get the last recorded sample at or before `time` (in recording time).
+    pub fn sample_at(&self, time: i64) -> Option<&TimelineSample> {
+        let samples = &self.recording.samples;
+        match samples.partition_point(|s| s.time <= time) {
+            0 => None,
+            i => Some(&samples[i - 1]),
+        }
+    }
+
+    /// Replay the recording onto `link` with the original timing between samples.
+    /// Blocks the calling thread until the whole recording has been played back.
+    ///
+    /// `on_sample` is invoked after each sample has been committed, which allows
+    /// observing the replay, for example with a sequencer under test.
+    pub fn replay<F: FnMut(&TimelineSample)>(&self, link: &AblLink, mut on_sample: F) {
+        let quantum = self.recording.quantum;
+        let mut session_state = SessionState::new();
+        let mut previous: Option<&TimelineSample> = None;
+
+        for sample in &self.recording.samples {
+            if let Some(previous) = previous {
+                let delta = sample.time.saturating_sub(previous.time).max(0);
+                thread::sleep(Duration::from_micros(delta as u64));
+            }
+
+            let now = link.clock_micros();
+            link.capture_app_session_state(&mut session_state);
+            session_state.set_tempo(sample.tempo, now);
+            session_state.force_beat_at_time(sample.beat, now as u64, quantum);
+            if session_state.is_playing() != sample.is_playing {
+                session_state.set_is_playing(sample.is_playing, now as u64);
+            }
+            link.commit_app_session_state(&session_state);
+
+            on_sample(sample);
+            previous = Some(sample);
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use crate::rust_bindings::*;
+use crate::{error::check_tempo, rust_bindings::*, Result};
+use std::fmt;
 
 ///  The representation of the current local state of a client in a Link Session.
 ///
@@ -17,6 +18,30 @@ use crate::rust_bindings::*;
 ///  start/stop change requests will be communicated to all connected peers.
 pub struct SessionState {
     pub(crate) session_state: abl_link_session_state,
+    /// Was the tempo changed or a beat requested since the last capture?
+    pub(crate) timeline_modified: bool,
+    /// Was transport started or stopped since the last capture?
+    pub(crate) transport_modified: bool,
+    /// Set by the capture functions of [AblLink](crate::AblLink)
+    pub(crate) capture: Option<Capture>,
+}
+
+/// The thread role a [SessionState] was captured for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureRole {
+    /// Captured with [AblLink::capture_audio_session_state](crate::AblLink::capture_audio_session_state)
+    Audio,
+    /// Captured with [AblLink::capture_app_session_state](crate::AblLink::capture_app_session_state)
+    /// or [AblLink::capture_render_session_state](crate::AblLink::capture_render_session_state)
+    App,
+}
+
+/// When and for which role a [SessionState] was last captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Capture {
+    pub generation: u64,
+    pub role: CaptureRole,
+    pub time: i64,
 }
 
 unsafe impl Send for SessionState {}
@@ -27,6 +52,32 @@ impl Drop for SessionState {
     }
 }
 
+impl fmt::Debug for SessionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionState")
+            .field("tempo", &self.tempo())
+            .field("is_playing", &self.is_playing())
+            .field("time_for_is_playing", &self.time_for_is_playing())
+            .finish()
+    }
+}
+
+/// Tempo and transport state, e.g. `120.00 bpm, playing`.
+impl fmt::Display for SessionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2} bpm, {}",
+            self.tempo(),
+            if self.is_playing() {
+                "playing"
+            } else {
+                "stopped"
+            }
+        )
+    }
+}
+
 impl Default for SessionState {
     fn default() -> Self {
         Self::new()
@@ -47,6 +98,9 @@ impl SessionState {
         unsafe {
             SessionState {
                 session_state: abl_link_create_session_state(),
+                timeline_modified: false,
+                transport_modified: false,
+                capture: None,
             }
         }
     }
@@ -62,9 +116,17 @@ impl SessionState {
 
     ///  Set the timeline tempo to the given bpm value, taking effect at the given time.
     pub fn set_tempo(&mut self, bpm: f64, at_time: i64) {
+        self.timeline_modified = true;
         unsafe { abl_link_set_tempo(self.session_state, bpm, at_time) }
     }
 
+    /// Same as [SessionState::set_tempo], but returns [Error::TempoOutOfRange](crate::Error::TempoOutOfRange)
+    /// instead of letting Link clamp tempi outside of [MIN_TEMPO](crate::MIN_TEMPO)..=[MAX_TEMPO](crate::MAX_TEMPO).
+    pub fn try_set_tempo(&mut self, bpm: f64, at_time: i64) -> Result<()> {
+        self.set_tempo(check_tempo(bpm)?, at_time);
+        Ok(())
+    }
+
     ///  Get the beat value corresponding to the given time for the given quantum.
     ///
     ///  The magnitude of the resulting beat value is unique to this Link
@@ -115,6 +177,7 @@ impl SessionState {
     ///  the session. The client application only needs to invoke this function to achieve
     ///  this behavior and should not need to explicitly check the number of peers.
     pub fn request_beat_at_time(&mut self, beat: f64, time: i64, quantum: f64) {
+        self.timeline_modified = true;
         unsafe { abl_link_request_beat_at_time(self.session_state, beat, time, quantum) }
     }
 
@@ -134,14 +197,54 @@ impl SessionState {
     ///  such a feature so that users do not accidentally disrupt Link sessions that they may
     ///  join.
     pub fn force_beat_at_time(&mut self, beat: f64, time: u64, quantum: f64) {
+        self.timeline_modified = true;
         unsafe { abl_link_force_beat_at_time(self.session_state, beat, time, quantum) }
     }
 
     /// Set if transport should be playing or stopped, taking effect at the given time.
     pub fn set_is_playing(&mut self, is_playing: bool, time: u64) {
+        self.transport_modified = true;
         unsafe { abl_link_set_is_playing(self.session_state, is_playing, time) }
     }
 
+    /// Was the tempo changed or a beat requested since the Session State was captured?
+    ///
+    /// Commits of such Session States are dropped with [CommitPolicy::FollowOnly](crate::CommitPolicy::FollowOnly).
+    pub fn is_timeline_modified(&self) -> bool {
+        self.timeline_modified
+    }
+
+    /// Copy timeline and start/stop state of `other` into this Session State.
+    ///
+    ///  Thread-safe: no
+    ///
+    ///  Realtime-safe: yes
+    ///
+    /// Allows double buffering without creating Session States, e.g. an app thread prepares
+    /// the next state while the audio thread reads a copy of the previous one.
+    pub fn copy_from(&mut self, other: &SessionState) {
+        unsafe { rusty_link_shim_copy_session_state(self.session_state, other.session_state) }
+        self.timeline_modified = other.timeline_modified;
+        self.transport_modified = other.transport_modified;
+        self.capture = other.capture;
+    }
+
+    /// Number of the capture which filled this Session State. Increases with every capture
+    /// of the same [AblLink](crate::AblLink) instance, `None` if it was never captured.
+    pub fn generation(&self) -> Option<u64> {
+        self.capture.map(|capture| capture.generation)
+    }
+
+    /// The thread role this Session State was last captured for.
+    pub fn captured_by(&self) -> Option<CaptureRole> {
+        self.capture.map(|capture| capture.role)
+    }
+
+    /// The Link clock time in microseconds of the last capture.
+    pub fn captured_at(&self) -> Option<i64> {
+        self.capture.map(|capture| capture.time)
+    }
+
     /// Is transport playing?
     pub fn is_playing(&self) -> bool {
         unsafe { abl_link_is_playing(self.session_state) }
@@ -156,6 +259,7 @@ impl SessionState {
     /// when transport is starting to play in context of the given quantum.
     /// This function evaluates to a no-op if abl_link_is_playing equals false.
     pub fn request_beat_at_start_playing_time(&mut self, beat: f64, quantum: f64) {
+        self.timeline_modified = true;
         unsafe { abl_link_request_beat_at_start_playing_time(self.session_state, beat, quantum) }
     }
 
@@ -168,6 +272,8 @@ impl SessionState {
         beat: f64,
         quantum: f64,
     ) {
+        self.timeline_modified = true;
+        self.transport_modified = true;
         unsafe {
             abl_link_set_is_playing_and_request_beat_at_time(
                 self.session_state,
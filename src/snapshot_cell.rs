@@ -0,0 +1,106 @@
+use std::{
+    cell::UnsafeCell,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+};
+
+/// Set in `middle`, if the middle slot holds a snapshot the reader has not seen yet.
+const NEW: u8 = 0b100;
+const INDEX: u8 = 0b011;
+
+/// Lock-free handoff of snapshots from one thread to another (triple buffer), e.g. of
+/// [FrameInfo](crate::FrameInfo)s or other plain values derived from a Session State.
+///
+/// The [SnapshotWriter] publishes snapshots, the [SnapshotReader] always reads the latest
+/// published one. Neither side ever waits for the other or allocates, so both can be used on
+/// the audio thread. Snapshots published between two reads are skipped.
+///
+/// ```no_run
+/// # use rusty_link::{AblLink, FrameInfo, SnapshotCell};
+/// # let link = AblLink::new(120.);
+/// let (mut writer, mut reader) = SnapshotCell::new(link.audio_frame_info(0, 4.));
+/// // Audio thread
+/// writer.publish(link.audio_frame_info(link.clock_micros(), 4.));
+/// // UI thread
+/// let info: FrameInfo = reader.latest();
+/// ```
+pub struct SnapshotCell<T> {
+    slots: [UnsafeCell<T>; 3],
+    /// Index of the slot between writer and reader, with the [NEW] flag
+    middle: AtomicU8,
+}
+
+// The writer and the reader only ever access the slot they own
+unsafe impl<T: Send> Sync for SnapshotCell<T> {}
+
+impl<T: Copy + Send> SnapshotCell<T> {
+    /// A cell holding `initial`, split into its writer and reader.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(initial: T) -> (SnapshotWriter<T>, SnapshotReader<T>) {
+        let cell = Arc::new(SnapshotCell {
+            slots: [
+                UnsafeCell::new(initial),
+                UnsafeCell::new(initial),
+                UnsafeCell::new(initial),
+            ],
+            middle: AtomicU8::new(1),
+        });
+
+        (
+            SnapshotWriter {
+                cell: Arc::clone(&cell),
+                back: 0,
+            },
+            SnapshotReader { cell, front: 2 },
+        )
+    }
+}
+
+/// Publishing side of a [SnapshotCell].
+pub struct SnapshotWriter<T> {
+    cell: Arc<SnapshotCell<T>>,
+    back: u8,
+}
+
+impl<T: Copy + Send> SnapshotWriter<T> {
+    /// Publish `snapshot`, replacing a previous snapshot the reader has not read yet.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn publish(&mut self, snapshot: T) {
+        unsafe { *self.cell.slots[self.back as usize].get() = snapshot };
+        self.back = self.cell.middle.swap(self.back | NEW, Ordering::AcqRel) & INDEX;
+    }
+}
+
+/// Reading side of a [SnapshotCell].
+pub struct SnapshotReader<T> {
+    cell: Arc<SnapshotCell<T>>,
+    front: u8,
+}
+
+impl<T: Copy + Send> SnapshotReader<T> {
+    /// The latest published snapshot, or the initial value if none was published.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn latest(&mut self) -> T {
+        self.update();
+        unsafe { *self.cell.slots[self.front as usize].get() }
+    }
+
+    /// Was a snapshot published since the last read?
+    pub fn has_new(&self) -> bool {
+        self.cell.middle.load(Ordering::Acquire) & NEW != 0
+    }
+
+    fn update(&mut self) {
+        if self.has_new() {
+            self.front = self.cell.middle.swap(self.front, Ordering::AcqRel) & INDEX;
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{AblLink, GateMode, GateState, SessionState, TransportGate};
+use std::time::Duration;
+
+/// A step of a [StepClock].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepEvent {
+    /// Index of the step in its bar, `0..steps_per_bar`.
+    pub index: usize,
+    /// Number of the step on the timeline, counted from beat 0.
+    pub step: i64,
+    /// Beat of the step, including swing.
+    pub beat: f64,
+    /// Link clock time of the step in microseconds.
+    pub time: i64,
+    pub is_accent: bool,
+}
+
+/// Clock of a step sequencer, emitting evenly spaced [StepEvent]s aligned to the bars of
+/// the Link timeline, e.g. 16 steps per bar for a groovebox.
+///
+/// Like [NoteScheduler](crate::NoteScheduler), the clock is driven by [StepClock::poll] and
+/// emits steps a lookahead ahead of their time, computed from the current Session State,
+/// so tempo changes are taken into account. The bar length is the quantum of the app, see
+/// [AblLink::quantum]. By default no steps are emitted while transport is stopped, see
+/// [TransportGate]; after transport starts or the timeline jumps, the clock continues with
+/// the next step on the grid.
+pub struct StepClock<F: FnMut(StepEvent)> {
+    emit: F,
+    pub steps_per_bar: usize,
+    /// Delay of every second step as a fraction of the step length, `0` for straight
+    /// timing. Clamped to `[0, 0.9]`.
+    pub swing: f64,
+    /// Bit per step of the bar, set for accented steps. Only the first step by default.
+    pub accents: u64,
+    lookahead: Duration,
+    gate: TransportGate,
+    next_step: Option<i64>,
+    /// End of the window of the previous call, Link clock time
+    last_until: Option<i64>,
+    session_state: SessionState,
+}
+
+impl<F: FnMut(StepEvent)> StepClock<F> {
+    /// A clock with `steps_per_bar` straight steps and 10ms of lookahead.
+    pub fn new(steps_per_bar: usize, emit: F) -> Self {
+        Self {
+            emit,
+            steps_per_bar: steps_per_bar.max(1),
+            swing: 0.,
+            accents: 1,
+            lookahead: Duration::from_millis(10),
+            gate: TransportGate::default(),
+            next_step: None,
+            last_until: None,
+            session_state: SessionState::new(),
+        }
+    }
+
+    pub fn with_swing(mut self, swing: f64) -> Self {
+        self.swing = swing;
+        self
+    }
+
+    pub fn with_accents(mut self, accents: u64) -> Self {
+        self.accents = accents;
+        self
+    }
+
+    /// How far ahead of their time steps are emitted.
+    pub fn with_lookahead(mut self, lookahead: Duration) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    /// Behavior while transport is stopped. Muted steps are not emitted.
+    pub fn with_transport_gate(mut self, gate: TransportGate) -> Self {
+        self.gate = gate;
+        self
+    }
+
+    /// Capture the app Session State of `link` and emit the steps due at the current time
+    /// plus the lookahead. Returns the number of emitted steps.
+    pub fn poll(&mut self, link: &AblLink) -> usize {
+        self.poll_at(link, link.clock_micros())
+    }
+
+    /// Same as [StepClock::poll], at the Link clock time `now` instead of the current
+    /// time, e.g. a virtual time in tests. Uses the app Session State, so it does not fit
+    /// the audio thread: call [StepClock::advance_to] with the audio Session State instead.
+    pub fn poll_at(&mut self, link: &AblLink, now: i64) -> usize {
+        if link.is_panicked() {
+            self.next_step = None;
+            return 0;
+        }
+        link.capture_app_session_state(&mut self.session_state);
+        link.apply_transport_policy(&mut self.session_state);
+        let lookahead = i64::try_from(self.lookahead.as_micros()).unwrap_or(i64::MAX);
+        self.emit_steps(link.quantum(), now, now.saturating_add(lookahead))
+    }
+
+    /// Emit the steps due up to the Link clock time `until` according to `session_state`
+    /// with bars of `quantum` beats, without lookahead. Returns the number of emitted steps.
+    ///
+    /// Pull-based alternative to [StepClock::poll] for contexts which can not run a timing
+    /// thread, e.g. plugins: call it from the audio callback with the audio Session State
+    /// and the end of the buffer, see [AudioBuffer](crate::AudioBuffer). Emits nothing, if
+    /// `quantum` is not a positive number of beats.
+    ///
+    ///  Realtime-safe: yes
+    pub fn advance_to(&mut self, session_state: &SessionState, quantum: f64, until: i64) -> usize {
+        // A zero step length would never leave the loop of emit_steps
+        if !(quantum.is_finite() && quantum > 0.) {
+            return 0;
+        }
+        self.session_state.copy_from(session_state);
+        let from = self
+            .last_until
+            .filter(|from| *from <= until)
+            .unwrap_or(until);
+        self.emit_steps(quantum, from, until)
+    }
+
+    /// Emit the steps between `now` and `until`, including steps missed since the last call.
+    fn emit_steps(&mut self, quantum: f64, now: i64, until: i64) -> usize {
+        self.last_until = Some(until);
+        if !self.session_state.is_playing() && self.gate.mode == GateMode::Pause {
+            self.next_step = None;
+            return 0;
+        }
+
+        let step_length = quantum / self.steps_per_bar as f64;
+        let beat = self.session_state.beat_at_time(now, quantum);
+        let until = self.session_state.beat_at_time(until, quantum);
+
+        // A step missed by a late poll is emitted late. After a start or a jump of the
+        // timeline, continue with the next step on the grid.
+        let first = (beat / step_length).ceil() as i64;
+        let last = (until / step_length).floor() as i64;
+        let mut step = match self.next_step {
+            Some(next) if next >= first - 1 && next <= last + 1 => next,
+            _ => first,
+        };
+
+        let mut count = 0;
+        loop {
+            let step_beat = self.step_beat(step, step_length);
+            if step_beat > until {
+                break;
+            }
+
+            // Steps before the resume beat of a started transport are skipped like muted ones
+            if self
+                .gate
+                .state_at_beat(&self.session_state, step_beat, quantum)
+                == GateState::Open
+            {
+                let index = step.rem_euclid(self.steps_per_bar as i64) as usize;
+                (self.emit)(StepEvent {
+                    index,
+                    step,
+                    beat: step_beat,
+                    time: self.session_state.time_at_beat(step_beat, quantum),
+                    is_accent: index < 64 && self.accents & (1 << index) != 0,
+                });
+                count += 1;
+            }
+            step += 1;
+        }
+
+        self.next_step = Some(step);
+        count
+    }
+
+    /// Beat of `step`, delayed by the swing for every second step.
+    fn step_beat(&self, step: i64, step_length: f64) -> f64 {
+        let swing = if step.rem_euclid(2) == 1 {
+            self.swing.clamp(0., 0.9) * step_length
+        } else {
+            0.
+        };
+        step as f64 * step_length + swing
+    }
+}
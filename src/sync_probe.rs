@@ -0,0 +1,128 @@
+use crate::SessionState;
+use std::{collections::VecDeque, fmt};
+
+/// Number of clicks a [SyncProbe] keeps by default.
+const DEFAULT_WINDOW: usize = 256;
+
+/// Measures how far rendered clicks are off the session grid and recommends a latency offset.
+///
+/// For every click, pass the beat it was rendered for and the time it was actually observed
+/// (e.g. measured with a loopback cable), both as Link clock time. The median difference
+/// is the recommended offset: positive values mean the clicks are late, so the output
+/// latency compensation of the app should be increased by that amount.
+#[derive(Debug, Clone)]
+pub struct SyncProbe {
+    window: usize,
+    /// Observed minus predicted click times in microseconds
+    offsets: VecDeque<i64>,
+}
+
+/// Statistics of a [SyncProbe]. All values in microseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncReport {
+    pub samples: usize,
+    pub mean: f64,
+    /// Standard deviation of the offsets.
+    pub jitter: f64,
+    pub min: i64,
+    pub max: i64,
+    /// Median of the offsets.
+    pub recommended_offset: i64,
+}
+
+impl Default for SyncProbe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncProbe {
+    pub fn new() -> Self {
+        Self::with_window(DEFAULT_WINDOW)
+    }
+
+    /// A probe which only keeps the last `window` clicks.
+    pub fn with_window(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            offsets: VecDeque::with_capacity(window.max(1)),
+        }
+    }
+
+    /// Add a click rendered for `beat` and observed at `observed_time`. The time the click
+    /// should have been heard is taken from `session_state`. Returns the offset of this click.
+    pub fn add_click(
+        &mut self,
+        session_state: &SessionState,
+        beat: f64,
+        quantum: f64,
+        observed_time: i64,
+    ) -> i64 {
+        let predicted_time = session_state.time_at_beat(beat, quantum);
+        self.add_offset(predicted_time, observed_time)
+    }
+
+    /// Add a click which should have been heard at `predicted_time` and was observed at
+    /// `observed_time`. Returns the offset of this click.
+    pub fn add_offset(&mut self, predicted_time: i64, observed_time: i64) -> i64 {
+        let offset = observed_time - predicted_time;
+        if self.offsets.len() == self.window {
+            self.offsets.pop_front();
+        }
+        self.offsets.push_back(offset);
+        offset
+    }
+
+    /// Statistics of the clicks in the window, `None` without clicks.
+    pub fn report(&self) -> Option<SyncReport> {
+        if self.offsets.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<i64> = self.offsets.iter().copied().collect();
+        sorted.sort_unstable();
+
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<i64>() as f64 / n;
+        let variance = sorted
+            .iter()
+            .map(|&offset| (offset as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+
+        Some(SyncReport {
+            samples: sorted.len(),
+            mean,
+            jitter: variance.sqrt(),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            recommended_offset: sorted[sorted.len() / 2],
+        })
+    }
+
+    /// Forget all clicks.
+    pub fn reset(&mut self) {
+        self.offsets.clear();
+    }
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = if self.recommended_offset >= 0 {
+            "late"
+        } else {
+            "early"
+        };
+        write!(
+            f,
+            "{} clicks | {:.1}ms {} (median) | mean {:.1}ms | jitter {:.2}ms | range {:.1}..{:.1}ms",
+            self.samples,
+            self.recommended_offset.abs() as f64 / 1e3,
+            direction,
+            self.mean / 1e3,
+            self.jitter / 1e3,
+            self.min as f64 / 1e3,
+            self.max as f64 / 1e3
+        )
+    }
+}
@@ -0,0 +1,70 @@
+// Publishing of Link state through the `metrics` facade (feature `metrics`).
+
+use crate::{AblLink, LinkEvent, SessionState};
+use metrics::{gauge, histogram};
+
+pub(crate) const TEMPO: &str = "rusty_link_tempo_bpm";
+pub(crate) const NUM_PEERS: &str = "rusty_link_num_peers";
+pub(crate) const IS_PLAYING: &str = "rusty_link_is_playing";
+pub(crate) const QUANTUM: &str = "rusty_link_quantum";
+pub(crate) const START_STOP_SYNC: &str = "rusty_link_start_stop_sync";
+pub(crate) const AUDIO_STALLED: &str = "rusty_link_audio_stalled";
+pub(crate) const NETWORK_AVAILABLE: &str = "rusty_link_network_available";
+pub(crate) const CLOCK_JITTER: &str = "rusty_link_clock_jitter_us";
+
+/// Update the gauge affected by a session change reported by Link.
+pub(crate) fn record_event(event: LinkEvent) {
+    match event {
+        LinkEvent::TempoChanged { bpm, .. } => gauge!(TEMPO).set(bpm),
+        LinkEvent::NumPeersChanged { num_peers }
+        | LinkEvent::PeerJoined { num_peers }
+        | LinkEvent::PeerLeft { num_peers } => gauge!(NUM_PEERS).set(num_peers as f64),
+        LinkEvent::StartStopChanged { is_playing } => {
+            gauge!(IS_PLAYING).set(if is_playing { 1. } else { 0. })
+        }
+        LinkEvent::QuantumChanged { quantum } => gauge!(QUANTUM).set(quantum),
+        LinkEvent::StartStopSyncChanged { enabled } => {
+            gauge!(START_STOP_SYNC).set(if enabled { 1. } else { 0. })
+        }
+        LinkEvent::AudioStalled { .. } => gauge!(AUDIO_STALLED).set(1.),
+        LinkEvent::AudioResumed => gauge!(AUDIO_STALLED).set(0.),
+        LinkEvent::NetworkChanged { available } => {
+            gauge!(NETWORK_AVAILABLE).set(if available { 1. } else { 0. })
+        }
+    }
+}
+
+/// Difference between the time a clock thread sent a tick and the time it was due.
+pub(crate) fn record_clock_jitter(lateness: i64) {
+    histogram!(CLOCK_JITTER).record(lateness as f64);
+}
+
+impl AblLink {
+    /// Publish tempo, peer count, transport state, quantum and start/stop sync of the app Session State as gauges
+    /// through the [metrics](https://crates.io/crates/metrics) facade.
+    ///
+    /// Gauges are updated automatically whenever Link reports a change. Call this once
+    /// after enabling Link, so the gauges have values before the first change.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn publish_metrics(&self) {
+        let mut session_state = SessionState::new();
+        self.capture_app_session_state(&mut session_state);
+
+        gauge!(TEMPO).set(session_state.tempo());
+        record_event(LinkEvent::NumPeersChanged {
+            num_peers: self.num_peers(),
+        });
+        record_event(LinkEvent::StartStopChanged {
+            is_playing: session_state.is_playing(),
+        });
+        record_event(LinkEvent::QuantumChanged {
+            quantum: self.quantum(),
+        });
+        record_event(LinkEvent::StartStopSyncChanged {
+            enabled: self.is_start_stop_sync_enabled(),
+        });
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{AblLink, SessionState};
+use std::{cmp::Ordering, collections::BinaryHeap, fmt};
+
+type Routine = Box<dyn FnMut(f64) -> Option<f64> + Send>;
+
+/// A beat-based scheduler on the Link timeline, modelled after SuperCollider's `TempoClock`.
+///
+/// Routines are closures, which are called with their logical beat: the beat they were
+/// scheduled for, not the beat at which they actually run. Returning `Some(delta)`
+/// reschedules the routine `delta` beats after its logical beat, `None` ends it. Because
+/// all timing is derived from logical beats, routines never accumulate scheduling jitter.
+///
+/// The clock has no thread of its own. Call [TempoClock::poll] regularly, e.g. from a
+/// control loop, or [TempoClock::advance_to] with the beat at the end of an audio buffer.
+pub struct TempoClock {
+    quantum: f64,
+    /// Logical time: the beat of the running routine or the beat the clock advanced to
+    beats: f64,
+    queue: BinaryHeap<Scheduled>,
+    /// Keeps routines of the same beat in the order they were scheduled
+    next_sequence: u64,
+    session_state: SessionState,
+}
+
+struct Scheduled {
+    beat: f64,
+    sequence: u64,
+    routine: Routine,
+}
+
+// BinaryHeap is a max-heap, so the earliest routine has to compare as the greatest
+impl Ord for Scheduled {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .beat
+            .total_cmp(&self.beat)
+            .then(other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for Scheduled {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Scheduled {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scheduled {}
+
+impl TempoClock {
+    /// A clock on the Link timeline with bars of `quantum` beats, starting at beat 0.
+    pub fn new(quantum: f64) -> Self {
+        Self {
+            quantum,
+            beats: 0.,
+            queue: BinaryHeap::new(),
+            next_sequence: 0,
+            session_state: SessionState::new(),
+        }
+    }
+
+    pub fn quantum(&self) -> f64 {
+        self.quantum
+    }
+
+    /// The logical time of the clock. While a routine runs, the beat it was scheduled for.
+    pub fn beats(&self) -> f64 {
+        self.beats
+    }
+
+    /// Number of scheduled routines.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Schedule `routine` to run `delta` beats after the logical time of the clock.
+    pub fn sched<F>(&mut self, delta: f64, routine: F)
+    where
+        F: FnMut(f64) -> Option<f64> + Send + 'static,
+    {
+        self.sched_abs(self.beats + delta, routine)
+    }
+
+    /// Schedule `routine` to run at `beat`. Beats in the past run at the next advance.
+    pub fn sched_abs<F>(&mut self, beat: f64, routine: F)
+    where
+        F: FnMut(f64) -> Option<f64> + Send + 'static,
+    {
+        self.push(beat, Box::new(routine));
+    }
+
+    /// Start `routine` on the next beat which is a multiple of `quant`, e.g. `4.` for the
+    /// next bar. A `quant` of zero starts it right away.
+    pub fn play<F>(&mut self, quant: f64, routine: F)
+    where
+        F: FnMut(f64) -> Option<f64> + Send + 'static,
+    {
+        let beat = if quant > 0. {
+            (self.beats / quant).ceil() * quant
+        } else {
+            self.beats
+        };
+        self.sched_abs(beat, routine)
+    }
+
+    /// Remove all scheduled routines.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Run all routines scheduled up to and including `beat` in order and move the
+    /// logical time to `beat`. Returns the number of routines run.
+    ///
+    /// Reschedule deltas which are not positive end the routine, since it would run forever.
+    pub fn advance_to(&mut self, beat: f64) -> usize {
+        let mut count = 0;
+        while self.queue.peek().is_some_and(|next| next.beat <= beat) {
+            let Some(mut scheduled) = self.queue.pop() else {
+                break;
+            };
+            self.beats = self.beats.max(scheduled.beat);
+            count += 1;
+
+            match (scheduled.routine)(scheduled.beat) {
+                Some(delta) if delta > 0. && delta.is_finite() => {
+                    self.push(scheduled.beat + delta, scheduled.routine)
+                }
+                _ => {}
+            }
+        }
+
+        self.beats = self.beats.max(beat);
+        count
+    }
+
+    /// Advance to the current beat of the app Session State of `link`.
+    /// Returns the number of routines run.
+    ///
+    /// Removes all scheduled routines after [AblLink::panic].
+    pub fn poll(&mut self, link: &AblLink) -> usize {
+        self.poll_at(link, link.clock_micros())
+    }
+
+    /// Same as [TempoClock::poll], at the Link clock time `now` instead of the current
+    /// time, e.g. a virtual time in tests. Uses the app Session State, so it does not fit
+    /// the audio thread: pass the beat of the audio buffer to [TempoClock::advance_to].
+    pub fn poll_at(&mut self, link: &AblLink, now: i64) -> usize {
+        if link.is_panicked() {
+            self.clear();
+            return 0;
+        }
+        link.capture_app_session_state(&mut self.session_state);
+        let beat = self.session_state.beat_at_time(now, self.quantum);
+        self.advance_to(beat)
+    }
+
+    /// Link clock time of `beat` according to the Session State captured by the last
+    /// [TempoClock::poll], e.g. to render a routine's event sample-accurately.
+    pub fn time_at_beat(&self, beat: f64) -> i64 {
+        self.session_state.time_at_beat(beat, self.quantum)
+    }
+
+    fn push(&mut self, beat: f64, routine: Routine) {
+        self.queue.push(Scheduled {
+            beat,
+            sequence: self.next_sequence,
+            routine,
+        });
+        self.next_sequence += 1;
+    }
+}
+
+impl fmt::Debug for TempoClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TempoClock")
+            .field("quantum", &self.quantum)
+            .field("beats", &self.beats)
+            .field("scheduled", &self.queue.len())
+            .finish()
+    }
+}
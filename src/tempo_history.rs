@@ -0,0 +1,101 @@
+use crate::{LinkEvent, TimedEvent};
+use std::{collections::VecDeque, time::Duration};
+
+/// Bounded record of tempo changes with their Link clock times, e.g. for tempo graphs or
+/// aligning recorded audio to the session afterwards.
+///
+/// Fill it from the tempo callback with [TempoHistory::record], or from the event history
+/// of [AblLink](crate::AblLink) with [TempoHistory::record_events].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoHistory {
+    capacity: usize,
+    /// (time, bpm), ordered by time
+    samples: VecDeque<(i64, f64)>,
+}
+
+impl TempoHistory {
+    /// A history keeping the last `capacity` tempo changes.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Record that the tempo changed to `bpm` at `time`. Changes older than the last
+    /// recorded one are ignored.
+    pub fn record(&mut self, time: i64, bpm: f64) {
+        if self.samples.back().is_some_and(|&(last, _)| time < last) {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((time, bpm));
+    }
+
+    /// Record the tempo changes of `events` newer than the last recorded change, e.g. from
+    /// [AblLink::recent_events](crate::AblLink::recent_events).
+    pub fn record_events(&mut self, events: &[TimedEvent]) {
+        let last = self.samples.back().map(|&(time, _)| time);
+        for event in events {
+            if let LinkEvent::TempoChanged { bpm, .. } = event.event {
+                if last.is_none_or(|last| event.time > last) {
+                    self.record(event.time, bpm);
+                }
+            }
+        }
+    }
+
+    /// The recorded tempo changes as (time, bpm), oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = (i64, f64)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// The tempo at `time`, `None` before the first recorded change.
+    pub fn bpm_at(&self, time: i64) -> Option<f64> {
+        let index = self.samples.partition_point(|&(t, _)| t <= time);
+        index.checked_sub(1).map(|i| self.samples[i].1)
+    }
+
+    /// Time-weighted average tempo in the `window` before `now`. Only the part of the window
+    /// after the first recorded change is taken into account.
+    pub fn average_over(&self, window: Duration, now: i64) -> Option<f64> {
+        let window = i64::try_from(window.as_micros()).unwrap_or(i64::MAX);
+        let start = now.saturating_sub(window);
+        let first = self.samples.front()?.0;
+        let from = start.max(first);
+        if from >= now {
+            return self.bpm_at(now);
+        }
+
+        let mut weighted = 0.;
+        let mut time = from;
+        let mut bpm = self.bpm_at(from)?;
+        let next = self.samples.partition_point(|&(t, _)| t <= from);
+        for &(change_time, change_bpm) in self.samples.range(next..) {
+            if change_time >= now {
+                break;
+            }
+            weighted += bpm * (change_time - time) as f64;
+            time = change_time;
+            bpm = change_bpm;
+        }
+        weighted += bpm * (now - time) as f64;
+
+        Some(weighted / (now - from) as f64)
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{AblLink, SessionState};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Limits the rate of tempo commits from continuous controllers, e.g. a tempo knob or
+/// slider, to avoid tempo thrash across the session.
+///
+/// At most one tempo is committed per interval. Tempi set in between replace each other
+/// (last write wins) and the latest one is committed by [TempoCommitLimiter::poll] once the
+/// interval has passed, so the session ends up at the final value of the controller. Call
+/// `poll` regularly, e.g. once per GUI frame. Pending tempi are committed on drop.
+pub struct TempoCommitLimiter {
+    link: Arc<AblLink>,
+    interval: Duration,
+    last_commit: Option<Instant>,
+    pending: Option<f64>,
+    session_state: SessionState,
+}
+
+impl TempoCommitLimiter {
+    /// A limiter committing at most `max_commits_per_second` tempi to `link`.
+    pub fn new(link: Arc<AblLink>, max_commits_per_second: f64) -> Self {
+        Self {
+            link,
+            interval: Duration::from_secs_f64(1. / max_commits_per_second.max(f64::MIN_POSITIVE)),
+            last_commit: None,
+            pending: None,
+            session_state: SessionState::new(),
+        }
+    }
+
+    /// Set the tempo. Returns whether it was committed right away, otherwise it is pending
+    /// until the next [TempoCommitLimiter::poll] after the interval.
+    pub fn set_tempo(&mut self, bpm: f64) -> bool {
+        self.pending = Some(bpm);
+        self.poll()
+    }
+
+    /// Commit the pending tempo, if the interval since the last commit has passed. Returns
+    /// whether a tempo was committed.
+    pub fn poll(&mut self) -> bool {
+        let now = Instant::now();
+        let due = self
+            .last_commit
+            .is_none_or(|last| now.duration_since(last) >= self.interval);
+        if !due || self.pending.is_none() {
+            return false;
+        }
+        self.commit(now);
+        true
+    }
+
+    /// Commit the pending tempo now, regardless of the interval.
+    pub fn flush(&mut self) {
+        if self.pending.is_some() {
+            self.commit(Instant::now());
+        }
+    }
+
+    /// The tempo waiting to be committed.
+    pub fn pending(&self) -> Option<f64> {
+        self.pending
+    }
+
+    fn commit(&mut self, now: Instant) {
+        let Some(bpm) = self.pending.take() else {
+            return;
+        };
+        self.link.capture_app_session_state(&mut self.session_state);
+        self.session_state.set_tempo(bpm, self.link.clock_micros());
+        self.link.commit_app_session_state(&self.session_state);
+        self.last_commit = Some(now);
+        trace!(bpm, "commit limited tempo");
+    }
+}
+
+impl Drop for TempoCommitLimiter {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
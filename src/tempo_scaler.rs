@@ -0,0 +1,63 @@
+use crate::SessionState;
+
+/// A virtual timeline running at a fixed ratio of the session tempo, e.g. to play
+/// half-time against a jam without changing the shared tempo.
+///
+/// Beats of the virtual timeline are session beats multiplied by the ratio. Quanta are
+/// scaled as well, so virtual bars stay aligned with the session: with a ratio of 0.5, a
+/// virtual bar of 4 beats spans 8 session beats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempoScaler {
+    ratio: f64,
+}
+
+impl TempoScaler {
+    /// A scaler with the given ratio of virtual to session tempo. Panics if the ratio is
+    /// not positive.
+    pub fn new(ratio: f64) -> Self {
+        assert!(
+            ratio > 0. && ratio.is_finite(),
+            "Tempo ratio has to be positive."
+        );
+        Self { ratio }
+    }
+
+    /// Half the session tempo.
+    pub fn half_time() -> Self {
+        Self::new(0.5)
+    }
+
+    /// Double the session tempo.
+    pub fn double_time() -> Self {
+        Self::new(2.)
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Tempo of the virtual timeline, in Beats Per Minute.
+    pub fn tempo(&self, session_state: &SessionState) -> f64 {
+        session_state.tempo() * self.ratio
+    }
+
+    /// Beat of the virtual timeline at `time`, for a virtual `quantum`.
+    pub fn beat_at_time(&self, session_state: &SessionState, time: i64, quantum: f64) -> f64 {
+        session_state.beat_at_time(time, self.session_quantum(quantum)) * self.ratio
+    }
+
+    /// Phase of the virtual timeline at `time`, in the interval `[0, quantum]`.
+    pub fn phase_at_time(&self, session_state: &SessionState, time: i64, quantum: f64) -> f64 {
+        session_state.phase_at_time(time, self.session_quantum(quantum)) * self.ratio
+    }
+
+    /// Time at which `beat` of the virtual timeline occurs, for a virtual `quantum`.
+    pub fn time_at_beat(&self, session_state: &SessionState, beat: f64, quantum: f64) -> i64 {
+        session_state.time_at_beat(beat / self.ratio, self.session_quantum(quantum))
+    }
+
+    /// Quantum of the session, which spans the same time as `quantum` of the virtual timeline.
+    pub fn session_quantum(&self, quantum: f64) -> f64 {
+        quantum / self.ratio
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{AblLink, Result};
+use std::{
+    io,
+    thread::{self, JoinHandle},
+};
+
+/// Scheduling priority of a thread spawned by this crate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThreadPriority {
+    /// Priority inherited from the spawning thread.
+    #[default]
+    Normal,
+    /// Realtime (`SCHED_FIFO`) priority, clamped to the range supported by the OS.
+    ///
+    /// Usually requires privileges, e.g. `RLIMIT_RTPRIO` on Linux. Only supported on unix
+    /// platforms.
+    Realtime(u8),
+}
+
+impl ThreadPriority {
+    /// Apply the priority to the calling thread.
+    pub fn apply_to_current_thread(self) -> Result<()> {
+        match self {
+            ThreadPriority::Normal => Ok(()),
+            ThreadPriority::Realtime(priority) => set_realtime_priority(priority),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn set_realtime_priority(priority: u8) -> Result<()> {
+    unsafe {
+        let min = libc::sched_get_priority_min(libc::SCHED_FIFO);
+        let max = libc::sched_get_priority_max(libc::SCHED_FIFO);
+        let param = libc::sched_param {
+            sched_priority: (priority as libc::c_int).clamp(min, max),
+        };
+
+        match libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) {
+            0 => Ok(()),
+            error => Err(io::Error::from_raw_os_error(error).into()),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn set_realtime_priority(_priority: u8) -> Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "realtime thread priority is only supported on unix",
+    )
+    .into())
+}
+
+/// Default prefix of the names of threads spawned by this crate.
+pub const DEFAULT_THREAD_NAME_PREFIX: &str = "rl";
+
+/// Configuration of the background threads spawned by this crate, e.g. by
+/// [MidiClockOut](crate::MidiClockOut) or [SessionRecorder](crate::SessionRecorder).
+///
+/// Timing threads running at normal priority get jittery under load. If the requested
+/// priority can not be applied, the thread keeps running at normal priority.
+///
+/// Threads are named `<name_prefix>/<role>` (e.g. `rl/midi-clock`), so they can be
+/// identified in profilers and debuggers. Linux truncates thread names to 15 bytes, which
+/// the default names fit into, so keep a custom prefix short.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadConfig {
+    pub priority: ThreadPriority,
+    pub name_prefix: String,
+}
+
+impl Default for ThreadConfig {
+    fn default() -> Self {
+        Self::new(ThreadPriority::default())
+    }
+}
+
+impl ThreadConfig {
+    pub fn new(priority: ThreadPriority) -> Self {
+        Self {
+            priority,
+            name_prefix: DEFAULT_THREAD_NAME_PREFIX.to_string(),
+        }
+    }
+
+    /// Name of the thread with the given role.
+    pub fn thread_name(&self, role: &str) -> String {
+        format!("{}/{}", self.name_prefix, role)
+    }
+
+    pub(crate) fn spawn<T, F>(&self, role: &str, f: F) -> JoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let priority = self.priority;
+        let name = self.thread_name(role);
+        thread::Builder::new()
+            .name(name.clone())
+            .spawn(move || {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::debug_span!("thread", name = %name).entered();
+
+                // Falls back to normal priority, e.g. without the required privileges
+                if let Err(_error) = priority.apply_to_current_thread() {
+                    debug!(?priority, error = %_error, "could not apply thread priority");
+                }
+                f()
+            })
+            .expect("Failed to spawn thread.")
+    }
+}
+
+impl AblLink {
+    /// Configure the threads spawned for this instance without a [ThreadConfig] of their
+    /// own: the callback dispatcher (see [Delivery::Dispatcher](crate::Delivery::Dispatcher))
+    /// and the shutdown thread of a [LinkRuntime](crate::LinkRuntime). Applies to threads
+    /// started afterwards.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn set_thread_config(&self, config: ThreadConfig) {
+        *self
+            .callbacks
+            .thread_config
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = config;
+    }
+
+    /// The configuration set with [AblLink::set_thread_config].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn thread_config(&self) -> ThreadConfig {
+        self.callbacks
+            .thread_config
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
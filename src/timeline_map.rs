@@ -0,0 +1,140 @@
+use crate::SessionState;
+
+/// Maps an application-internal song position (in beats since the start of the song) to
+/// beats of the Link timeline.
+///
+/// The mapping is anchored at one pair of song and Link beats, plus an adjustable offset.
+/// While transport is stopped, the song position stays where transport stopped. Use
+/// [TimelineMap::start_playing], [TimelineMap::stop_playing] and
+/// [TimelineMap::request_beat_at_time] instead of the functions of [SessionState], so
+/// the song position stays continuous when Link re-maps its beats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineMap {
+    quantum: f64,
+    /// Song beat and Link beat of the same point in time
+    anchor: Option<(f64, f64)>,
+    offset: f64,
+    /// Song position while transport is stopped
+    stopped_at: Option<f64>,
+}
+
+impl TimelineMap {
+    /// A map for songs with bars of `quantum` beats. Starts out stopped at the beginning
+    /// of the song.
+    pub fn new(quantum: f64) -> Self {
+        Self {
+            quantum,
+            anchor: None,
+            offset: 0.,
+            stopped_at: Some(0.),
+        }
+    }
+
+    pub fn quantum(&self) -> f64 {
+        self.quantum
+    }
+
+    /// Map `song_beat` to `link_beat`, e.g. after locating in the song while playing.
+    pub fn anchor(&mut self, song_beat: f64, link_beat: f64) {
+        self.anchor = Some((song_beat, link_beat));
+        self.stopped_at = None;
+    }
+
+    /// Beats added to every song position, e.g. to nudge the song against the session.
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, beats: f64) {
+        self.offset = beats;
+    }
+
+    /// Is the song position frozen, because transport is stopped?
+    pub fn is_stopped(&self) -> bool {
+        self.stopped_at.is_some()
+    }
+
+    /// Song position at `link_beat`. While stopped, the position where transport stopped.
+    pub fn song_beat(&self, link_beat: f64) -> f64 {
+        match (self.stopped_at, self.anchor) {
+            (Some(stopped_at), _) => stopped_at,
+            (None, Some((song, link))) => song + (link_beat - link) + self.offset,
+            (None, None) => link_beat + self.offset,
+        }
+    }
+
+    /// Link beat of `song_beat`, `None` while stopped.
+    pub fn link_beat(&self, song_beat: f64) -> Option<f64> {
+        if self.is_stopped() {
+            return None;
+        }
+        let (song, link) = self.anchor.unwrap_or((0., 0.));
+        Some(link + (song_beat - self.offset - song))
+    }
+
+    /// Song position at `time`.
+    pub fn song_beat_at_time(&self, session_state: &SessionState, time: i64) -> f64 {
+        self.song_beat(session_state.beat_at_time(time, self.quantum))
+    }
+
+    /// Song position in bars since the start of the song at `time`, counted from 0.
+    pub fn bars_at_time(&self, session_state: &SessionState, time: i64) -> f64 {
+        self.song_beat_at_time(session_state, time) / self.quantum
+    }
+
+    /// Same as [SessionState::request_beat_at_time], but re-anchors the map, so the song
+    /// position at `time` does not jump when Link re-maps its beats.
+    pub fn request_beat_at_time(&mut self, session_state: &mut SessionState, beat: f64, time: i64) {
+        let song_beat = self.song_beat_at_time(session_state, time);
+        session_state.request_beat_at_time(beat, time, self.quantum);
+
+        if !self.is_stopped() {
+            self.anchor = Some((
+                song_beat - self.offset,
+                session_state.beat_at_time(time, self.quantum),
+            ));
+        }
+    }
+
+    /// Start transport at `time` and continue the song from where it was stopped.
+    ///
+    /// The song position is requested as Link beat, so with other peers in the session the
+    /// start is quantized and song bars line up with the bars of the session.
+    pub fn start_playing(&mut self, session_state: &mut SessionState, time: i64) {
+        let Some(song_beat) = self.stopped_at else {
+            return;
+        };
+
+        let beat = song_beat - self.offset;
+        session_state.set_is_playing_and_request_beat_at_time(
+            true,
+            time.max(0) as u64,
+            beat,
+            self.quantum,
+        );
+        self.anchor = Some((beat, beat));
+        self.stopped_at = None;
+    }
+
+    /// Stop transport at `time` and freeze the song position at that time.
+    pub fn stop_playing(&mut self, session_state: &mut SessionState, time: i64) {
+        if self.is_stopped() {
+            return;
+        }
+
+        self.stopped_at = Some(self.song_beat_at_time(session_state, time));
+        session_state.set_is_playing(false, time.max(0) as u64);
+    }
+
+    /// Jump to `song_beat`. While playing, the jump happens at `time`.
+    pub fn locate(&mut self, session_state: &SessionState, song_beat: f64, time: i64) {
+        if self.is_stopped() {
+            self.stopped_at = Some(song_beat);
+        } else {
+            self.anchor(
+                song_beat - self.offset,
+                session_state.beat_at_time(time, self.quantum),
+            );
+        }
+    }
+}
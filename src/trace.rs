@@ -0,0 +1,27 @@
+// Internal diagnostics macros. They forward to `tracing` with the `tracing` feature and
+// compile to nothing otherwise. Arguments use the `tracing` field syntax.
+//
+// Events in realtime-safe functions are emitted at TRACE level and outside of the checked
+// section, since a subscriber may allocate.
+
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+#[allow(unused_macros)]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($arg)*);
+    };
+}
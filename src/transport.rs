@@ -0,0 +1,130 @@
+use crate::{AblLink, Error, Result, SessionState};
+use std::fmt;
+
+/// The state of transport, derived from the start/stop state and timeline of a [SessionState].
+///
+/// A start requested in a session with other peers is quantized: Link reports transport
+/// as playing right away, but maps beat 0 to the next matching phase. Until the timeline
+/// reaches the start beat, transport is [Transport::StartQueuedAt] that beat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transport {
+    Stopped,
+    /// Transport starts when the timeline reaches this beat.
+    StartQueuedAt(f64),
+    /// Transport is playing since this Link clock time.
+    Playing {
+        since: i64,
+    },
+}
+
+impl Transport {
+    /// The state of transport at `now`, with beats of the given quantum.
+    pub fn from_session_state(session_state: &SessionState, now: i64, quantum: f64) -> Self {
+        if !session_state.is_playing() {
+            return Transport::Stopped;
+        }
+
+        let mut start_time = session_state.time_for_is_playing() as i64;
+        if session_state.beat_at_time(start_time, quantum) < 0. {
+            // Quantized start, transport is silent until beat 0
+            start_time = session_state.time_at_beat(0., quantum);
+        }
+
+        if now < start_time {
+            Transport::StartQueuedAt(session_state.beat_at_time(start_time, quantum))
+        } else {
+            Transport::Playing { since: start_time }
+        }
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        matches!(self, Transport::Stopped)
+    }
+
+    pub fn is_start_queued(&self) -> bool {
+        matches!(self, Transport::StartQueuedAt(_))
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(self, Transport::Playing { .. })
+    }
+
+    /// Start transport at `now` with beat 0 at the next bar of the session, or right away
+    /// without peers. Only possible while stopped.
+    ///
+    /// Returns the new state. `session_state` has to be committed to take effect.
+    pub fn start(
+        self,
+        session_state: &mut SessionState,
+        now: i64,
+        quantum: f64,
+    ) -> Result<Transport> {
+        if !self.is_stopped() {
+            return Err(Error::InvalidTransition {
+                action: "start",
+                state: self,
+            });
+        }
+
+        session_state.set_is_playing_and_request_beat_at_time(true, now as u64, 0., quantum);
+        Ok(Transport::from_session_state(session_state, now, quantum))
+    }
+
+    /// Stop transport at `now`, or cancel a queued start. Not possible while stopped.
+    ///
+    /// Returns the new state. `session_state` has to be committed to take effect.
+    pub fn stop(self, session_state: &mut SessionState, now: i64) -> Result<Transport> {
+        if self.is_stopped() {
+            return Err(Error::InvalidTransition {
+                action: "stop",
+                state: self,
+            });
+        }
+
+        session_state.set_is_playing(false, now as u64);
+        Ok(Transport::Stopped)
+    }
+}
+
+/// `stopped`, `start queued at beat 0.00` or `playing since 1234us`.
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transport::Stopped => write!(f, "stopped"),
+            Transport::StartQueuedAt(beat) => write!(f, "start queued at beat {:.2}", beat),
+            Transport::Playing { since } => write!(f, "playing since {}us", since),
+        }
+    }
+}
+
+impl AblLink {
+    /// The state of transport according to the app Session State, see [Transport].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn transport(&self, quantum: f64) -> Transport {
+        self.transport_at(self.clock_micros(), quantum)
+    }
+
+    /// Same as [AblLink::transport], at the Link clock time `now` instead of the current
+    /// time.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn transport_at(&self, now: i64, quantum: f64) -> Transport {
+        let mut session_state = SessionState::new();
+        self.capture_app_session_state(&mut session_state);
+        Transport::from_session_state(&session_state, now, quantum)
+    }
+
+    /// [AblLink::transport] with the quantum of the app, see [AblLink::quantum].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: no
+    pub fn current_transport(&self) -> Transport {
+        self.transport(self.quantum())
+    }
+}
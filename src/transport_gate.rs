@@ -0,0 +1,120 @@
+use crate::{SessionState, Transport};
+
+/// What a [TransportGate] does with the output of a subsystem while transport is stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GateMode {
+    /// Stop following the timeline. Position is picked up again when transport plays.
+    #[default]
+    Pause,
+    /// Keep following the timeline and producing output regardless of transport.
+    FreeRun,
+    /// Keep following the timeline, but drop the output.
+    Mute,
+}
+
+/// Where output continues after transport started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResumeAlignment {
+    /// Right at the start of transport.
+    #[default]
+    Immediately,
+    /// At the first whole beat at or after the start of transport.
+    NextBeat,
+    /// At the first bar at or after the start of transport.
+    NextBar,
+}
+
+/// State of a [TransportGate] at one beat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateState {
+    /// Output is produced.
+    Open,
+    /// Position advances, but output is dropped.
+    Muted,
+    /// Position does not advance.
+    Paused,
+}
+
+/// Behavior of crate-managed outputs when transport stops and starts again, shared by
+/// [MidiClockOut](crate::MidiClockOut), [NoteScheduler](crate::NoteScheduler) and
+/// [StepClock](crate::StepClock).
+///
+/// The default pauses output while transport is stopped and resumes right at the start of
+/// transport, i.e. at beat 0 for a quantized start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TransportGate {
+    pub mode: GateMode,
+    pub resume: ResumeAlignment,
+}
+
+impl TransportGate {
+    pub fn pause() -> Self {
+        Self::default()
+    }
+
+    pub fn free_run() -> Self {
+        Self {
+            mode: GateMode::FreeRun,
+            ..Self::default()
+        }
+    }
+
+    pub fn mute() -> Self {
+        Self {
+            mode: GateMode::Mute,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_resume(mut self, resume: ResumeAlignment) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// The state of the gate at `time`, with bars of `quantum` beats.
+    pub fn state(&self, session_state: &SessionState, time: i64, quantum: f64) -> GateState {
+        let beat = session_state.beat_at_time(time, quantum);
+        self.state_at_beat(session_state, beat, quantum)
+    }
+
+    /// The state of the gate at `beat`, with bars of `quantum` beats.
+    pub fn state_at_beat(
+        &self,
+        session_state: &SessionState,
+        beat: f64,
+        quantum: f64,
+    ) -> GateState {
+        let closed = match self.mode {
+            GateMode::FreeRun => return GateState::Open,
+            GateMode::Pause => GateState::Paused,
+            GateMode::Mute => GateState::Muted,
+        };
+
+        let time = session_state.time_at_beat(beat, quantum);
+        match Transport::from_session_state(session_state, time, quantum) {
+            Transport::Playing { since } => {
+                let start = session_state.beat_at_time(since, quantum);
+                if beat >= self.resume_beat(start, quantum) {
+                    GateState::Open
+                } else {
+                    closed
+                }
+            }
+            _ => closed,
+        }
+    }
+
+    /// Beat at which output continues, for transport started at beat `start`.
+    pub fn resume_beat(&self, start: f64, quantum: f64) -> f64 {
+        // Tolerate rounding of the start beat, which is derived from a time
+        const EPSILON: f64 = 1e-6;
+        match self.resume {
+            ResumeAlignment::Immediately => start,
+            ResumeAlignment::NextBeat => (start - EPSILON).ceil(),
+            ResumeAlignment::NextBar if quantum > 0. => {
+                ((start - EPSILON) / quantum).ceil() * quantum
+            }
+            ResumeAlignment::NextBar => start,
+        }
+    }
+}
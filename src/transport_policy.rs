@@ -0,0 +1,79 @@
+use crate::{AblLink, SessionState};
+use std::sync::atomic::Ordering;
+
+/// Which start/stop changes of the session the crate-managed outputs follow, e.g.
+/// [MidiClockOut](crate::MidiClockOut) or [NoteScheduler](crate::NoteScheduler).
+///
+/// Link itself always shares start/stop between all peers with start/stop sync enabled.
+/// The policy only decides what the outputs of this instance make of it, e.g. an
+/// installation which must never be stopped by a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum TransportPolicy {
+    /// Follow every start and stop.
+    #[default]
+    AcceptAll,
+    /// Follow every start, but only stops committed by this instance.
+    IgnoreRemoteStops,
+    /// Only follow starts and stops committed by this instance.
+    ManualOnly,
+}
+
+impl TransportPolicy {
+    pub(crate) fn from_u8(value: u8) -> TransportPolicy {
+        match value {
+            1 => TransportPolicy::IgnoreRemoteStops,
+            2 => TransportPolicy::ManualOnly,
+            _ => TransportPolicy::AcceptAll,
+        }
+    }
+}
+
+impl AblLink {
+    /// Change which start/stop changes the crate-managed outputs follow.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    ///
+    /// Outputs keep their current state, the policy applies to the following changes.
+    pub fn set_transport_policy(&self, policy: TransportPolicy) {
+        debug!(?policy, "transport policy changed");
+        self.callbacks
+            .transport_policy
+            .store(policy as u8, Ordering::Release);
+    }
+
+    /// The policy set by [AblLink::set_transport_policy], [TransportPolicy::AcceptAll] by
+    /// default.
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    pub fn transport_policy(&self) -> TransportPolicy {
+        TransportPolicy::from_u8(self.callbacks.transport_policy.load(Ordering::Acquire))
+    }
+
+    /// Replace the start/stop state of a captured `session_state` with the state of the
+    /// outputs according to the [TransportPolicy].
+    ///
+    ///  Thread-safe: yes
+    ///
+    ///  Realtime-safe: yes
+    ///
+    /// The crate-managed outputs do this with every capture. Call it before passing an
+    /// audio Session State to e.g. [NoteScheduler::advance_to](crate::NoteScheduler::advance_to).
+    /// Never commit the result, it would force the state of the outputs onto the session.
+    pub fn apply_transport_policy(&self, session_state: &mut SessionState) {
+        if self.transport_policy() == TransportPolicy::AcceptAll {
+            return;
+        }
+
+        let is_playing = self.callbacks.outputs_playing.load(Ordering::Acquire);
+        let since = self.callbacks.outputs_since.load(Ordering::Acquire).max(0) as u64;
+        if session_state.is_playing() != is_playing || session_state.time_for_is_playing() != since
+        {
+            session_state.set_is_playing(is_playing, since);
+        }
+    }
+}
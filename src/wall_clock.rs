@@ -0,0 +1,77 @@
+use crate::{osc_clock::system_clock_offset, AblLink, SessionState};
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// Conversions between Link time and wall-clock time, to express logs and cues in human time.
+///
+/// The offset between the Link clock and the system clock is measured once by
+/// [WallClock::calibrate]. The system clock can be adjusted (e.g. by NTP) while the Link
+/// clock can not, so long-running apps should calibrate again from time to time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WallClock {
+    /// Wall-clock time minus Link host time, in microseconds
+    offset: i64,
+}
+
+impl WallClock {
+    /// Measure the offset between the Link clock of `link` and the system clock.
+    ///
+    /// Uses the fastest of a few paired clock reads, which are least disturbed by preemption.
+    pub fn calibrate(link: &AblLink) -> Self {
+        Self {
+            offset: system_clock_offset(link),
+        }
+    }
+
+    /// A wall clock with a known offset, e.g. from a previous calibration.
+    pub fn from_offset(offset: TimeDelta) -> Self {
+        Self {
+            offset: offset.num_microseconds().unwrap_or(i64::MAX),
+        }
+    }
+
+    /// Wall-clock time minus Link host time.
+    pub fn offset(&self) -> TimeDelta {
+        TimeDelta::microseconds(self.offset)
+    }
+
+    /// The wall-clock time of a Link host time in microseconds, e.g. [AblLink::clock_micros].
+    pub fn host_to_date_time(&self, host: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_micros(host.saturating_add(self.offset))
+            .expect("Link time within the range of DateTime")
+    }
+
+    /// The Link host time in microseconds of a wall-clock time.
+    pub fn date_time_to_host(&self, date_time: DateTime<Utc>) -> i64 {
+        date_time.timestamp_micros().saturating_sub(self.offset)
+    }
+
+    /// The wall-clock time of a Link ghost time, see [AblLink::ghost_to_host].
+    pub fn ghost_to_date_time(&self, link: &AblLink, ghost: i64) -> DateTime<Utc> {
+        self.host_to_date_time(link.ghost_to_host(ghost))
+    }
+
+    /// The Link ghost time of a wall-clock time, see [AblLink::host_to_ghost].
+    pub fn date_time_to_ghost(&self, link: &AblLink, date_time: DateTime<Utc>) -> i64 {
+        link.host_to_ghost(self.date_time_to_host(date_time))
+    }
+
+    /// The wall-clock time at which `beat` occurs on the timeline of `session_state`.
+    pub fn date_time_at_beat(
+        &self,
+        session_state: &SessionState,
+        beat: f64,
+        quantum: f64,
+    ) -> DateTime<Utc> {
+        self.host_to_date_time(session_state.time_at_beat(beat, quantum))
+    }
+
+    /// The beat at a wall-clock time on the timeline of `session_state`.
+    pub fn beat_at_date_time(
+        &self,
+        session_state: &SessionState,
+        date_time: DateTime<Utc>,
+        quantum: f64,
+    ) -> f64 {
+        session_state.beat_at_time(self.date_time_to_host(date_time), quantum)
+    }
+}
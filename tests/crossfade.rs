@@ -0,0 +1,30 @@
+// Gains of Crossfade along the beat timeline.
+
+use rusty_link::{Crossfade, FadeCurve};
+
+#[test]
+fn linear_crossfade_gains_add_up_to_one() {
+    let fade = Crossfade::over_beats(4.)
+        .starting_at(8.)
+        .with_curve(FadeCurve::Linear);
+
+    assert_eq!(fade.gains_at_beat(0.), (1., 0.));
+    assert_eq!(fade.gains_at_beat(9.), (0.75, 0.25));
+    assert_eq!(fade.gains_at_beat(12.), (0., 1.));
+    assert_eq!(fade.gains_at_beat(100.), (0., 1.));
+    assert!(!fade.is_finished(11.9));
+    assert!(fade.is_finished(12.));
+}
+
+#[test]
+fn equal_power_crossfade_keeps_power_constant() {
+    let fade = Crossfade::over_beats(16.).starting_at(4.);
+
+    assert_eq!(fade.gains_at_beat(4.), (1., 0.));
+    for beat in 0..32 {
+        let (out, into) = fade.gains_at_beat(beat as f64);
+        assert!((out * out + into * into - 1.).abs() < 1e-9);
+    }
+    let (out, into) = fade.gains_at_beat(12.);
+    assert!((out - into).abs() < 1e-9);
+}
@@ -0,0 +1,31 @@
+// Rounding and hysteresis of DisplayTempo.
+
+use rusty_link::DisplayTempo;
+
+#[test]
+fn jitter_around_the_shown_tempo_is_ignored() {
+    let mut tempo = DisplayTempo::new(2);
+    assert_eq!(tempo.to_string(), "-");
+
+    assert_eq!(tempo.update(119.998), 120.);
+    for bpm in [120.003, 119.991, 120.009, 119.995] {
+        assert_eq!(tempo.update(bpm), 120.);
+    }
+    assert_eq!(tempo.to_string(), "120.00");
+}
+
+#[test]
+fn real_changes_are_shown() {
+    let mut tempo = DisplayTempo::new(1);
+    tempo.update(120.);
+    assert_eq!(tempo.update(121.26), 121.3);
+    assert_eq!(tempo.to_string(), "121.3");
+}
+
+#[test]
+fn hysteresis_can_be_disabled() {
+    let mut tempo = DisplayTempo::new(0).with_hysteresis(0.);
+    tempo.update(120.);
+    assert_eq!(tempo.update(120.4), 120.);
+    assert_eq!(tempo.update(120.6), 121.);
+}
@@ -0,0 +1,76 @@
+// Ordering, coalescing and capacity of the event queue of AblLink::poll_events.
+// Uses the events which are reported locally, so no peers are needed.
+
+use rusty_link::{AblLink, LinkEvent, EVENT_QUEUE_CAPACITY};
+
+fn poll(link: &AblLink) -> Vec<LinkEvent> {
+    let mut events = Vec::new();
+    link.poll_events(|event| events.push(event));
+    events
+}
+
+#[test]
+fn coalesced_events_are_polled_in_order_of_their_latest_occurrence() {
+    let link = AblLink::new_offline(120.);
+    poll(&link);
+
+    link.set_quantum(3.).unwrap();
+    link.enable_start_stop_sync(true);
+    link.set_quantum(5.).unwrap();
+
+    assert_eq!(
+        poll(&link),
+        [
+            LinkEvent::StartStopSyncChanged { enabled: true },
+            LinkEvent::QuantumChanged { quantum: 5. },
+        ]
+    );
+    assert!(poll(&link).is_empty());
+}
+
+#[test]
+fn events_are_kept_without_coalescing() {
+    let link = AblLink::new_offline(120.);
+    link.set_event_coalescing(false);
+    poll(&link);
+
+    link.set_quantum(3.).unwrap();
+    link.enable_start_stop_sync(true);
+    link.set_quantum(5.).unwrap();
+
+    assert_eq!(
+        poll(&link),
+        [
+            LinkEvent::QuantumChanged { quantum: 3. },
+            LinkEvent::StartStopSyncChanged { enabled: true },
+            LinkEvent::QuantumChanged { quantum: 5. },
+        ]
+    );
+}
+
+#[test]
+fn oldest_events_are_dropped_when_the_queue_is_full() {
+    let link = AblLink::new_offline(120.);
+    link.set_event_coalescing(false);
+    poll(&link);
+
+    let extra = 6;
+    for quantum in 1..=EVENT_QUEUE_CAPACITY + extra {
+        link.set_quantum(quantum as f64).unwrap();
+    }
+
+    let events = poll(&link);
+    assert_eq!(events.len(), EVENT_QUEUE_CAPACITY);
+    assert_eq!(
+        events[0],
+        LinkEvent::QuantumChanged {
+            quantum: (extra + 1) as f64
+        }
+    );
+    assert_eq!(
+        events[EVENT_QUEUE_CAPACITY - 1],
+        LinkEvent::QuantumChanged {
+            quantum: (EVENT_QUEUE_CAPACITY + extra) as f64
+        }
+    );
+}
@@ -0,0 +1,145 @@
+// Golden compatibility test, which runs Ableton's C++ LinkHutSilent next to this
+// crate and checks that both agree on tempo and phase.
+// Run with: cargo test --features golden-tests --test golden_link_hut
+
+#![cfg(feature = "golden-tests")]
+
+use rusty_link::{AblLink, SessionState};
+use std::{
+    io::{Read, Write},
+    process::{Child, Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const QUANTUM: f64 = 4.;
+/// Allowed phase difference in beats.
+const PHASE_TOLERANCE: f64 = 0.1;
+
+/// Values of one status line printed by LinkHut:
+/// `enabled | num peers | quantum | start stop sync | tempo | beats | metro`
+struct Status {
+    tempo: f64,
+    beats: f64,
+}
+
+struct LinkHut {
+    child: Child,
+    lines: mpsc::Receiver<String>,
+}
+
+impl LinkHut {
+    fn spawn() -> LinkHut {
+        let mut child = Command::new(env!("LINK_HUT_SILENT"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Could not start LinkHutSilent.");
+
+        // LinkHut rewrites its status line with '\r', so split on both line endings
+        let mut stdout = child.stdout.take().unwrap();
+        let (tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            let mut line = Vec::new();
+            let mut byte = [0u8];
+            while let Ok(1) = stdout.read(&mut byte) {
+                if byte[0] == b'\r' || byte[0] == b'\n' {
+                    let _ = tx.send(String::from_utf8_lossy(&line).into_owned());
+                    line.clear();
+                } else {
+                    line.push(byte[0]);
+                }
+            }
+        });
+
+        LinkHut { child, lines }
+    }
+
+    fn send_key(&mut self, key: u8) {
+        self.try_send_key(key)
+            .expect("Could not write to LinkHutSilent.");
+    }
+
+    fn try_send_key(&mut self, key: u8) -> std::io::Result<()> {
+        let stdin = self.child.stdin.as_mut().unwrap();
+        stdin.write_all(&[key])?;
+        stdin.flush()
+    }
+
+    /// Wait for a status line matching `predicate`.
+    fn wait_for_status<P: Fn(&Status) -> bool>(&self, predicate: P) -> Option<Status> {
+        let deadline = Instant::now() + TIMEOUT;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let line = self.lines.recv_timeout(remaining).ok()?;
+            if let Some(status) = parse_status(&line) {
+                if predicate(&status) {
+                    return Some(status);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Drop for LinkHut {
+    fn drop(&mut self) {
+        // Best effort, LinkHut may have exited already
+        let _ = self.try_send_key(b'q');
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn parse_status(line: &str) -> Option<Status> {
+    let columns: Vec<&str> = line.split('|').map(str::trim).collect();
+    if columns.len() < 6 {
+        return None;
+    }
+    Some(Status {
+        tempo: columns[4].parse().ok()?,
+        beats: columns[5].parse().ok()?,
+    })
+}
+
+fn wait_for_peers(link: &AblLink) {
+    let deadline = Instant::now() + TIMEOUT;
+    while link.num_peers() == 0 {
+        assert!(Instant::now() < deadline, "LinkHutSilent never joined.");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn link_hut_follows_tempo_and_phase() {
+    let link = AblLink::new(120.);
+    link.enable(true);
+
+    let mut link_hut = LinkHut::spawn();
+    link_hut.send_key(b'a'); // enable Link in LinkHut
+    wait_for_peers(&link);
+
+    let mut session_state = SessionState::new();
+    link.capture_app_session_state(&mut session_state);
+    session_state.set_tempo(133., link.clock_micros());
+    link.commit_app_session_state(&session_state);
+
+    let status = link_hut
+        .wait_for_status(|status| (status.tempo - 133.).abs() < 0.01)
+        .expect("LinkHutSilent did not follow the tempo change.");
+
+    // Only the phase is shared between peers, the beat magnitude is per client
+    link.capture_app_session_state(&mut session_state);
+    let phase = session_state.phase_at_time(link.clock_micros(), QUANTUM);
+    let link_hut_phase = status.beats.rem_euclid(QUANTUM);
+    let distance = (phase - link_hut_phase).rem_euclid(QUANTUM);
+    assert!(
+        distance.min(QUANTUM - distance) < PHASE_TOLERANCE,
+        "Phases differ: rusty_link {:.2}, LinkHut {:.2}",
+        phase,
+        link_hut_phase
+    );
+
+    link.enable(false);
+}
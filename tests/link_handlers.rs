@@ -0,0 +1,60 @@
+// Registration of handler methods with the `link_handlers` attribute macro.
+// Run with: cargo test --features derive --test link_handlers
+
+#![cfg(feature = "derive")]
+
+use rusty_link::{link_handlers, AblLink, SessionState};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[derive(Default)]
+struct App {
+    tempo: f64,
+    num_peers: u64,
+}
+
+#[link_handlers]
+impl App {
+    #[on_tempo]
+    fn tempo_changed(&mut self, bpm: f64) {
+        self.tempo = bpm;
+    }
+
+    #[on_peers]
+    fn peers_changed(&mut self, num_peers: u64) {
+        self.num_peers = num_peers;
+    }
+
+    fn describe(&self) -> String {
+        format!("{} bpm, {} peers", self.tempo, self.num_peers)
+    }
+}
+
+#[test]
+fn marked_methods_are_registered_as_callbacks() {
+    let link = AblLink::new_offline(120.);
+    let app = Arc::new(Mutex::new(App::default()));
+
+    link.set_handlers(&app);
+
+    let diagnostics = link.diagnostics();
+    assert!(diagnostics.tempo_callback);
+    assert!(diagnostics.num_peers_callback);
+    assert!(!diagnostics.start_stop_callback);
+    assert_eq!(app.lock().unwrap().describe(), "0 bpm, 0 peers");
+
+    let mut session_state = SessionState::new();
+    link.capture_app_session_state(&mut session_state);
+    session_state.set_tempo(130., link.clock_micros());
+    link.commit_app_session_state(&session_state);
+
+    // Link invokes the callback on its own thread
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while app.lock().unwrap().tempo != 130. && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(app.lock().unwrap().describe(), "130 bpm, 0 peers");
+}
@@ -0,0 +1,10 @@
+// Compile errors of the `link_handlers` attribute macro for invalid handler methods.
+// Run with: cargo test --features derive --test link_handlers_ui
+// Update the expected errors with: TRYBUILD=overwrite cargo test ...
+
+#![cfg(feature = "derive")]
+
+#[test]
+fn invalid_handlers_do_not_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/link_handlers/*.rs");
+}
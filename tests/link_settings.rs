@@ -0,0 +1,38 @@
+// Reading and validation of LinkSettings.
+
+use rusty_link::{Error, LinkSettings};
+
+#[test]
+fn settings_round_trip() {
+    let settings = LinkSettings {
+        tempo: 97.5,
+        quantum: 3.,
+        start_stop_sync: true,
+        enabled: true,
+    };
+
+    let mut text = Vec::new();
+    settings.write_to(&mut text).unwrap();
+    assert_eq!(LinkSettings::read_from(&text[..]).unwrap(), settings);
+}
+
+#[test]
+fn out_of_range_values_are_rejected() {
+    for text in [
+        "tempo = 0",
+        "tempo = 1000",
+        "tempo = nan",
+        "quantum = 0",
+        "quantum = -4",
+        "quantum = inf",
+    ] {
+        assert!(
+            matches!(
+                LinkSettings::read_from(text.as_bytes()),
+                Err(Error::InvalidSettings(_))
+            ),
+            "{}",
+            text
+        );
+    }
+}
@@ -0,0 +1,195 @@
+// Loom model of registering, replacing, deleting and invoking callbacks concurrently.
+// Run with: RUSTFLAGS="--cfg rusty_link_loom" cargo test --release --test loom_callbacks
+
+#![cfg(rusty_link_loom)]
+
+use loom::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+use rusty_link::CallbackSlot;
+
+fn counting_callback(counter: &Arc<AtomicUsize>) -> Box<dyn FnMut(u64) + Send> {
+    let counter = Arc::clone(counter);
+    Box::new(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    })
+}
+
+#[test]
+fn invoke_while_replacing() {
+    loom::model(|| {
+        let slot = Arc::new(CallbackSlot::<u64>::new());
+        let first = Arc::new(AtomicUsize::new(0));
+        let second = Arc::new(AtomicUsize::new(0));
+
+        slot.set(counting_callback(&first));
+
+        // Link-managed thread invoking the callback
+        let link_thread = {
+            let slot = Arc::clone(&slot);
+            thread::spawn(move || slot.invoke(1))
+        };
+
+        slot.set(counting_callback(&second));
+        link_thread.join().unwrap();
+
+        // Exactly one of the two closures observed the invocation
+        let total = first.load(Ordering::SeqCst) + second.load(Ordering::SeqCst);
+        assert_eq!(total, 1);
+
+        // The replaced closure has been dropped, only the slot holds the second one
+        assert_eq!(Arc::strong_count(&first), 1);
+        assert_eq!(Arc::strong_count(&second), 2);
+    });
+}
+
+#[test]
+fn invoke_while_deleting() {
+    loom::model(|| {
+        let slot = Arc::new(CallbackSlot::<u64>::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        slot.set(counting_callback(&counter));
+
+        let link_thread = {
+            let slot = Arc::clone(&slot);
+            thread::spawn(move || slot.invoke(1))
+        };
+
+        slot.clear();
+        link_thread.join().unwrap();
+
+        assert!(counter.load(Ordering::SeqCst) <= 1);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    });
+}
+
+#[test]
+fn drop_after_concurrent_registration() {
+    loom::model(|| {
+        let slot = Arc::new(CallbackSlot::<u64>::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let register_thread = {
+            let slot = Arc::clone(&slot);
+            let callback = counting_callback(&counter);
+            thread::spawn(move || slot.set(callback))
+        };
+        let link_thread = {
+            let slot = Arc::clone(&slot);
+            thread::spawn(move || slot.invoke(1))
+        };
+
+        register_thread.join().unwrap();
+        link_thread.join().unwrap();
+
+        // Dropping the last handle to the slot releases the closure
+        drop(slot);
+        assert!(counter.load(Ordering::SeqCst) <= 1);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    });
+}
+
+#[test]
+fn delete_from_inside_while_replacing() {
+    loom::model(|| {
+        let slot = Arc::new(CallbackSlot::<u64>::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let inner = Arc::clone(&slot);
+        slot.set(Box::new(move |_| inner.clear()));
+
+        let link_thread = {
+            let slot = Arc::clone(&slot);
+            thread::spawn(move || slot.invoke(1))
+        };
+
+        slot.set(counting_callback(&counter));
+        link_thread.join().unwrap();
+
+        // Either the self-deleting closure ran first and the replacement is stored, or the
+        // replacement ran, but nothing deadlocked
+        slot.clear();
+        assert!(counter.load(Ordering::SeqCst) <= 1);
+        assert_eq!(Arc::strong_count(&counter), 1);
+        assert_eq!(Arc::strong_count(&slot), 1);
+    });
+}
+
+#[test]
+fn callback_deletes_itself() {
+    loom::model(|| {
+        let slot = Arc::new(CallbackSlot::<u64>::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let inner = Arc::clone(&slot);
+        let calls = Arc::clone(&counter);
+        slot.set(Box::new(move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            inner.clear();
+            // Still running, the deletion is applied when the closure returns
+            assert!(!inner.is_set());
+        }));
+
+        slot.invoke(1);
+        slot.invoke(2);
+
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(!slot.is_set());
+        // The closure and its handle to the slot have been dropped
+        assert_eq!(Arc::strong_count(&slot), 1);
+    });
+}
+
+#[test]
+fn callback_replaces_itself() {
+    loom::model(|| {
+        let slot = Arc::new(CallbackSlot::<u64>::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let inner = Arc::clone(&slot);
+        let replacement = counting_callback(&counter);
+        let mut replacement = Some(replacement);
+        slot.set(Box::new(move |_| {
+            if let Some(replacement) = replacement.take() {
+                inner.set(replacement);
+            }
+        }));
+
+        slot.invoke(1);
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+        slot.invoke(2);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        slot.clear();
+        assert_eq!(Arc::strong_count(&slot), 1);
+    });
+}
+
+#[test]
+fn queued_values_wait_while_the_slot_is_busy() {
+    loom::model(|| {
+        let slot = Arc::new(CallbackSlot::<u64>::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        slot.set(counting_callback(&counter));
+
+        let link_thread = {
+            let slot = Arc::clone(&slot);
+            thread::spawn(move || slot.invoke(1))
+        };
+
+        // Thread running queued callbacks, with a single queued value
+        let mut queued = Some(2);
+        let consumed = slot.try_invoke_each(|| queued.take());
+        link_thread.join().unwrap();
+
+        // A busy slot leaves the value queued instead of waiting
+        assert_eq!(consumed + queued.iter().count(), 1);
+        assert_eq!(counter.load(Ordering::SeqCst), 1 + consumed);
+    });
+}
@@ -0,0 +1,24 @@
+use rusty_link::{MscCommand, MscEncoder, MSC_LIGHTING};
+
+#[test]
+fn go_with_cue_number() {
+    let encoder = MscEncoder::new(1, MSC_LIGHTING);
+    assert_eq!(
+        encoder.encode(MscCommand::Go, "12.5"),
+        [0xF0, 0x7F, 0x01, 0x02, 0x01, 0x01, b'1', b'2', b'.', b'5', 0xF7]
+    );
+}
+
+#[test]
+fn stop_current_cue_to_all() {
+    assert_eq!(
+        MscEncoder::default().encode(MscCommand::Stop, ""),
+        [0xF0, 0x7F, 0x7F, 0x02, 0x7F, 0x02, 0xF7]
+    );
+}
+
+#[test]
+fn invalid_characters_are_dropped() {
+    let message = MscEncoder::new(0xFF, 0x81).encode(MscCommand::Resume, "cue 3");
+    assert_eq!(message, [0xF0, 0x7F, 0x7F, 0x02, 0x01, 0x03, b'3', 0xF7]);
+}
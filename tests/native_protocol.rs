@@ -0,0 +1,79 @@
+// Wire format of the native Link discovery protocol.
+// Run with: cargo test --features native --test native_protocol
+
+#![cfg(feature = "native")]
+
+use rusty_link::{
+    native::{
+        protocol::{decode, encode, encode_in_group, MessageType, PROTOCOL_HEADER},
+        NodeId, PeerState, StartStopState, Timeline,
+    },
+    SessionKey,
+};
+
+fn peer_state() -> PeerState {
+    let node_id = NodeId(*b"abcd1234");
+    PeerState {
+        node_id,
+        session_id: node_id,
+        timeline: Timeline::new(123.4, 1.5, 1_000_000),
+        start_stop_state: Some(StartStopState {
+            is_playing: true,
+            beats: 4_000_000,
+            timestamp: 2_000_000,
+        }),
+        measurement_endpoint: Some("192.168.0.2:54321".parse().unwrap()),
+    }
+}
+
+#[test]
+fn alive_round_trip() {
+    let state = peer_state();
+    let message = decode(&encode(MessageType::Alive, 5, &state)).unwrap();
+
+    assert_eq!(message.header.message_type, MessageType::Alive);
+    assert_eq!(message.header.ttl, 5);
+    assert_eq!(message.header.ident, state.node_id);
+    assert_eq!(message.state, Some(state));
+}
+
+#[test]
+fn private_session_group() {
+    let key = SessionKey::new("band rehearsal");
+    assert_ne!(key.group_id(), 0);
+    assert_ne!(key, SessionKey::new("other band"));
+
+    let message = decode(&encode_in_group(
+        MessageType::Alive,
+        5,
+        key.group_id(),
+        &peer_state(),
+    ));
+    assert_eq!(message.unwrap().header.group_id, key.group_id());
+}
+
+#[test]
+fn bye_bye_has_no_state() {
+    let message = decode(&encode(MessageType::ByeBye, 5, &peer_state())).unwrap();
+
+    assert_eq!(message.header.message_type, MessageType::ByeBye);
+    assert_eq!(message.state, None);
+}
+
+#[test]
+fn rejects_foreign_and_truncated_messages() {
+    let message = encode(MessageType::Alive, 5, &peer_state());
+
+    assert!(decode(&message[..message.len() - 1]).is_err());
+    assert!(decode(&message[..PROTOCOL_HEADER.len()]).is_err());
+    assert!(decode(b"_link_v\x01\x01").is_err());
+}
+
+#[test]
+fn timeline_maps_beats_and_time() {
+    let timeline = peer_state().timeline;
+
+    assert_eq!(timeline.to_beats(1_000_000), 1.5);
+    assert!((timeline.to_beats(timeline.from_beats(7.25)) - 7.25).abs() < 1e-6);
+    assert!((timeline.tempo() - 123.4).abs() < 1e-3);
+}
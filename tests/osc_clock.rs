@@ -0,0 +1,32 @@
+// Conversion of Link time to OSC time tags, without a Link session.
+
+use rusty_link::OscClock;
+use std::time::Duration;
+
+#[test]
+fn time_tags_use_the_ntp_epoch() {
+    let clock = OscClock::from_offset(0);
+    assert_eq!(clock.time_tag_at(0), 2_208_988_800 << 32);
+    assert_eq!(
+        clock.time_tag_at(500_000),
+        (2_208_988_800 << 32) | (1 << 31)
+    );
+}
+
+#[test]
+fn time_tags_convert_back_to_link_time() {
+    let clock = OscClock::from_offset(1_700_000_000_000_000).with_latency(Duration::from_millis(5));
+    for host in [0, 1, 123_456_789, 86_400_000_001] {
+        assert_eq!(clock.host_at_time_tag(clock.time_tag_at(host)), host);
+    }
+}
+
+#[test]
+fn latency_makes_time_tags_earlier() {
+    let clock = OscClock::from_offset(0);
+    let compensated = clock.with_latency(Duration::from_secs(1));
+    assert_eq!(
+        clock.time_tag_at(2_000_000) - compensated.time_tag_at(2_000_000),
+        1 << 32
+    );
+}
@@ -0,0 +1,31 @@
+// PhaseProvider reading its time from a virtual clock instead of the Link clock.
+
+use rusty_link::{AblLink, PhaseProvider};
+use std::{
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+#[test]
+fn beat_follows_the_virtual_clock() {
+    let link = Arc::new(AblLink::new_offline(120.));
+    let now = Arc::new(AtomicI64::new(link.clock_micros()));
+    let clock = Arc::clone(&now);
+    let mut provider = PhaseProvider::new(Arc::clone(&link))
+        .with_capture_interval(Duration::from_secs(3600))
+        .with_clock(Arc::new(move || clock.load(Ordering::Acquire)));
+
+    let start = provider.beat(4.);
+
+    // Real time passing does not move the beat
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(provider.beat(4.), start);
+
+    // One second at 120 bpm is two beats
+    now.fetch_add(1_000_000, Ordering::AcqRel);
+    assert!((provider.beat(4.) - start - 2.).abs() < 1e-9);
+}
@@ -0,0 +1,26 @@
+use rusty_link::{Quantum, TimeSignature};
+
+#[test]
+fn quantum_of_time_signatures() {
+    let quantum = |n, d| Quantum::from_time_signature(TimeSignature::new(n, d)).unwrap();
+    assert_eq!(quantum(4, 4), Quantum::BAR_4_4);
+    assert_eq!(quantum(3, 4), Quantum::BAR_3_4);
+    assert_eq!(quantum(6, 8), Quantum::BAR_6_8);
+    assert_eq!(quantum(7, 8), Quantum::BAR_7_8);
+    assert_eq!(quantum(12, 8), Quantum::BAR_12_8);
+    assert_eq!(quantum(2, 2).beats(), 4.);
+}
+
+#[test]
+fn invalid_quanta() {
+    assert!(Quantum::from_time_signature(TimeSignature::new(0, 4)).is_err());
+    assert!(Quantum::from_time_signature(TimeSignature::new(4, 0)).is_err());
+    assert!(Quantum::new(f64::NAN).is_err());
+    assert!(Quantum::new(-1.).is_err());
+}
+
+#[test]
+fn phrases_of_bars() {
+    let quantum = Quantum::bars(TimeSignature::new(3, 4), 4).unwrap();
+    assert_eq!(f64::from(quantum), 12.);
+}
@@ -0,0 +1,27 @@
+// Values of Ramp along the beat timeline.
+
+use rusty_link::Ramp;
+
+#[test]
+fn ramp_interpolates_between_start_and_end() {
+    let ramp = Ramp::over_beats(0., 1., 4.).starting_at(8.);
+
+    assert_eq!(ramp.value_at_beat(0.), 0.);
+    assert_eq!(ramp.value_at_beat(8.), 0.);
+    assert_eq!(ramp.value_at_beat(9.), 0.25);
+    assert_eq!(ramp.value_at_beat(11.), 0.75);
+    assert_eq!(ramp.value_at_beat(12.), 1.);
+    assert_eq!(ramp.value_at_beat(100.), 1.);
+    assert!(!ramp.is_finished(11.9));
+    assert!(ramp.is_finished(12.));
+}
+
+#[test]
+fn ramps_can_fall_and_jump() {
+    let fade_out = Ramp::over_beats(1., 0.5, 2.);
+    assert_eq!(fade_out.value_at_beat(1.), 0.75);
+
+    let jump = Ramp::over_beats(0., 1., 0.).starting_at(4.);
+    assert_eq!(jump.value_at_beat(3.9), 0.);
+    assert_eq!(jump.value_at_beat(4.), 1.);
+}
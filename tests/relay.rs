@@ -0,0 +1,154 @@
+// Wire format of the relay protocol and forwarding by a loopback RelayServer.
+// Run with: cargo test --features relay --test relay
+
+#![cfg(feature = "relay")]
+
+use rusty_link::{
+    relay::{RelayMessage, RelayServer, RelayState, MAX_LINE},
+    Error, ThreadConfig,
+};
+use std::{
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+fn state(tempo: f64) -> RelayState {
+    RelayState {
+        tempo,
+        beat: 17.25,
+        quantum: 4.,
+        is_playing: true,
+        latency: 1500,
+        session: 42,
+    }
+}
+
+fn line_of(message: RelayMessage) -> String {
+    let mut line = Vec::new();
+    message.write_to(&mut line).unwrap();
+    String::from_utf8(line).unwrap()
+}
+
+#[test]
+fn messages_round_trip() {
+    for message in [
+        RelayMessage::State(state(121.5)),
+        RelayMessage::Ping(7),
+        RelayMessage::Pong(u64::MAX),
+    ] {
+        let line = line_of(message);
+        assert!(line.ends_with('\n'));
+        assert_eq!(RelayMessage::parse(&line).unwrap(), message);
+    }
+}
+
+#[test]
+fn states_without_session_belong_to_no_session() {
+    let RelayMessage::State(state) = RelayMessage::parse("state 120 1 4 0 0").unwrap() else {
+        panic!("expected a state");
+    };
+    assert_eq!(state.session, 0);
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+    for line in [
+        "",
+        "hello",
+        "ping",
+        "pong x",
+        "state 120 1 4 0",
+        "state NaN 1 4 0 0",
+        "state 5000 1 4 0 0",
+        "state 120 inf 4 0 0",
+        "state 120 1 0 0 0",
+        "state 120 1 -4 0 0",
+    ] {
+        assert!(
+            matches!(RelayMessage::parse(line), Err(Error::InvalidMessage(_))),
+            "{}",
+            line
+        );
+    }
+}
+
+#[test]
+fn long_lines_are_rejected() {
+    let padding = " ".repeat(MAX_LINE);
+    assert!(RelayMessage::parse(&format!("ping 1{}", padding)).is_err());
+}
+
+struct TestClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl TestClient {
+    fn connect(server: &RelayServer) -> TestClient {
+        let stream = TcpStream::connect(server.local_addr()).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_millis(500)))
+            .unwrap();
+        TestClient {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            writer: stream,
+        }
+    }
+
+    fn send(&mut self, message: RelayMessage) {
+        message.write_to(&mut self.writer).unwrap();
+    }
+
+    /// The next message, None after the read timeout.
+    fn receive(&mut self) -> Option<RelayMessage> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => Some(RelayMessage::parse(&line).unwrap()),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => None,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Wait until the server answers a ping, so the client is known to be registered.
+    fn sync(&mut self, id: u64) {
+        self.send(RelayMessage::Ping(id));
+        assert_eq!(self.receive(), Some(RelayMessage::Pong(id)));
+    }
+}
+
+#[test]
+fn server_forwards_states_to_other_clients() {
+    let server = RelayServer::start("127.0.0.1:0", ThreadConfig::default()).unwrap();
+    let mut a = TestClient::connect(&server);
+    let mut b = TestClient::connect(&server);
+    a.sync(1);
+    b.sync(2);
+
+    a.send(RelayMessage::State(state(99.)));
+    assert_eq!(b.receive(), Some(RelayMessage::State(state(99.))));
+    // Not echoed to the sender
+    assert_eq!(a.receive(), None);
+
+    // Malformed lines are not forwarded
+    a.writer.write_all(b"state NaN 1 4 0 0\n").unwrap();
+    a.sync(3);
+    assert_eq!(b.receive(), None);
+
+    server.stop();
+}
+
+#[test]
+fn server_closes_connections_with_too_long_lines() {
+    let server = RelayServer::start("127.0.0.1:0", ThreadConfig::default()).unwrap();
+    let mut client = TestClient::connect(&server);
+    client.sync(1);
+
+    client.writer.write_all(&vec![b'x'; MAX_LINE + 1]).unwrap();
+    let mut rest = Vec::new();
+    let read = client.reader.read_to_end(&mut rest);
+    assert!(matches!(read, Ok(0)) || read.is_err_and(|e| e.kind() == ErrorKind::ConnectionReset));
+
+    server.stop();
+}
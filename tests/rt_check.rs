@@ -0,0 +1,83 @@
+// Checks that functions documented as realtime-safe do not allocate.
+// Run with: cargo test --features rt-check --test rt_check
+
+#![cfg(feature = "rt-check")]
+
+use rusty_link::{
+    rt_check::{assert_no_alloc, RtCheckAllocator},
+    AblLink, SessionState,
+};
+
+#[global_allocator]
+static ALLOCATOR: RtCheckAllocator = RtCheckAllocator::new();
+
+#[test]
+fn audio_session_state_round_trip_does_not_allocate() {
+    let link = AblLink::new_offline(120.);
+    let mut session_state = SessionState::new();
+    let mut copy = SessionState::new();
+
+    assert_no_alloc(|| {
+        for _ in 0..1000 {
+            link.capture_audio_session_state(&mut session_state);
+            let time = link.clock_micros();
+            session_state.beat_at_time(time, 4.);
+            session_state.phase_at_time(time, 4.);
+            link.commit_audio_session_state(&session_state);
+            link.try_commit_audio_session_state(&session_state);
+            copy.copy_from(&session_state);
+        }
+    });
+}
+
+#[test]
+fn realtime_safe_getters_do_not_allocate() {
+    let link = AblLink::new_offline(120.);
+    let mut session_state = SessionState::new();
+
+    assert_no_alloc(|| {
+        link.is_enabled();
+        link.num_peers();
+        link.clock_micros();
+        link.capture_app_session_state(&mut session_state);
+        link.is_start_stop_sync_enabled();
+        link.dropped_audio_commits();
+        link.uptime();
+    });
+}
+
+#[test]
+#[should_panic(expected = "allocation(s) in a realtime-safe section")]
+fn allocations_are_detected() {
+    assert_no_alloc(|| vec![0u8; 64]);
+}
+
+#[test]
+fn callback_invocation_does_not_allocate() {
+    use std::{
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    let link = AblLink::new_offline(120.);
+    let tempo = Arc::new(AtomicU64::new(0));
+    let tempo_clone = Arc::clone(&tempo);
+    link.set_tempo_callback(move |bpm| tempo_clone.store(bpm.to_bits(), Ordering::Release));
+
+    let mut session_state = SessionState::new();
+    link.capture_app_session_state(&mut session_state);
+    session_state.set_tempo(133., link.clock_micros());
+    link.commit_app_session_state(&session_state);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while f64::from_bits(tempo.load(Ordering::Acquire)) != 133. {
+        assert!(Instant::now() < deadline, "Tempo callback not invoked.");
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(rusty_link::rt_check::callback_allocations(), 0);
+}
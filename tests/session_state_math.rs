@@ -0,0 +1,81 @@
+// Property based tests of the beat/phase/time math of SessionState.
+
+use proptest::prelude::*;
+use rusty_link::{render_phase_buffer, AblLink, SessionState};
+
+/// Allowed error in beats. Time values are rounded to whole microseconds.
+const BEAT_TOLERANCE: f64 = 1e-4;
+
+fn session_state_with_tempo(bpm: f64) -> (AblLink, SessionState) {
+    let link = AblLink::new_offline(120.);
+    let mut session_state = SessionState::new();
+    link.capture_app_session_state(&mut session_state);
+    session_state.set_tempo(bpm, link.clock_micros());
+    (link, session_state)
+}
+
+proptest! {
+    #[test]
+    fn beat_at_time_inverts_time_at_beat(
+        bpm in 20.0..999.0f64,
+        quantum in 1.0..16.0f64,
+        beat in -10_000.0..10_000.0f64,
+    ) {
+        let (_link, state) = session_state_with_tempo(bpm);
+        let time = state.time_at_beat(beat, quantum);
+        prop_assert!((state.beat_at_time(time, quantum) - beat).abs() < BEAT_TOLERANCE);
+    }
+
+    #[test]
+    fn phase_is_within_quantum(
+        bpm in 20.0..999.0f64,
+        quantum in 1.0..16.0f64,
+        offset in -1_000_000_000i64..1_000_000_000i64,
+    ) {
+        let (link, state) = session_state_with_tempo(bpm);
+        let phase = state.phase_at_time(link.clock_micros() + offset, quantum);
+        prop_assert!((0.0..quantum).contains(&phase));
+    }
+
+    #[test]
+    fn phase_equals_beat_modulo_quantum(
+        bpm in 20.0..999.0f64,
+        quantum in 1.0..16.0f64,
+        beat in 0.0..10_000.0f64,
+    ) {
+        let (_link, state) = session_state_with_tempo(bpm);
+        let time = state.time_at_beat(beat, quantum);
+        let expected = state.beat_at_time(time, quantum) % quantum;
+        let phase = state.phase_at_time(time, quantum);
+
+        // Values right at the quantum boundary may wrap to either side
+        let diff = (phase - expected).abs();
+        prop_assert!(diff < BEAT_TOLERANCE || (diff - quantum).abs() < BEAT_TOLERANCE);
+    }
+
+    #[test]
+    fn tempo_is_stored(bpm in 20.0..999.0f64) {
+        let (_link, state) = session_state_with_tempo(bpm);
+        prop_assert!((state.tempo() - bpm).abs() < 1e-6);
+    }
+
+    #[test]
+    fn phase_buffer_matches_phase_at_time(
+        bpm in 20.0..999.0f64,
+        quantum in 1.0..16.0f64,
+        offset in -1_000_000_000i64..1_000_000_000i64,
+    ) {
+        let (link, state) = session_state_with_tempo(bpm);
+        let begin = link.clock_micros() + offset;
+        let mut buffer = [0f32; 256];
+        render_phase_buffer(&state, &mut buffer, 48_000., begin, quantum);
+
+        for (index, phase) in buffer.iter().enumerate() {
+            let time = begin + (index as f64 * 1e6 / 48_000.).round() as i64;
+            let expected = state.phase_at_time(time, quantum) / quantum;
+            // Time values are rounded to whole microseconds, phases may wrap at the bar line
+            let diff = (*phase as f64 - expected).abs();
+            prop_assert!(diff < 1e-3 || (diff - 1.).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+// Handoff of snapshots through SnapshotCell, without a Link session.
+
+use rusty_link::SnapshotCell;
+use std::thread;
+
+#[test]
+fn reader_sees_initial_value_until_published() {
+    let (mut writer, mut reader) = SnapshotCell::new(1);
+    assert_eq!(reader.latest(), 1);
+    assert!(!reader.has_new());
+
+    writer.publish(2);
+    assert!(reader.has_new());
+    assert_eq!(reader.latest(), 2);
+    assert_eq!(reader.latest(), 2);
+    assert!(!reader.has_new());
+}
+
+#[test]
+fn reader_skips_to_latest_snapshot() {
+    let (mut writer, mut reader) = SnapshotCell::new(0);
+    for value in 1..=10 {
+        writer.publish(value);
+    }
+    assert_eq!(reader.latest(), 10);
+}
+
+#[test]
+fn snapshots_are_never_torn_or_reordered() {
+    let (mut writer, mut reader) = SnapshotCell::new((0u64, 0u64));
+
+    let publisher = thread::spawn(move || {
+        for value in 1..=100_000u64 {
+            writer.publish((value, value * 2));
+        }
+    });
+
+    let mut previous = 0;
+    while previous < 100_000 {
+        let (value, double) = reader.latest();
+        assert_eq!(double, value * 2);
+        assert!(value >= previous);
+        previous = value;
+    }
+    publisher.join().unwrap();
+}
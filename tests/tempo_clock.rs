@@ -0,0 +1,75 @@
+// Scheduling order and logical time of TempoClock, without a Link session.
+
+use rusty_link::TempoClock;
+use std::sync::{Arc, Mutex};
+
+type Log = Arc<Mutex<Vec<(&'static str, f64)>>>;
+
+fn recorder() -> (Log, Log) {
+    let events = Log::default();
+    (Arc::clone(&events), events)
+}
+
+#[test]
+fn routines_run_in_beat_order() {
+    let (events, log) = recorder();
+    let mut clock = TempoClock::new(4.);
+
+    let e = Arc::clone(&events);
+    clock.sched_abs(2., move |beat| {
+        e.lock().unwrap().push(("b", beat));
+        None
+    });
+    let e = Arc::clone(&events);
+    clock.sched_abs(1., move |beat| {
+        e.lock().unwrap().push(("a", beat));
+        None
+    });
+    let e = Arc::clone(&events);
+    clock.sched_abs(2., move |beat| {
+        e.lock().unwrap().push(("c", beat));
+        None
+    });
+
+    assert_eq!(clock.advance_to(1.5), 1);
+    assert_eq!(clock.advance_to(2.), 2);
+    assert!(clock.is_empty());
+    assert_eq!(*log.lock().unwrap(), [("a", 1.), ("b", 2.), ("c", 2.)]);
+}
+
+#[test]
+fn rescheduling_uses_logical_time() {
+    let (events, log) = recorder();
+    let mut clock = TempoClock::new(4.);
+
+    let mut remaining = 3;
+    clock.sched(0.5, move |beat| {
+        events.lock().unwrap().push(("tick", beat));
+        remaining -= 1;
+        (remaining > 0).then_some(0.25)
+    });
+
+    // Advancing late still runs every tick at its logical beat
+    assert_eq!(clock.advance_to(10.), 3);
+    assert_eq!(
+        *log.lock().unwrap(),
+        [("tick", 0.5), ("tick", 0.75), ("tick", 1.)]
+    );
+    assert_eq!(clock.beats(), 10.);
+}
+
+#[test]
+fn play_starts_on_the_next_multiple_of_quant() {
+    let (events, log) = recorder();
+    let mut clock = TempoClock::new(4.);
+    clock.advance_to(5.);
+
+    clock.play(4., move |beat| {
+        events.lock().unwrap().push(("bar", beat));
+        None
+    });
+
+    assert_eq!(clock.advance_to(7.9), 0);
+    assert_eq!(clock.advance_to(8.), 1);
+    assert_eq!(*log.lock().unwrap(), [("bar", 8.)]);
+}
@@ -0,0 +1,28 @@
+// Names of the threads spawned by the crate.
+
+use rusty_link::ThreadConfig;
+
+/// Roles of all threads spawned by the crate.
+const ROLES: [&str; 12] = [
+    "callbacks",
+    "midi-clock",
+    "msc-cues",
+    "network",
+    "peers",
+    "recorder",
+    "relay-client",
+    "relay-conn",
+    "relay-out",
+    "relay-server",
+    "shutdown",
+    "watchdog",
+];
+
+#[test]
+fn default_names_are_not_truncated_on_linux() {
+    let config = ThreadConfig::default();
+    for role in ROLES {
+        let name = config.thread_name(role);
+        assert!(name.len() <= 15, "{} is longer than 15 bytes", name);
+    }
+}
@@ -0,0 +1,11 @@
+use rusty_link::link_handlers;
+
+struct App;
+
+#[link_handlers]
+impl App {
+    #[on_peers]
+    fn peers_changed(&mut self) {}
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use rusty_link::link_handlers;
+
+struct App;
+
+#[link_handlers]
+impl App {
+    #[on_tempo]
+    fn tempo_changed(&mut self, _bpm: f64) {}
+
+    #[on_tempo]
+    fn tempo_changed_again(&mut self, _bpm: f64) {}
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use rusty_link::link_handlers;
+
+struct App;
+
+#[link_handlers]
+impl App {
+    #[on_start_stop]
+    fn start_stop_changed(&self, _is_playing: bool) {}
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use rusty_link::link_handlers;
+
+struct App;
+
+#[link_handlers]
+impl App {
+    #[on_tempo]
+    #[on_peers]
+    fn changed(&mut self, _value: f64) {}
+}
+
+fn main() {}